@@ -13,6 +13,9 @@ use core::{
 };
 use num_traits::Signed;
 
+#[cfg(feature = "serde")]
+mod serde;
+
 #[doc(hidden)]
 /// Used internally by the [num!] macro which should be used instead.
 pub use agb_macros::num as num_inner;
@@ -378,6 +381,39 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> Num<I, N> {
         Self::from_raw(I::from_as_i32((input * (1 << N) as f64) as i32))
     }
 
+    /// Transforms the fixed point number into an f32, for example to log it somewhere which
+    /// expects floats.
+    ///
+    /// This is lossy for large numbers, because an f32 only has 24 bits of precision. Any
+    /// number whose [raw representation](Num::to_raw()) needs more bits than that is rounded,
+    /// so a `Num<i32, 8>` starts losing its fractional part above 65536. Use
+    /// [`to_f64`](Num::to_f64()) if you need every number to be converted exactly.
+    /// ```rust
+    /// # use agb_fixnum::*;
+    /// let n: Num<i32, 8> = num!(-5.25);
+    /// assert_eq!(n.to_f32(), -5.25);
+    /// ```
+    pub fn to_f32(self) -> f32
+    where
+        I: Into<f64>,
+    {
+        self.to_f64() as f32
+    }
+
+    /// Transforms the fixed point number into an f64. This is exact, since every supported
+    /// integer fits in the 53 bits of precision an f64 has.
+    /// ```rust
+    /// # use agb_fixnum::*;
+    /// let n: Num<u16, 4> = num!(3.125);
+    /// assert_eq!(n.to_f64(), 3.125);
+    /// ```
+    pub fn to_f64(self) -> f64
+    where
+        I: Into<f64>,
+    {
+        self.0.into() / (1u64 << N) as f64
+    }
+
     /// Truncates the fixed point number returning the integral part
     /// ```rust
     /// # use agb_fixnum::*;
@@ -415,6 +451,25 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> Num<I, N> {
         }
     }
 
+    #[must_use]
+    /// Performs the equivalent to the integer div_euclid, giving the whole number
+    /// of times `rhs` goes into `self` such that
+    /// `self == self.div_euclid(rhs) * rhs + self.rem_euclid(rhs)`.
+    /// ```rust
+    /// # use agb_fixnum::*;
+    /// let n: Num<i32, 8> = num!(5.5);
+    /// let r: Num<i32, 8> = num!(2.);
+    /// assert_eq!(n.div_euclid(r), 2.into());
+    ///
+    /// let n: Num<i32, 8> = num!(-0.25);
+    /// let r: Num<i32, 8> = num!(1.);
+    /// assert_eq!(n.div_euclid(r), (-1).into());
+    /// ```
+    pub fn div_euclid(self, rhs: Self) -> Self {
+        // the difference is an exact multiple of rhs, so this division has no remainder
+        Self::new((self.0 - self.rem_euclid(rhs).0) / rhs.0)
+    }
+
     /// Performs rounding towards negative infinity
     /// ```rust
     /// # use agb_fixnum::*;
@@ -437,6 +492,67 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> Num<I, N> {
         self.0 & ((I::one() << N) - I::one())
     }
 
+    #[must_use]
+    /// Returns the fractional component of a number as a fixed point number. Like
+    /// [`frac`](Num::frac()), this is the part above the [`floor`](Num::floor()), so it is
+    /// never negative and `Num::new(n.floor()) + n.fract() == n`.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let n: Num<i32, 8> = num!(5.25);
+    /// assert_eq!(n.fract(), num!(0.25));
+    /// let n: Num<i32, 8> = num!(-5.25);
+    /// assert_eq!(n.fract(), num!(0.75));
+    /// ```
+    pub fn fract(self) -> Self {
+        Self(self.frac())
+    }
+
+    /// Performs rounding towards positive infinity
+    /// ```rust
+    /// # use agb_fixnum::*;
+    /// let n: Num<i32, 8> = num!(5.25);
+    /// assert_eq!(n.ceil(), 6);
+    /// let n: Num<i32, 8> = num!(-5.25);
+    /// assert_eq!(n.ceil(), -5);
+    /// ```
+    pub fn ceil(self) -> I {
+        if self.frac() == I::zero() {
+            self.floor()
+        } else {
+            self.floor() + I::one()
+        }
+    }
+
+    /// Rounds to the nearest integer, with halves rounded away from zero
+    /// ```rust
+    /// # use agb_fixnum::*;
+    /// let n: Num<i32, 8> = num!(5.5);
+    /// assert_eq!(n.round(), 6);
+    /// let n: Num<i32, 8> = num!(-5.5);
+    /// assert_eq!(n.round(), -6);
+    /// let n: Num<i32, 8> = num!(-5.25);
+    /// assert_eq!(n.round(), -5);
+    /// ```
+    pub fn round(self) -> I {
+        let frac = self.frac();
+        if frac == I::zero() {
+            return self.floor();
+        }
+
+        let half = I::one() << (N - 1);
+        let rounds_up = if self.0 < I::zero() {
+            frac > half
+        } else {
+            frac >= half
+        };
+
+        if rounds_up {
+            self.floor() + I::one()
+        } else {
+            self.floor()
+        }
+    }
+
     /// Creates an integer represented by a fixed point number
     /// ```
     /// # use agb_fixnum::*;
@@ -504,6 +620,26 @@ impl<I: FixedWidthSignedInteger, const N: usize> Num<I, N> {
         Num(self.0.abs())
     }
 
+    #[must_use]
+    /// Linearly interpolates between `self` and `other`, returning `self` when
+    /// `t` is 0 and exactly `other` when `t` is 1. Values of `t` outside of
+    /// that range extrapolate rather than saturating, so clamp `t` first if you
+    /// need the result to stay between the two. The intermediate
+    /// multiplication is done at double width, so this only overflows if the
+    /// difference between `self` and `other` doesn't fit in the number.
+    ///
+    /// To clamp a number between two bounds, use [`Ord::clamp`].
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let a: Num<i32, 8> = num!(2.);
+    /// let b: Num<i32, 8> = num!(-4.);
+    /// assert_eq!(a.lerp(b, num!(0.25)), num!(0.5));
+    /// assert_eq!(a.lerp(b, num!(1.5)), num!(-7.));
+    /// ```
+    pub fn lerp(self, other: Self, t: Self) -> Self {
+        self + (other - self) * t
+    }
+
     /// Calculates the cosine of a fixed point number with the domain of [0, 1].
     /// Uses a [fifth order polynomial](https://github.com/tarcieri/micromath/blob/24584465b48ff4e87cffb709c7848664db896b4f/src/float/cos.rs#L226).
     /// ```
@@ -649,6 +785,7 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> Debug for Num<I, N> {
 
 /// A vector of two points: (x, y) represented by integers or fixed point numbers
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct Vector2D<T: Number> {
     /// The x coordinate
     pub x: T,
@@ -800,6 +937,24 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> Vector2D<Num<I, N>> {
     }
 }
 
+impl<I: FixedWidthSignedInteger, const N: usize> Vector2D<Num<I, N>> {
+    #[must_use]
+    /// Linearly interpolates each component between `self` and `other`, see
+    /// [Num::lerp]
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let v1: Vector2D<Num<i32, 8>> = (num!(0.), num!(10.)).into();
+    /// let v2: Vector2D<Num<i32, 8>> = (num!(4.), num!(0.)).into();
+    /// assert_eq!(v1.lerp(v2, num!(0.5)), (num!(2.), num!(5.)).into());
+    /// ```
+    pub fn lerp(self, other: Self, t: Num<I, N>) -> Self {
+        Vector2D {
+            x: self.x.lerp(other.x, t),
+            y: self.y.lerp(other.y, t),
+        }
+    }
+}
+
 impl<const N: usize> Vector2D<Num<i32, N>> {
     #[must_use]
     /// Calculates the magnitude by square root
@@ -851,6 +1006,70 @@ impl<const N: usize> Vector2D<Num<i32, N>> {
     pub fn fast_normalise(self) -> Self {
         self / self.fast_magnitude()
     }
+
+    #[must_use]
+    /// Calculates the square of the distance between two points. This avoids the square root in
+    /// [Vector2D::distance], so prefer it for radius checks by comparing against the radius
+    /// squared. The intermediate calculation is done in 64 bits and the result saturates at the
+    /// largest representable value rather than overflowing once the points are far apart.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let a: Vector2D<Num<i32, 8>> = (num!(1.), num!(1.)).into();
+    /// let b: Vector2D<Num<i32, 8>> = (num!(4.), num!(5.)).into();
+    /// assert_eq!(a.distance_squared(b), 25.into());
+    ///
+    /// let radius: Num<i32, 8> = 6.into();
+    /// assert!(a.distance_squared(b) < radius * radius);
+    /// ```
+    pub fn distance_squared(self, other: Self) -> Num<i32, N> {
+        let squared = self.raw_distance_squared(other) >> N;
+        Num::from_raw(squared.try_into().unwrap_or(i32::MAX))
+    }
+
+    #[must_use]
+    /// Calculates the distance between two points by square root. Unlike
+    /// `(self - other).magnitude()` this doesn't overflow for points which are far apart and
+    /// doesn't require `N` to be even.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let a: Vector2D<Num<i32, 8>> = (num!(1.), num!(1.)).into();
+    /// let b: Vector2D<Num<i32, 8>> = (num!(4.), num!(5.)).into();
+    /// assert_eq!(a.distance(b), 5.into());
+    /// ```
+    pub fn distance(self, other: Self) -> Num<i32, N> {
+        let distance = integer_sqrt(self.raw_distance_squared(other));
+        Num::from_raw(distance.try_into().unwrap_or(i32::MAX))
+    }
+
+    fn raw_distance_squared(self, other: Self) -> u64 {
+        let dx = u64::from(self.x.to_raw().abs_diff(other.x.to_raw()));
+        let dy = u64::from(self.y.to_raw().abs_diff(other.y.to_raw()));
+
+        (dx * dx).saturating_add(dy * dy)
+    }
+}
+
+/// The square root rounded down, calculated a digit at a time in the same way as [Num::sqrt]
+fn integer_sqrt(n: u64) -> u64 {
+    let mut d = 1 << 62;
+    let mut x = n;
+    let mut c = 0;
+
+    while d > n {
+        d >>= 2;
+    }
+
+    while d != 0 {
+        if x >= c + d {
+            x -= c + d;
+            c = (c >> 1) + d;
+        } else {
+            c >>= 1;
+        }
+        d >>= 2;
+    }
+
+    c
 }
 
 impl<T: Number, P: Number + Into<T>> From<(P, P)> for Vector2D<T> {
@@ -897,6 +1116,7 @@ impl<I: FixedWidthUnsignedInteger, const N: usize> From<Vector2D<I>> for Vector2
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// A rectangle with a position in 2d space and a 2d size
 pub struct Rect<T: Number> {
     /// The position of the rectangle
@@ -919,6 +1139,8 @@ impl<T: Number> Rect<T> {
     }
 
     /// Returns true if the rectangle contains the point given, note that the boundary counts as containing the rectangle.
+    /// So the rectangle contains points from `position` to `position + size` inclusive, unlike
+    /// [`touches`](Rect::touches) where rectangles which only share an edge don't touch.
     /// ```
     /// # use agb_fixnum::*;
     /// let r = Rect::new(Vector2D::new(1,1), Vector2D::new(3,3));
@@ -939,7 +1161,47 @@ impl<T: Number> Rect<T> {
             && point.y <= self.position.y + self.size.y
     }
 
-    /// Returns true if the other rectangle touches or overlaps the first.
+    /// Returns true if the other rectangle is entirely within this one. Like
+    /// [`contains_point`](Rect::contains_point), the boundary counts as inside, so a rectangle
+    /// contains itself and any rectangle which lines up with its edges.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let r = Rect::new(Vector2D::new(1,1), Vector2D::new(3,3));
+    ///
+    /// assert!(r.contains_rect(&r));
+    /// assert!(r.contains_rect(&Rect::new(Vector2D::new(2,2), Vector2D::new(1,1))));
+    /// assert!(r.contains_rect(&Rect::new(Vector2D::new(2,1), Vector2D::new(2,3))));
+    ///
+    /// // overlapping but not contained
+    /// assert!(!r.contains_rect(&Rect::new(Vector2D::new(2,2), Vector2D::new(3,3))));
+    /// ```
+    pub fn contains_rect(&self, other: &Rect<T>) -> bool {
+        self.contains_point(other.position) && self.contains_point(other.position + other.size)
+    }
+
+    #[must_use]
+    /// Returns the point in the rectangle which is closest to the given one. Each coordinate is
+    /// clamped separately, and the edges count as inside the rectangle like they do for
+    /// [`contains_point`](Rect::contains_point).
+    ///
+    /// Useful for keeping a camera within the bounds of the level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the rectangle is negative.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let r = Rect::new(Vector2D::new(1,1), Vector2D::new(3,3));
+    ///
+    /// assert_eq!(r.clamp_point(Vector2D::new(2,3)), Vector2D::new(2,3));
+    /// assert_eq!(r.clamp_point(Vector2D::new(0,10)), Vector2D::new(1,4));
+    /// ```
+    pub fn clamp_point(&self, point: Vector2D<T>) -> Vector2D<T> {
+        point.clamp(self.position, self.position + self.size)
+    }
+
+    /// Returns true if the other rectangle touches or overlaps the first. Rectangles which only
+    /// share an edge or a corner don't count as touching.
     /// ```
     /// # use agb_fixnum::*;
     /// let r = Rect::new(Vector2D::new(1,1), Vector2D::new(3,3));
@@ -1085,6 +1347,26 @@ impl<T: Number> Vector2D<T> {
         (self.x, self.y)
     }
 
+    #[must_use]
+    /// Restricts each coordinate of the vector to be between the corresponding coordinates of
+    /// `min` and `max` inclusive. See [`Rect::clamp_point`] to keep a point within a rectangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate of `min` is greater than the same coordinate of `max`.
+    /// ```
+    /// # use agb_fixnum::*;
+    /// let v = Vector2D::new(-5, 3);
+    ///
+    /// assert_eq!(v.clamp(Vector2D::new(0, 0), Vector2D::new(10, 2)), Vector2D::new(0, 2));
+    /// ```
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Self {
+            x: self.x.clamp(min.x, max.x),
+            y: self.y.clamp(min.y, max.y),
+        }
+    }
+
     #[must_use]
     /// Calculates the hadamard product of two vectors
     /// ```
@@ -1447,6 +1729,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn euclidean_division_with_negative_numbers() {
+        fn check(x: Num<i32, 8>, y: i32, quotient: i32, remainder: Num<i32, 8>) {
+            assert_eq!(
+                x.div_euclid(y.into()),
+                quotient.into(),
+                "{x} div_euclid {y}"
+            );
+            assert_eq!(x.rem_euclid(y.into()), remainder, "{x} rem_euclid {y}");
+        }
+
+        check(num!(-0.25), 1, -1, num!(0.75));
+        check(num!(7.5), -2, -3, num!(1.5));
+        check(num!(-7.5), -2, 4, num!(0.5));
+        check(num!(-8.), 2, -4, 0.into());
+
+        let one: Num<i32, 8> = 1.into();
+        let third = one / 3;
+
+        for i in -20..20 {
+            for j in (-20..20).filter(|&j| j != 0) {
+                let x: Num<i32, 8> = third * i;
+                let y: Num<i32, 8> = Num::new(j) / 4;
+
+                assert_eq!(x.div_euclid(y) * y + x.rem_euclid(y), x);
+                assert_eq!(x.div_euclid(y).frac(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_positive_and_negative_numbers() {
+        fn check(x: Num<i32, 8>, floor: i32, ceil: i32, round: i32) {
+            assert_eq!(x.floor(), floor, "{x} floor");
+            assert_eq!(x.ceil(), ceil, "{x} ceil");
+            assert_eq!(x.round(), round, "{x} round");
+        }
+
+        check(num!(2.25), 2, 3, 2);
+        check(num!(2.5), 2, 3, 3);
+        check(num!(2.75), 2, 3, 3);
+        check(num!(-2.25), -3, -2, -2);
+        check(num!(-2.5), -3, -2, -3);
+        check(num!(-2.75), -3, -2, -3);
+        check(num!(0.5), 0, 1, 1);
+        check(num!(-0.5), -1, 0, -1);
+        check(3.into(), 3, 3, 3);
+        check((-3).into(), -3, -3, -3);
+
+        let unsigned: Num<u8, 4> = num!(1.5);
+        assert_eq!((unsigned.ceil(), unsigned.round()), (2, 2));
+    }
+
+    #[test]
+    fn floor_and_fract_add_up_to_the_number() {
+        for raw in -1000..1000 {
+            let x: Num<i32, 8> = Num::from_raw(raw);
+
+            assert!(x.fract() >= 0.into() && x.fract() < 1.into(), "{x}");
+            assert_eq!(Num::new(x.floor()) + x.fract(), x);
+        }
+    }
+
+    #[test]
+    fn converting_to_floats_and_back_gives_the_same_number() {
+        fn round_trip<I: FixedWidthUnsignedInteger + Into<f64>, const N: usize>(
+            raws: impl IntoIterator<Item = I>,
+        ) {
+            for raw in raws {
+                let x: Num<I, N> = Num::from_raw(raw);
+
+                assert_eq!(Num::<I, N>::from_f64(x.to_f64()), x);
+                assert_eq!(Num::<I, N>::from_f32(x.to_f32()), x);
+            }
+        }
+
+        round_trip::<i32, 8>(-1000..1000);
+        round_trip::<i32, 8>([i32::MIN / 256, i32::MAX / 256]);
+        round_trip::<i32, 20>(-1000..1000);
+        round_trip::<i16, 4>(i16::MIN..=i16::MAX);
+        round_trip::<u16, 12>(0..=u16::MAX);
+        round_trip::<u8, 4>(0..=u8::MAX);
+        round_trip::<u32, 16>((0..1000).map(|raw| raw * 4099));
+    }
+
+    #[test]
+    fn large_numbers_lose_precision_as_f32s() {
+        let x: Num<i32, 8> = Num::from_raw(i32::MAX - 1);
+
+        assert_eq!(x.to_f64(), (i32::MAX - 1) as f64 / 256.);
+
+        let round_tripped: Num<i32, 8> = Num::from_f32(x.to_f32() / 2.);
+        let error = (round_tripped - x / 2).abs();
+        assert_ne!(error, 0.into(), "should have lost some precision");
+        assert!(
+            error <= 1.into(),
+            "should still be close, but is off by {error}"
+        );
+    }
+
     #[test]
     fn test_only_frac_bits() {
         let quarter: Num<u8, 8> = num!(0.25);
@@ -1456,6 +1838,39 @@ mod tests {
         assert_eq!(neg_quarter + neg_quarter, num!(-0.5));
     }
 
+    #[test]
+    fn lerp_at_the_ends_and_middle() {
+        let a: Num<i32, 8> = num!(-3.5);
+        let b: Num<i32, 8> = num!(12.25);
+
+        assert_eq!(a.lerp(b, num!(0.)), a);
+        assert_eq!(a.lerp(b, num!(1.)), b);
+        assert_eq!(a.lerp(b, num!(0.5)), num!(4.375));
+        assert_eq!(b.lerp(a, num!(0.5)), num!(4.375));
+
+        // large values don't overflow in the multiplication
+        let a: Num<i32, 8> = Num::new(-10_000);
+        let b: Num<i32, 8> = Num::new(20_000);
+        assert_eq!(a.lerp(b, num!(1.)), b);
+        assert_eq!(a.lerp(b, num!(0.5)), Num::new(5_000));
+
+        let v1: Vector2D<Num<i32, 8>> = (num!(1.), num!(-1.)).into();
+        let v2: Vector2D<Num<i32, 8>> = (num!(3.), num!(7.)).into();
+        assert_eq!(v1.lerp(v2, num!(0.)), v1);
+        assert_eq!(v1.lerp(v2, num!(1.)), v2);
+        assert_eq!(v1.lerp(v2, num!(0.5)), (num!(2.), num!(3.)).into());
+    }
+
+    #[test]
+    fn clamp_hits_both_bounds() {
+        let min: Num<i32, 8> = num!(-1.5);
+        let max: Num<i32, 8> = num!(2.25);
+
+        assert_eq!(num!(-8.).clamp(min, max), min);
+        assert_eq!(num!(8.).clamp(min, max), max);
+        assert_eq!(num!(0.75).clamp(min, max), num!(0.75));
+    }
+
     #[test]
     fn test_vector_multiplication_and_division() {
         let a: Vector2D<i32> = (1, 2).into();
@@ -1474,6 +1889,35 @@ mod tests {
         assert!((n.magnitude() - 5).abs() < num!(0.1));
     }
 
+    #[test]
+    fn distance_of_right_triangles() {
+        let origin: Vector2D<Num<i32, 8>> = (1, -2).into();
+
+        for (x, y, hypotenuse) in [(3, 4, 5), (5, 12, 13), (8, 15, 17), (-20, 21, 29)] {
+            let point = origin + (x, y).into();
+
+            assert_eq!(origin.distance(point), hypotenuse.into());
+            assert_eq!(point.distance(origin), hypotenuse.into());
+            assert_eq!(
+                origin.distance_squared(point),
+                origin.distance(point) * origin.distance(point)
+            );
+        }
+    }
+
+    #[test]
+    fn distance_of_far_apart_points() {
+        let a: Vector2D<Num<i32, 8>> = (-3000, -4000).into();
+        let b: Vector2D<Num<i32, 8>> = (3000, 4000).into();
+
+        assert_eq!(a.distance(b), 10000.into());
+        assert_eq!(a.distance_squared(b), Num::from_raw(i32::MAX));
+
+        let a: Vector2D<Num<i32, 7>> = (num!(0.5), 0.into()).into();
+        let b: Vector2D<Num<i32, 7>> = (num!(3.5), 4.into()).into();
+        assert_eq!(a.distance(b), 5.into());
+    }
+
     #[test]
     fn test_vector_changing() {
         let v1: Vector2D<FixedNum<8>> = Vector2D::new(1.into(), 2.into());
@@ -1503,6 +1947,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rect_contains_points_on_its_edges_and_corners() {
+        let rect: Rect<i32> = Rect::new((2, 3).into(), (4, 5).into());
+
+        for inside in [(3, 4), (2, 3), (6, 8), (2, 8), (6, 3), (4, 3), (6, 5)] {
+            assert!(
+                rect.contains_point(inside.into()),
+                "{inside:?} should be inside"
+            );
+        }
+
+        for outside in [(1, 4), (7, 4), (3, 2), (3, 9), (1, 2), (7, 9), (-10, -10)] {
+            assert!(
+                !rect.contains_point(outside.into()),
+                "{outside:?} should be outside"
+            );
+        }
+    }
+
+    #[test]
+    fn rect_contains_nested_rects_but_not_overlapping_ones() {
+        let rect: Rect<Num<i32, 8>> =
+            Rect::new((num!(1.), num!(1.)).into(), (num!(4.), num!(4.)).into());
+
+        let nested = Rect::new((num!(2.), num!(2.)).into(), (num!(1.5), num!(2.5)).into());
+        assert!(rect.contains_rect(&nested));
+        assert!(!nested.contains_rect(&rect));
+        assert!(rect.contains_rect(&rect));
+
+        let overlapping = Rect::new((num!(4.), num!(4.)).into(), (num!(2.), num!(2.)).into());
+        assert!(rect.touches(overlapping));
+        assert!(!rect.contains_rect(&overlapping));
+
+        let sharing_an_edge = Rect::new((num!(5.), num!(1.)).into(), (num!(1.), num!(4.)).into());
+        assert!(!rect.touches(sharing_an_edge));
+        assert!(!rect.contains_rect(&sharing_an_edge));
+
+        let just_outside = Rect::new(
+            (num!(1.), num!(1.)).into(),
+            (num!(4.00390625), num!(4.)).into(),
+        );
+        assert!(!rect.contains_rect(&just_outside));
+    }
+
+    #[test]
+    fn rect_clamps_each_coordinate_of_a_point_separately() {
+        let rect: Rect<i32> = Rect::new((2, 3).into(), (4, 5).into());
+
+        for inside in [(3, 4), (2, 3), (6, 8), (2, 8), (6, 5)] {
+            assert_eq!(rect.clamp_point(inside.into()), inside.into());
+        }
+
+        for (outside, clamped) in [
+            ((1, 4), (2, 4)),
+            ((7, 4), (6, 4)),
+            ((3, 2), (3, 3)),
+            ((3, 9), (3, 8)),
+            ((-10, -10), (2, 3)),
+            ((10, 10), (6, 8)),
+            ((10, 0), (6, 3)),
+        ] {
+            assert_eq!(
+                rect.clamp_point(outside.into()),
+                clamped.into(),
+                "{outside:?} should clamp to {clamped:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn vector_clamp_works_with_fixed_point_numbers() {
+        let min: Vector2D<Num<i32, 8>> = (num!(0.), num!(-1.5)).into();
+        let max = (num!(2.5), num!(1.)).into();
+
+        let v: Vector2D<Num<i32, 8>> = (num!(3.25), num!(0.5)).into();
+        assert_eq!(v.clamp(min, max), (num!(2.5), num!(0.5)).into());
+
+        let v: Vector2D<Num<i32, 8>> = (num!(1.), num!(-2.)).into();
+        assert_eq!(v.clamp(min, max), (num!(1.), num!(-1.5)).into());
+    }
+
+    #[test]
+    #[should_panic]
+    fn vector_clamp_panics_if_min_is_greater_than_max() {
+        let _ = Vector2D::new(1, 1).clamp(Vector2D::new(2, 0), Vector2D::new(0, 2));
+    }
+
     #[test]
     fn test_str_radix() {
         use alloc::string::ToString;
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{FixedWidthUnsignedInteger, Num};
+
+/// Serialized as the raw backing integer so that round trips are exact and no floating point is
+/// involved
+impl<I: FixedWidthUnsignedInteger + Serialize, const N: usize> Serialize for Num<I, N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_raw().serialize(serializer)
+    }
+}
+
+impl<'de, I: FixedWidthUnsignedInteger + Deserialize<'de>, const N: usize> Deserialize<'de>
+    for Num<I, N>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        I::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate alloc;
+
+    use alloc::vec::Vec;
+
+    use crate::{num, Num, Rect, Vector2D};
+
+    #[test]
+    fn serialize_num_as_raw() {
+        let n: Num<i32, 8> = num!(1.5);
+
+        assert_eq!(serde_json::to_string(&n).unwrap(), "384");
+    }
+
+    #[test]
+    fn num_round_trips_exactly() {
+        for raw in [0, 1, -1, 384, -12345, i32::MAX, i32::MIN] {
+            let n: Num<i32, 8> = Num::from_raw(raw);
+
+            let json = serde_json::to_string(&n).unwrap();
+            let deserialized: Num<i32, 8> = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized.to_raw(), raw);
+        }
+
+        let n: Num<u8, 4> = num!(2.25);
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(serde_json::from_str::<Num<u8, 4>>(&json).unwrap(), n);
+    }
+
+    #[test]
+    fn vectors_round_trip_exactly() {
+        let positions: Vec<Vector2D<Num<i32, 8>>> = alloc::vec![
+            (num!(1.5), num!(-2.25)).into(),
+            (Num::from_raw(i32::MIN), Num::from_raw(1)).into(),
+            Vector2D::default(),
+        ];
+
+        let json = serde_json::to_string(&positions).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Vector2D<Num<i32, 8>>>>(&json).unwrap(),
+            positions
+        );
+    }
+
+    #[test]
+    fn rect_round_trips_exactly() {
+        let hitbox: Rect<Num<i32, 8>> = Rect::new(
+            (num!(10.125), num!(-3.5)).into(),
+            (num!(16.), num!(0.00390625)).into(),
+        );
+
+        let json = serde_json::to_string(&hitbox).unwrap();
+        assert_eq!(
+            json,
+            r#"{"position":{"x":2592,"y":-896},"size":{"x":4096,"y":1}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Rect<Num<i32, 8>>>(&json).unwrap(),
+            hitbox
+        );
+    }
+}
@@ -314,7 +314,10 @@ where
     ///
     /// let union: Vec<_> = a.union(&b).collect();
     /// assert_eq!(union.len(), 4);
-    /// assert_eq!(HashSet::from_iter(union), HashSet::from([&1, &2, &3, &4]));
+    /// assert_eq!(
+    ///     union.into_iter().collect::<HashSet<_>>(),
+    ///     HashSet::from([&1, &2, &3, &4])
+    /// );
     /// ```
     pub fn union<'a>(&'a self, other: &'a HashSet<K, ALLOCATOR>) -> impl Iterator<Item = &'a K> {
         let (smaller, larger) = if self.len() < other.len() {
@@ -323,7 +326,69 @@ where
             (other, self)
         };
 
-        larger.iter().chain(smaller.difference(self))
+        larger.iter().chain(smaller.difference(larger))
+    }
+
+    /// Returns `true` if `self` has no values in common with `other`. This is equivalent to
+    /// checking for an empty intersection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agb_hashmap::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let mut b = HashSet::new();
+    ///
+    /// assert_eq!(a.is_disjoint(&b), true);
+    /// b.insert(4);
+    /// assert_eq!(a.is_disjoint(&b), true);
+    /// b.insert(1);
+    /// assert_eq!(a.is_disjoint(&b), false);
+    /// ```
+    pub fn is_disjoint(&self, other: &HashSet<K, ALLOCATOR>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns `true` if every value in `self` is also in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agb_hashmap::HashSet;
+    ///
+    /// let sup = HashSet::from([1, 2, 3]);
+    /// let mut set = HashSet::new();
+    ///
+    /// assert_eq!(set.is_subset(&sup), true);
+    /// set.insert(2);
+    /// assert_eq!(set.is_subset(&sup), true);
+    /// set.insert(4);
+    /// assert_eq!(set.is_subset(&sup), false);
+    /// ```
+    pub fn is_subset(&self, other: &HashSet<K, ALLOCATOR>) -> bool {
+        self.len() <= other.len() && self.iter().all(|k| other.contains(k))
+    }
+
+    /// Returns `true` if every value in `other` is also in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agb_hashmap::HashSet;
+    ///
+    /// let sub = HashSet::from([1, 2]);
+    /// let mut set = HashSet::new();
+    ///
+    /// assert_eq!(set.is_superset(&sub), false);
+    /// set.insert(0);
+    /// set.insert(1);
+    /// assert_eq!(set.is_superset(&sub), false);
+    /// set.insert(2);
+    /// assert_eq!(set.is_superset(&sub), true);
+    /// ```
+    pub fn is_superset(&self, other: &HashSet<K, ALLOCATOR>) -> bool {
+        other.is_subset(self)
     }
 }
 
@@ -389,18 +454,18 @@ impl<'a, K, ALLOCATOR: ClonableAllocator> Iterator for Iter<'a, K, ALLOCATOR> {
 
 impl<K, ALLOCATOR: ClonableAllocator> ExactSizeIterator for Iter<'_, K, ALLOCATOR> {}
 
-impl<K> FromIterator<K> for HashSet<K>
+impl<K, ALLOCATOR: ClonableAllocator + Default> FromIterator<K> for HashSet<K, ALLOCATOR>
 where
     K: Eq + Hash,
 {
     fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
-        let mut set = HashSet::new();
+        let mut set = HashSet::new_in(ALLOCATOR::default());
         set.extend(iter);
         set
     }
 }
 
-impl<K> Extend<K> for HashSet<K>
+impl<K, ALLOCATOR: ClonableAllocator> Extend<K> for HashSet<K, ALLOCATOR>
 where
     K: Eq + Hash,
 {
@@ -411,11 +476,12 @@ where
     }
 }
 
-impl<K, ALLOCATOR: ClonableAllocator> PartialEq for HashSet<K, ALLOCATOR>
+impl<K, ALLOCATOR: ClonableAllocator, OtherAllocator: ClonableAllocator>
+    PartialEq<HashSet<K, OtherAllocator>> for HashSet<K, ALLOCATOR>
 where
     K: Eq + Hash,
 {
-    fn eq(&self, other: &Self) -> bool {
+    fn eq(&self, other: &HashSet<K, OtherAllocator>) -> bool {
         self.map == other.map
     }
 }
@@ -439,3 +505,71 @@ where
         HashSet::from_iter(value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn sorted<'a>(values: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        let mut values: Vec<_> = values.copied().collect();
+        values.sort_unstable();
+        values
+    }
+
+    #[test]
+    fn set_operations_on_sets_of_different_sizes() {
+        let a = HashSet::from([1, 3, 5, 9, 11, 16, 19, 24]);
+        let b = HashSet::from([-2, 1, 5, 9, 13, 19]);
+
+        assert_eq!(sorted(a.difference(&b)), [3, 11, 16, 24]);
+        assert_eq!(sorted(b.difference(&a)), [-2, 13]);
+        assert_eq!(sorted(a.intersection(&b)), [1, 5, 9, 19]);
+        assert_eq!(sorted(b.intersection(&a)), [1, 5, 9, 19]);
+        assert_eq!(sorted(a.symmetric_difference(&b)), [-2, 3, 11, 13, 16, 24]);
+
+        let union = [-2, 1, 3, 5, 9, 11, 13, 16, 19, 24];
+        assert_eq!(sorted(a.union(&b)), union);
+        assert_eq!(sorted(b.union(&a)), union);
+    }
+
+    #[test]
+    fn subset_and_superset() {
+        let a = HashSet::from([0, 5, 11, 7]);
+        let b = HashSet::from([0, 7, 19, 250, 11, 200]);
+
+        assert!(!a.is_subset(&b));
+        assert!(!a.is_superset(&b));
+        assert!(!b.is_subset(&a));
+        assert!(!b.is_superset(&a));
+
+        let b = HashSet::from([0, 7, 19, 250, 11, 200, 5]);
+
+        assert!(a.is_subset(&b));
+        assert!(!a.is_superset(&b));
+        assert!(!b.is_subset(&a));
+        assert!(b.is_superset(&a));
+
+        assert!(a.is_subset(&a));
+        assert!(a.is_superset(&a));
+    }
+
+    #[test]
+    fn disjoint() {
+        let mut a = HashSet::new();
+        let mut b = HashSet::new();
+
+        assert!(a.is_disjoint(&b));
+        assert!(a.insert(5));
+        assert!(b.insert(11));
+        assert!(a.insert(7));
+        assert!(b.insert(3));
+        assert!(a.is_disjoint(&b));
+        assert!(b.is_disjoint(&a));
+
+        assert!(b.insert(7));
+        assert!(!a.is_disjoint(&b));
+        assert!(!b.is_disjoint(&a));
+    }
+}
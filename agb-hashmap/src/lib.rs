@@ -28,11 +28,13 @@ extern crate alloc;
 
 pub(crate) use allocate::{Allocator, Global};
 
+use alloc::vec::Vec;
+
 #[cfg(not(feature = "allocator_api"))]
 mod allocate {
     pub trait Allocator {}
 
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, Default)]
     pub struct Global;
 
     impl Allocator for Global {}
@@ -60,11 +62,13 @@ use rustc_hash::FxHasher;
 mod hash_set;
 mod node;
 mod node_storage;
+mod raw_entry;
 
 use node::Node;
 use node_storage::NodeStorage;
 
 pub use hash_set::HashSet;
+pub use raw_entry::{RawEntryBuilderMut, RawEntryMut, RawOccupiedEntryMut, RawVacantEntryMut};
 
 // # Robin Hood Hash Tables
 //
@@ -169,10 +173,32 @@ pub use hash_set::HashSet;
 #[derive(Clone)]
 pub struct HashMap<K, V, ALLOCATOR: Allocator = Global> {
     nodes: NodeStorage<K, V, ALLOCATOR>,
+    fixed_capacity: bool,
 
     hasher: BuildHasherDefault<FxHasher>,
 }
 
+/// Statistics about the layout of a [`HashMap`], returned by [`HashMap::stats`].
+///
+/// DIB is the distance from initial bucket, how many places after the one its hash
+/// points to an element has been stored.
+#[cfg(any(test, feature = "stats"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HashMapStats {
+    /// The number of elements in the map
+    pub len: usize,
+    /// The number of elements the map can hold before it resizes
+    pub capacity: usize,
+    /// The number of buckets in the map, always a power of 2
+    pub backing_size: usize,
+    /// The fraction of buckets which contain an element
+    pub load_factor: f32,
+    /// The largest DIB of any element in the map
+    pub max_dib: usize,
+    /// The mean DIB of the elements in the map, or 0 if the map is empty
+    pub average_dib: f32,
+}
+
 /// Trait for allocators that are clonable, blanket implementation for all types that implement Allocator and Clone
 pub trait ClonableAllocator: Allocator + Clone {}
 impl<T: Allocator + Clone> ClonableAllocator for T {}
@@ -196,6 +222,13 @@ impl<K, V> HashMap<K, V> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self::with_capacity_in(capacity, Global)
     }
+
+    /// Creates an empty `HashMap` which can hold at least `capacity` elements and will never
+    /// resize. See [`with_fixed_capacity_in`](HashMap::with_fixed_capacity_in) for details.
+    #[must_use]
+    pub fn with_fixed_capacity(capacity: usize) -> Self {
+        Self::with_fixed_capacity_in(capacity, Global)
+    }
 }
 
 impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
@@ -205,6 +238,7 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
     pub fn with_size_in(size: usize, alloc: ALLOCATOR) -> Self {
         Self {
             nodes: NodeStorage::with_size_in(size, alloc),
+            fixed_capacity: false,
             hasher: BuildHasherDefault::default(),
         }
     }
@@ -220,6 +254,12 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
         self.nodes.allocator()
     }
 
+    /// Returns a reference to the map's [`BuildHasher`], which can be used to work out the
+    /// hash of a key to look it up with [`raw_entry_mut`](HashMap::raw_entry_mut()).
+    pub fn hasher(&self) -> &impl BuildHasher {
+        &self.hasher
+    }
+
     /// Creates an empty `HashMap` which can hold at least `capacity` elements before resizing. The actual
     /// internal size may be larger as it must be a power of 2
     ///
@@ -228,17 +268,49 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
     /// Panics if capacity is larger than 2^32 * .85
     #[must_use]
     pub fn with_capacity_in(capacity: usize, alloc: ALLOCATOR) -> Self {
-        for i in 0..32 {
-            let attempted_size = 1usize << i;
-            if number_before_resize(attempted_size) > capacity {
-                return Self::with_size_in(attempted_size, alloc);
-            }
-        }
+        let Some(size) = size_for_capacity(capacity) else {
+            panic!(
+                "Failed to come up with a size which satisfies capacity {}",
+                capacity
+            );
+        };
 
-        panic!(
-            "Failed to come up with a size which satisfies capacity {}",
-            capacity
-        );
+        Self::with_size_in(size, alloc)
+    }
+
+    /// Creates an empty `HashMap` which can hold at least `capacity` elements using the
+    /// specified allocator, and which will never resize.
+    ///
+    /// All the memory the map will ever use is allocated here, so nothing is allocated
+    /// or rehashed in the middle of a frame. Use [`try_insert`](HashMap::try_insert) to
+    /// add elements and handle the map being full. [`insert`](HashMap::insert), the entry
+    /// API and [`reserve`](HashMap::reserve) panic if they would need to resize the map,
+    /// and [`try_reserve`](HashMap::try_reserve) returns an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if capacity is larger than 2^32 * .85
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    ///
+    /// let mut map = HashMap::with_fixed_capacity(2);
+    /// let capacity = map.capacity();
+    ///
+    /// for i in 0..capacity {
+    ///     assert_eq!(map.try_insert(i, i), Ok(None));
+    /// }
+    ///
+    /// assert_eq!(map.try_insert(capacity, capacity), Err(capacity));
+    /// assert_eq!(map.capacity(), capacity);
+    /// ```
+    #[must_use]
+    pub fn with_fixed_capacity_in(capacity: usize, alloc: ALLOCATOR) -> Self {
+        let mut map = Self::with_capacity_in(capacity, alloc);
+        map.fixed_capacity = true;
+        map
     }
 
     /// Returns the number of elements in the map
@@ -253,6 +325,40 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
         self.nodes.capacity()
     }
 
+    /// Reports how full the map is and how far its elements are from where their hash would
+    /// put them. Lookups have to probe past every element between a key's initial bucket and
+    /// where it was stored, so a large [`max_dib`](HashMapStats::max_dib) means the keys have
+    /// a poor hash distribution.
+    ///
+    /// This scans the whole map, so it is only intended for tuning capacities and diagnosing
+    /// slow lookups, and is only available with the `stats` feature.
+    #[cfg(any(test, feature = "stats"))]
+    #[must_use]
+    pub fn stats(&self) -> HashMapStats {
+        let len = self.len();
+        let backing_size = self.nodes.backing_vec_size();
+
+        let mut max_dib = 0;
+        let mut total_dib = 0;
+        for distance in self.nodes.distances() {
+            max_dib = max_dib.max(distance);
+            total_dib += distance as usize;
+        }
+
+        HashMapStats {
+            len,
+            capacity: self.capacity(),
+            backing_size,
+            load_factor: len as f32 / backing_size as f32,
+            max_dib: max_dib as usize,
+            average_dib: if len == 0 {
+                0.0
+            } else {
+                total_dib as f32 / len as f32
+            },
+        }
+    }
+
     /// An iterator visiting all keys in an arbitrary order
     pub fn keys(&self) -> impl Iterator<Item = &'_ K> {
         self.iter().map(|(k, _)| k)
@@ -287,6 +393,33 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
         self.nodes.iter_mut().filter_map(Node::key_value_mut)
     }
 
+    /// An iterator visiting all key-value pairs in order of their keys.
+    ///
+    /// The order of [`HashMap::iter`] is unspecified. It is the same every time the same sequence
+    /// of operations is made on a map, but it depends on the order the keys were inserted and
+    /// removed in and on the capacity of the map. This collects and sorts the entries every time
+    /// it is called so it is slower than `iter`, but the order only depends on the keys which
+    /// makes it suitable for things like deterministic replays.
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    ///
+    /// let map = HashMap::from([(3, "c"), (1, "a"), (2, "b")]);
+    ///
+    /// assert_eq!(
+    ///     map.iter_sorted().collect::<Vec<_>>(),
+    ///     [(&1, &"a"), (&2, &"b"), (&3, &"c")]
+    /// );
+    /// ```
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (&'_ K, &'_ V)>
+    where
+        K: Ord,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        entries.into_iter()
+    }
+
     /// Retains only the elements specified by the predicate `f`.
     pub fn retain<F>(&mut self, f: F)
     where
@@ -301,6 +434,86 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
         self.len() == 0
     }
 
+    /// Reserves space for at least `additional` more elements to be inserted
+    /// without the map resizing. Does nothing if there is already enough space.
+    ///
+    /// Resizing reallocates and rehashes every element in the map, so if you know
+    /// roughly how many elements you are about to insert then reserving space for
+    /// them first means this only happens once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting capacity is larger than a `HashMap` can hold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("a", 1);
+    ///
+    /// map.reserve(100);
+    /// assert!(map.capacity() >= 101);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let Ok(size) = self.size_to_reserve(additional) else {
+            panic!("Capacity overflow reserving space for {additional} more elements");
+        };
+
+        if size > self.nodes.backing_vec_size() {
+            self.resize(size);
+        }
+    }
+
+    /// Tries to reserve space for at least `additional` more elements to be
+    /// inserted without the map resizing, the same as [`reserve`](HashMap::reserve).
+    ///
+    /// # Errors
+    ///
+    /// If the capacity would be too large, or if the allocator fails to allocate
+    /// the new storage, then an error is returned and the map is unchanged. Maps
+    /// created with [`with_fixed_capacity`](HashMap::with_fixed_capacity) return
+    /// [`TryReserveError::CapacityOverflow`] rather than resizing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agb_hashmap::{HashMap, TryReserveError};
+    ///
+    /// let mut map: HashMap<i32, i32> = HashMap::new();
+    ///
+    /// assert_eq!(map.try_reserve(100), Ok(()));
+    /// assert_eq!(
+    ///     map.try_reserve(usize::MAX),
+    ///     Err(TryReserveError::CapacityOverflow)
+    /// );
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let size = self.size_to_reserve(additional)?;
+
+        if size > self.nodes.backing_vec_size() {
+            if self.fixed_capacity {
+                return Err(TryReserveError::CapacityOverflow);
+            }
+
+            self.nodes = self
+                .nodes
+                .try_resized_to(size)
+                .map_err(|_| TryReserveError::AllocError)?;
+        }
+
+        Ok(())
+    }
+
+    /// The size the backing storage needs to be to hold `additional` more elements
+    fn size_to_reserve(&self, additional: usize) -> Result<usize, TryReserveError> {
+        self.len()
+            .checked_add(additional)
+            .and_then(size_for_capacity)
+            .ok_or(TryReserveError::CapacityOverflow)
+    }
+
     fn resize(&mut self, new_size: usize) {
         assert!(
             new_size >= self.nodes.backing_vec_size(),
@@ -310,6 +523,12 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
             return;
         }
 
+        assert!(
+            !self.fixed_capacity,
+            "Cannot resize a hash map with a fixed capacity of {}",
+            self.capacity()
+        );
+
         self.nodes = self.nodes.resized_to(new_size);
     }
 }
@@ -324,6 +543,17 @@ impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR>
 where
     K: Eq + Hash,
 {
+    /// Creates a `HashMap` containing the key-value pairs from `iter` using the specified
+    /// allocator. This is the equivalent of [`FromIterator`], which is only implemented for
+    /// allocators which can be created with [`Default`].
+    ///
+    /// If a key appears more than once, the last value for it is kept.
+    pub fn from_iter_in<T: IntoIterator<Item = (K, V)>>(iter: T, alloc: ALLOCATOR) -> Self {
+        let mut map = Self::new_in(alloc);
+        map.extend(iter);
+        map
+    }
+
     /// Inserts a key-value pair into the map.
     ///
     /// If the map did not have this key present, [`None`] is returned.
@@ -353,6 +583,36 @@ where
         }
     }
 
+    /// Inserts a key-value pair into the map without ever resizing it.
+    ///
+    /// Returns the same as [`insert`](HashMap::insert) if there was space, or gives the value
+    /// back as an error if the key isn't already present and the map is at its
+    /// [capacity](HashMap::capacity). This works for any map, but is mainly useful for ones
+    /// created with [`with_fixed_capacity`](HashMap::with_fixed_capacity).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(value)` if the map would have to resize to fit the new element.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, V> {
+        let hash = self.hash(&key);
+
+        if let Some(location) = self.nodes.location(&key, hash) {
+            Ok(Some(
+                // SAFETY: location is valid due to the above
+                unsafe {
+                    self.nodes
+                        .replace_at_location_unchecked(location, key, value)
+                },
+            ))
+        } else if self.nodes.capacity() <= self.len() {
+            Err(value)
+        } else {
+            self.nodes.insert_new(key, value, hash);
+
+            Ok(None)
+        }
+    }
+
     unsafe fn insert_new_and_get(&mut self, key: K, value: V, hash: HashType) -> &'_ mut V {
         if self.nodes.capacity() <= self.len() {
             self.resize(self.nodes.backing_vec_size() * 2);
@@ -485,12 +745,7 @@ where
         K: Borrow<Q>,
         Q: Hash + ?Sized,
     {
-        let result = self.hasher.hash_one(key);
-
-        // we want to allow truncation here since we're reducing 64 bits to 32
-        #[allow(clippy::cast_possible_truncation)]
-        let reduced = (result as u32) ^ ((result >> 32) as u32);
-        HashType::bit_mix(reduced)
+        HashType::from_full_hash(self.hasher.hash_one(key))
     }
 }
 
@@ -733,9 +988,151 @@ mod entries {
             unsafe { self.map.insert_new_and_get(self.key, value, self.hash) }
         }
     }
+
+    /// A view into an occupied entry in a `HashMap` found using a borrowed key. This is part of the
+    /// [`crate::EntryRef`] enum.
+    pub struct OccupiedEntryRef<'a, 'b, K: 'a, Q: ?Sized, V: 'a, ALLOCATOR: Allocator> {
+        key: &'b Q,
+        map: &'a mut HashMap<K, V, ALLOCATOR>,
+        location: usize,
+    }
+
+    impl<'a, 'b, K: 'a, Q: ?Sized, V: 'a, ALLOCATOR: ClonableAllocator>
+        OccupiedEntryRef<'a, 'b, K, Q, V, ALLOCATOR>
+    {
+        /// # Safety
+        ///
+        /// You must call this with a valid location (one where the entry is defined)
+        pub(crate) unsafe fn new(
+            key: &'b Q,
+            map: &'a mut HashMap<K, V, ALLOCATOR>,
+            location: usize,
+        ) -> Self {
+            Self { key, map, location }
+        }
+
+        /// Gets a reference to the key in the map.
+        #[must_use]
+        pub fn key(&self) -> &K {
+            // SAFETY: This can only be constructed with valid locations
+            unsafe {
+                self.map
+                    .nodes
+                    .node_at_unchecked(self.location)
+                    .key_value_ref_unchecked()
+                    .0
+            }
+        }
+
+        /// Gets a reference to the value in the entry.
+        #[must_use]
+        pub fn get(&self) -> &V {
+            // SAFETY: This can only be constructed with valid locations
+            unsafe {
+                self.map
+                    .nodes
+                    .node_at_unchecked(self.location)
+                    .value_ref_unchecked()
+            }
+        }
+
+        /// Gets a mutable reference to the value in the entry.
+        ///
+        /// If you need a reference to the `OccupiedEntryRef` which may outlive the destruction
+        /// of the `EntryRef` value, see [`into_mut`].
+        ///
+        /// [`into_mut`]: Self::into_mut
+        pub fn get_mut(&mut self) -> &mut V {
+            // SAFETY: This can only be constructed with valid locations
+            unsafe {
+                self.map
+                    .nodes
+                    .node_at_unchecked_mut(self.location)
+                    .value_mut_unchecked()
+            }
+        }
+
+        /// Converts the `OccupiedEntryRef` into a mutable reference to the value in the entry with
+        /// a lifetime bound to the map itself.
+        ///
+        /// If you need multiple references to the `OccupiedEntryRef`, see [`get_mut`].
+        ///
+        /// [`get_mut`]: Self::get_mut
+        #[must_use]
+        pub fn into_mut(self) -> &'a mut V {
+            // SAFETY: This can only be constructed with valid locations
+            unsafe {
+                self.map
+                    .nodes
+                    .node_at_unchecked_mut(self.location)
+                    .value_mut_unchecked()
+            }
+        }
+
+        /// Sets the value of the entry and returns the entry's old value.
+        pub fn insert(&mut self, value: V) -> V {
+            // SAFETY: This can only be constructed with valid locations
+            unsafe {
+                self.map
+                    .nodes
+                    .node_at_unchecked_mut(self.location)
+                    .replace_value_unchecked(value)
+            }
+        }
+
+        /// Takes the value out of the entry and returns it.
+        #[must_use]
+        pub fn remove(self) -> V {
+            self.map.nodes.remove_from_location(self.location)
+        }
+
+        pub(crate) fn borrowed_key(&self) -> &'b Q {
+            self.key
+        }
+    }
+
+    /// A view into a vacant entry in a `HashMap` found using a borrowed key. It is part of the
+    /// [`crate::EntryRef`] enum.
+    pub struct VacantEntryRef<'a, 'b, K: 'a, Q: ?Sized, V: 'a, ALLOCATOR: Allocator> {
+        key: &'b Q,
+        map: &'a mut HashMap<K, V, ALLOCATOR>,
+        hash: HashType,
+    }
+
+    impl<'a, 'b, K: 'a, Q: ?Sized, V: 'a, ALLOCATOR: ClonableAllocator>
+        VacantEntryRef<'a, 'b, K, Q, V, ALLOCATOR>
+    {
+        pub(crate) unsafe fn new(
+            key: &'b Q,
+            hash: HashType,
+            map: &'a mut HashMap<K, V, ALLOCATOR>,
+        ) -> Self {
+            Self { key, map, hash }
+        }
+
+        /// Gets a reference to the borrowed key that would be used when inserting a value
+        /// through `VacantEntryRef`
+        #[must_use]
+        pub fn key(&self) -> &'b Q {
+            self.key
+        }
+
+        /// Sets the value of the entry with an owned key created from the borrowed key, and
+        /// returns a mutable reference to the value. This is the only time the owned key is created.
+        pub fn insert(self, value: V) -> &'a mut V
+        where
+            K: Hash + Eq + From<&'b Q>,
+        {
+            // SAFETY: by construction, this doesn't already exist in the hashmap and we were given the hash and key
+            unsafe {
+                self.map
+                    .insert_new_and_get(K::from(self.key), value, self.hash)
+            }
+        }
+    }
 }
 
-pub use entries::{OccupiedEntry, VacantEntry};
+pub use entries::{OccupiedEntry, OccupiedEntryRef, VacantEntry, VacantEntryRef};
 
 /// A view into a single entry in a map, which may be vacant or occupied.
 ///
@@ -830,10 +1227,173 @@ where
     }
 }
 
+/// A view into a single entry in a map found using a borrowed key, which may be vacant or occupied.
+///
+/// This is constructed using the [`entry_ref`] method on [`HashMap`]
+///
+/// [`entry_ref`]: HashMap::entry_ref()
+pub enum EntryRef<'a, 'b, K: 'a, Q: ?Sized, V: 'a, ALLOCATOR: Allocator = Global> {
+    /// An occupied entry
+    Occupied(OccupiedEntryRef<'a, 'b, K, Q, V, ALLOCATOR>),
+    /// A vacant entry
+    Vacant(VacantEntryRef<'a, 'b, K, Q, V, ALLOCATOR>),
+}
+
+impl<'a, 'b, K, Q: ?Sized, V, ALLOCATOR: ClonableAllocator> EntryRef<'a, 'b, K, Q, V, ALLOCATOR>
+where
+    K: Hash + Eq + From<&'b Q>,
+{
+    /// Ensures a value is in the entry by inserting the given value, and returns a mutable
+    /// reference to the value in the entry.
+    pub fn or_insert(self, value: V) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(e) => e.into_mut(),
+            EntryRef::Vacant(e) => e.insert(value),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the function if empty, and
+    /// returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, f: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            EntryRef::Occupied(e) => e.into_mut(),
+            EntryRef::Vacant(e) => e.insert(f()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the function if empty, and
+    /// returns a mutable reference to the value in the entry. This method allows for key-derived
+    /// values for insertion by providing the function with a reference to the borrowed key.
+    pub fn or_insert_with_key<F>(self, f: F) -> &'a mut V
+    where
+        F: FnOnce(&Q) -> V,
+    {
+        match self {
+            EntryRef::Occupied(e) => e.into_mut(),
+            EntryRef::Vacant(e) => {
+                let value = f(e.key());
+                e.insert(value)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts
+    /// into the map.
+    #[must_use]
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            EntryRef::Occupied(mut e) => {
+                f(e.get_mut());
+                EntryRef::Occupied(e)
+            }
+            EntryRef::Vacant(e) => EntryRef::Vacant(e),
+        }
+    }
+
+    /// Ensures a value is in th entry by inserting the default value if empty. Returns a
+    /// mutable reference to the value in the entry.
+    #[must_use]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        match self {
+            EntryRef::Occupied(e) => e.into_mut(),
+            EntryRef::Vacant(e) => e.insert(Default::default()),
+        }
+    }
+
+    /// Returns a reference to the borrowed key used to find this entry.
+    #[must_use]
+    pub fn key(&self) -> &'b Q {
+        match self {
+            EntryRef::Occupied(e) => e.borrowed_key(),
+            EntryRef::Vacant(e) => e.key(),
+        }
+    }
+}
+
 impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR>
 where
     K: Hash + Eq,
 {
+    /// Gets the entry for the given borrowed key in the map for in-place manipulation. Unlike
+    /// [`entry`](HashMap::entry()), this doesn't need an owned key. The owned key is only created,
+    /// using `From<&Q>`, if a value is inserted into a vacant entry.
+    ///
+    /// The key is only hashed once, so this is faster than calling [`contains_key`](HashMap::contains_key())
+    /// followed by [`insert`](HashMap::insert()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    /// use std::string::String;
+    ///
+    /// let mut counts: HashMap<String, usize> = HashMap::new();
+    ///
+    /// for word in ["hat", "wizard", "hat"] {
+    ///     // a `String` is only allocated the first time each word is seen
+    ///     *counts.entry_ref(word).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(counts.get("hat"), Some(&2));
+    /// assert_eq!(counts.get("wizard"), Some(&1));
+    /// ```
+    pub fn entry_ref<'b, Q>(&mut self, key: &'b Q) -> EntryRef<'_, 'b, K, Q, V, ALLOCATOR>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hash(key);
+        let location = self.nodes.location(key, hash);
+
+        if let Some(location) = location {
+            EntryRef::Occupied(
+                // SAFETY: location is valid by the call to location above
+                unsafe { OccupiedEntryRef::new(key, self, location) },
+            )
+        } else {
+            EntryRef::Vacant(
+                // SAFETY: item doesn't exist yet and the hash is correct here
+                unsafe { VacantEntryRef::new(key, hash, self) },
+            )
+        }
+    }
+
+    /// Returns the value for the given borrowed key, inserting the result of `f` first if the
+    /// key isn't in the map. The key is only hashed once, and the owned key is only created
+    /// using `From<&Q>` if the value needs inserting.
+    ///
+    /// This is a shorthand for `map.entry_ref(key).or_insert_with(f)`, which is useful for caches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    /// use std::string::String;
+    ///
+    /// let mut lengths: HashMap<String, usize> = HashMap::new();
+    ///
+    /// assert_eq!(*lengths.get_or_insert_with("wizard", || 6), 6);
+    /// // already cached, so the function isn't called
+    /// assert_eq!(*lengths.get_or_insert_with("wizard", || unreachable!()), 6);
+    /// ```
+    pub fn get_or_insert_with<'b, Q, F>(&mut self, key: &'b Q, f: F) -> &mut V
+    where
+        K: Borrow<Q> + From<&'b Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce() -> V,
+    {
+        self.entry_ref(key).or_insert_with(f)
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     pub fn entry(&mut self, key: K) -> Entry<'_, K, V, ALLOCATOR> {
         let hash = self.hash(&key);
@@ -853,18 +1413,16 @@ where
     }
 }
 
-impl<K, V> FromIterator<(K, V)> for HashMap<K, V>
+impl<K, V, ALLOCATOR: ClonableAllocator + Default> FromIterator<(K, V)> for HashMap<K, V, ALLOCATOR>
 where
     K: Eq + Hash,
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut map = HashMap::new();
-        map.extend(iter);
-        map
+        Self::from_iter_in(iter, ALLOCATOR::default())
     }
 }
 
-impl<K, V> Extend<(K, V)> for HashMap<K, V>
+impl<K, V, ALLOCATOR: ClonableAllocator> Extend<(K, V)> for HashMap<K, V, ALLOCATOR>
 where
     K: Eq + Hash,
 {
@@ -875,6 +1433,17 @@ where
     }
 }
 
+impl<K, V, const N: usize> From<[(K, V); N]> for HashMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn from(value: [(K, V); N]) -> Self {
+        let mut map = HashMap::with_capacity(N);
+        map.extend(value);
+        map
+    }
+}
+
 impl<K, V, Q, ALLOCATOR: ClonableAllocator> Index<&Q> for HashMap<K, V, ALLOCATOR>
 where
     K: Eq + Hash + Borrow<Q>,
@@ -887,12 +1456,13 @@ where
     }
 }
 
-impl<K, V, ALLOCATOR: ClonableAllocator> PartialEq for HashMap<K, V, ALLOCATOR>
+impl<K, V, ALLOCATOR: ClonableAllocator, OtherAllocator: ClonableAllocator>
+    PartialEq<HashMap<K, V, OtherAllocator>> for HashMap<K, V, ALLOCATOR>
 where
     K: Eq + Hash,
     V: PartialEq,
 {
-    fn eq(&self, other: &HashMap<K, V, ALLOCATOR>) -> bool {
+    fn eq(&self, other: &HashMap<K, V, OtherAllocator>) -> bool {
         if self.len() != other.len() {
             return false;
         }
@@ -923,6 +1493,34 @@ const fn number_before_resize(capacity: usize) -> usize {
     capacity * 60 / 100
 }
 
+/// The smallest backing storage size which can hold `capacity` elements and then insert one more
+/// without needing to resize.
+fn size_for_capacity(capacity: usize) -> Option<usize> {
+    (0..32)
+        .map(|i| 1usize << i)
+        .find(|&attempted_size| number_before_resize(attempted_size) > capacity)
+}
+
+/// The error returned by [`HashMap::try_reserve`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TryReserveError {
+    /// The requested capacity is larger than a `HashMap` can hold
+    CapacityOverflow,
+    /// The allocator failed to allocate the resized storage
+    AllocError,
+}
+
+impl core::fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => {
+                write!(f, "requested capacity is larger than a hash map can hold")
+            }
+            TryReserveError::AllocError => write!(f, "failed to allocate the resized hash map"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct HashType(u32);
 
@@ -939,6 +1537,14 @@ impl HashType {
         Self(0)
     }
 
+    /// Reduces a hash from the map's hasher to the 32 bits stored in each node
+    fn from_full_hash(hash: u64) -> Self {
+        // we want to allow truncation here since we're reducing 64 bits to 32
+        #[allow(clippy::cast_possible_truncation)]
+        let reduced = (hash as u32) ^ ((hash >> 32) as u32);
+        Self::bit_mix(reduced)
+    }
+
     // 32 bit mix function from here: https://github.com/skeeto/hash-prospector
     fn bit_mix(key: u32) -> Self {
         let mut key = Wrapping(key);
@@ -967,9 +1573,12 @@ impl core::ops::Add<i32> for HashType {
 
 #[cfg(test)]
 mod test {
-    use core::{cell::RefCell, hash::Hasher};
+    use core::{
+        cell::{Cell, RefCell},
+        hash::Hasher,
+    };
 
-    use alloc::vec::Vec;
+    use alloc::{rc::Rc, string::String};
 
     use super::*;
 
@@ -986,6 +1595,108 @@ mod test {
         }
     }
 
+    #[test]
+    fn fixed_capacity_map_never_resizes() {
+        let mut map = HashMap::with_fixed_capacity(10);
+        let capacity = map.capacity();
+        let backing_size = map.nodes.backing_vec_size();
+
+        assert!(capacity >= 10);
+
+        for i in 0..capacity {
+            assert_eq!(map.try_insert(i, i), Ok(None));
+        }
+
+        assert_eq!(map.try_insert(capacity, 1234), Err(1234));
+        assert_eq!(map.try_reserve(1), Err(TryReserveError::CapacityOverflow));
+
+        // replacing an existing key still works when full
+        assert_eq!(map.try_insert(0, 50), Ok(Some(0)));
+
+        assert_eq!(map.len(), capacity);
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(map.nodes.backing_vec_size(), backing_size);
+
+        map.remove(&1);
+        assert_eq!(map.try_insert(capacity, 1234), Ok(None));
+        assert_eq!(map.get(&capacity), Some(&1234));
+    }
+
+    #[test]
+    #[should_panic(expected = "fixed capacity")]
+    fn inserting_into_a_full_fixed_capacity_map_panics() {
+        let mut map = HashMap::with_fixed_capacity(2);
+
+        for i in 0..=map.capacity() {
+            map.insert(i, i);
+        }
+    }
+
+    #[test]
+    fn reserve_means_inserting_does_not_resize() {
+        let mut map = HashMap::new();
+        map.insert(-1, -1);
+
+        map.reserve(500);
+        let capacity = map.capacity();
+        assert!(capacity >= 501);
+
+        for i in 0..500 {
+            map.insert(i, i);
+            assert_eq!(map.capacity(), capacity, "resized after inserting {i}");
+        }
+
+        // already has enough space, so this shouldn't change anything
+        map.reserve(0);
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(map.len(), 501);
+        assert_eq!(map.get(&-1), Some(&-1));
+        assert_eq!(map.get(&499), Some(&499));
+    }
+
+    #[test]
+    fn iter_sorted_does_not_depend_on_insertion_order() {
+        let forwards: HashMap<_, _> = (0..50).map(|i| (i, i * 3)).collect();
+
+        let mut backwards = HashMap::with_capacity(200);
+        for i in (0..60).rev() {
+            backwards.insert(i, i * 3);
+        }
+        for i in 50..60 {
+            backwards.remove(&i);
+        }
+
+        let expected: Vec<_> = (0..50).map(|i| (i, i * 3)).collect();
+        let sorted = |map: &HashMap<i32, i32>| -> Vec<_> {
+            map.iter_sorted().map(|(&k, &v)| (k, v)).collect()
+        };
+
+        assert_eq!(sorted(&forwards), expected);
+        assert_eq!(sorted(&backwards), expected);
+    }
+
+    #[test]
+    fn try_reserve_grows_the_map_or_leaves_it_unchanged() {
+        let mut map = HashMap::new();
+        for i in 0..10 {
+            map.insert(i, i * 2);
+        }
+
+        assert_eq!(map.try_reserve(100), Ok(()));
+        let capacity = map.capacity();
+        assert!(capacity >= 110);
+
+        assert_eq!(
+            map.try_reserve(usize::MAX - 5),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(map.capacity(), capacity);
+
+        for i in 0..10 {
+            assert_eq!(map.get(&i), Some(&(i * 2)));
+        }
+    }
+
     #[test]
     fn can_get_the_length() {
         let mut map = HashMap::new();
@@ -1464,11 +2175,259 @@ mod test {
         }
     }
 
+    struct CountedKey<'a> {
+        value: i32,
+        hashes: &'a Cell<usize>,
+        constructions: &'a Cell<usize>,
+    }
+
+    impl PartialEq for CountedKey<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
+    impl Eq for CountedKey<'_> {}
+
+    impl Hash for CountedKey<'_> {
+        fn hash<H: Hasher>(&self, hasher: &mut H) {
+            self.hashes.set(self.hashes.get() + 1);
+            hasher.write_i32(self.value);
+        }
+    }
+
+    impl<'a> From<&CountedKey<'a>> for CountedKey<'a> {
+        fn from(key: &CountedKey<'a>) -> Self {
+            key.constructions.set(key.constructions.get() + 1);
+            CountedKey { ..*key }
+        }
+    }
+
+    #[test]
+    fn entry_ref_hashes_once_and_only_creates_key_on_insert() {
+        let hashes = Cell::new(0);
+        let constructions = Cell::new(0);
+        let key = |value| CountedKey {
+            value,
+            hashes: &hashes,
+            constructions: &constructions,
+        };
+
+        let mut map = HashMap::new();
+
+        for i in 0..10 {
+            *map.entry_ref(&key(i % 5)).or_insert(0) += 1;
+        }
+
+        assert_eq!(hashes.get(), 10);
+        assert_eq!(constructions.get(), 5);
+
+        for i in 0..5 {
+            assert_eq!(map.get(&key(i)), Some(&2));
+        }
+
+        hashes.set(0);
+        let new_key = key(7);
+        if !map.contains_key(&new_key) {
+            map.insert(CountedKey::from(&new_key), 1);
+        }
+        assert_eq!(hashes.get(), 2);
+    }
+
+    #[test]
+    fn get_or_insert_with_only_creates_key_on_miss() {
+        let hashes = Cell::new(0);
+        let constructions = Cell::new(0);
+        let calls = Cell::new(0);
+        let key = |value| CountedKey {
+            value,
+            hashes: &hashes,
+            constructions: &constructions,
+        };
+
+        let mut map: HashMap<CountedKey, i32> = HashMap::new();
+
+        for i in 0..12 {
+            let value = map.get_or_insert_with(&key(i % 3), || {
+                calls.set(calls.get() + 1);
+                i * 10
+            });
+            assert_eq!(*value, (i % 3) * 10);
+        }
+
+        assert_eq!(hashes.get(), 12);
+        assert_eq!(constructions.get(), 3);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(map.len(), 3);
+
+        *map.get_or_insert_with(&key(1), || 0) += 5;
+        assert_eq!(map.get(&key(1)), Some(&15));
+    }
+
+    #[test]
+    fn entry_ref_with_borrowed_str() {
+        let mut map: HashMap<String, i32> = HashMap::new();
+        map.insert("hat".into(), 1);
+
+        match map.entry_ref("hat") {
+            EntryRef::Occupied(mut e) => {
+                assert_eq!(e.key(), "hat");
+                assert_eq!(e.insert(5), 1);
+            }
+            EntryRef::Vacant(_) => panic!("hat should be occupied"),
+        }
+
+        match map.entry_ref("wizard") {
+            EntryRef::Occupied(_) => panic!("wizard should be vacant"),
+            EntryRef::Vacant(e) => {
+                assert_eq!(e.key(), "wizard");
+                *e.insert(3) += 1;
+            }
+        }
+
+        let hat = map.entry_ref("hat").and_modify(|v| *v *= 2).or_default();
+        assert_eq!(*hat, 10);
+        *map.entry_ref("chooses").or_default() += 4;
+
+        assert_eq!(map.get("hat"), Some(&10));
+        assert_eq!(map.get("wizard"), Some(&4));
+        assert_eq!(map.get("chooses"), Some(&4));
+        assert_eq!(map.len(), 3);
+    }
+
+    #[derive(PartialEq, Eq)]
+    struct CollidingKey(i32);
+
+    impl Hash for CollidingKey {
+        fn hash<H: Hasher>(&self, hasher: &mut H) {
+            hasher.write_i32(0);
+        }
+    }
+
+    #[test]
+    fn stats_of_an_empty_map() {
+        let map: HashMap<i32, i32> = HashMap::with_size(16);
+
+        assert_eq!(
+            map.stats(),
+            HashMapStats {
+                len: 0,
+                capacity: map.capacity(),
+                backing_size: 16,
+                load_factor: 0.0,
+                max_dib: 0,
+                average_dib: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn colliding_keys_are_pushed_further_from_their_initial_bucket() {
+        let mut map = HashMap::with_size(32);
+
+        for i in 0..10 {
+            map.insert(CollidingKey(i), i);
+
+            let stats = map.stats();
+            assert_eq!(stats.len, i as usize + 1);
+            assert_eq!(stats.max_dib, i as usize);
+        }
+
+        let stats = map.stats();
+        assert_eq!(stats.backing_size, 32);
+        assert_eq!(stats.load_factor, 10.0 / 32.0);
+        // distances are 0, 1, ..., 9
+        assert_eq!(stats.average_dib, 4.5);
+
+        map.remove(&CollidingKey(0));
+        assert_eq!(map.stats().max_dib, 8);
+    }
+
+    #[derive(Clone, Default)]
+    struct CountingAllocator {
+        allocations: Rc<Cell<usize>>,
+    }
+
+    #[cfg(feature = "allocator_api")]
+    // SAFETY: every allocation is made and freed by `Global`
+    unsafe impl Allocator for CountingAllocator {
+        fn allocate(
+            &self,
+            layout: core::alloc::Layout,
+        ) -> Result<core::ptr::NonNull<[u8]>, core::alloc::AllocError> {
+            self.allocations.set(self.allocations.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: core::ptr::NonNull<u8>, layout: core::alloc::Layout) {
+            // SAFETY: the caller guarantees `ptr` was allocated by `allocate`, so by `Global`
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    #[cfg(not(feature = "allocator_api"))]
+    impl Allocator for CountingAllocator {}
+
+    #[test]
+    fn can_collect_with_a_default_allocator() {
+        let map: HashMap<_, _, CountingAllocator> = (0..20).map(|i| (i, i * 2)).collect();
+        let set: HashSet<_, CountingAllocator> = (0..20).collect();
+
+        assert_eq!(map.len(), 20);
+        assert!((0..20).all(|i| map[&i] == i * 2));
+        assert_eq!(set.len(), 20);
+        assert!((0..20).all(|i| set.contains(&i)));
+    }
+
+    #[test]
+    fn can_collect_into_a_map_with_a_custom_allocator() {
+        let allocator = CountingAllocator::default();
+
+        let mut map = HashMap::from_iter_in((0..20).map(|i| (i, i * 2)), allocator.clone());
+        map.extend([(5, 100), (20, 40)]);
+
+        assert_eq!(map.len(), 21);
+        assert_eq!(map[&5], 100);
+        assert_eq!(map[&20], 40);
+        assert!((0..21).filter(|&i| i != 5).all(|i| map[&i] == i * 2));
+
+        if cfg!(feature = "allocator_api") {
+            assert!(allocator.allocations.get() > 0);
+        }
+    }
+
+    #[test]
+    fn maps_with_different_allocators_can_be_compared() {
+        let map = HashMap::from([(1, "a"), (2, "b"), (3, "c")]);
+        let mut other =
+            HashMap::from_iter_in([(3, "c"), (2, "b"), (1, "a")], CountingAllocator::default());
+
+        assert_eq!(map, other);
+        assert_eq!(other, map);
+
+        other.insert(2, "not b");
+        assert_ne!(map, other);
+
+        other.insert(2, "b");
+        other.insert(4, "d");
+        assert_ne!(map, other);
+        assert_ne!(other, map);
+    }
+
+    #[test]
+    fn can_create_a_map_from_an_array() {
+        let map = HashMap::from([("a", 1), ("b", 2), ("a", 3)]);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 3);
+        assert_eq!(map["b"], 2);
+    }
+
     #[cfg(not(miri))]
     quickcheck::quickcheck! {
         fn test_against_btree_map(entries: Vec<(u8, u32)>) -> bool {
             let std_hashmap = alloc::collections::BTreeMap::from_iter(entries.clone());
-            let agb_hashmap = HashMap::from_iter(entries);
+            let agb_hashmap: HashMap<_, _> = HashMap::from_iter(entries);
 
             if std_hashmap.len() != agb_hashmap.len() {
                 return false;
@@ -1,5 +1,7 @@
 use core::{borrow::Borrow, mem};
 
+use alloc::collections::TryReserveError;
+
 use crate::allocate::{Allocator, Global};
 use crate::{node::Node, number_before_resize, ClonableAllocator, HashType};
 
@@ -19,7 +21,22 @@ impl<K, V, ALLOCATOR: ClonableAllocator> NodeStorage<K, V, ALLOCATOR> {
     pub(crate) fn with_size_in(capacity: usize, alloc: ALLOCATOR) -> Self {
         assert!(capacity.is_power_of_two(), "Capacity must be a power of 2");
 
-        let mut nodes = MyVec::with_capacity_in(capacity, alloc);
+        Self::from_nodes(MyVec::with_capacity_in(capacity, alloc), capacity)
+    }
+
+    pub(crate) fn try_with_size_in(
+        capacity: usize,
+        alloc: ALLOCATOR,
+    ) -> Result<Self, TryReserveError> {
+        assert!(capacity.is_power_of_two(), "Capacity must be a power of 2");
+
+        Ok(Self::from_nodes(
+            MyVec::try_with_capacity_in(capacity, alloc)?,
+            capacity,
+        ))
+    }
+
+    fn from_nodes(mut nodes: MyVec<Node<K, V>, ALLOCATOR>, capacity: usize) -> Self {
         for _ in 0..capacity {
             nodes.push(Node::new());
         }
@@ -48,6 +65,14 @@ impl<K, V, ALLOCATOR: ClonableAllocator> NodeStorage<K, V, ALLOCATOR> {
         self.number_of_items
     }
 
+    #[cfg(any(test, feature = "stats"))]
+    pub(crate) fn distances(&self) -> impl Iterator<Item = i32> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| node.has_value())
+            .map(Node::distance)
+    }
+
     pub(crate) fn insert_new(&mut self, key: K, value: V, hash: HashType) -> usize {
         debug_assert!(
             self.capacity() > self.len(),
@@ -140,6 +165,14 @@ impl<K, V, ALLOCATOR: ClonableAllocator> NodeStorage<K, V, ALLOCATOR> {
         K: Borrow<Q>,
         Q: Eq + ?Sized,
     {
+        self.location_matching(hash, |node_key| node_key.borrow() == key)
+    }
+
+    pub(crate) fn location_matching(
+        &self,
+        hash: HashType,
+        mut is_match: impl FnMut(&K) -> bool,
+    ) -> Option<usize> {
         for distance_to_initial_bucket in 0..(self.max_distance_to_initial_bucket + 1) {
             let location = (hash + distance_to_initial_bucket).fast_mod(self.backing_vec_size());
 
@@ -153,7 +186,7 @@ impl<K, V, ALLOCATOR: ClonableAllocator> NodeStorage<K, V, ALLOCATOR> {
 
             let node_key_ref = node.key_ref()?;
 
-            if node_key_ref.borrow() == key {
+            if is_match(node_key_ref) {
                 return Some(location);
             }
         }
@@ -162,8 +195,16 @@ impl<K, V, ALLOCATOR: ClonableAllocator> NodeStorage<K, V, ALLOCATOR> {
     }
 
     pub(crate) fn resized_to(&mut self, new_size: usize) -> Self {
-        let mut new_node_storage = Self::with_size_in(new_size, self.allocator().clone());
+        let new_node_storage = Self::with_size_in(new_size, self.allocator().clone());
+        self.move_into(new_node_storage)
+    }
+
+    pub(crate) fn try_resized_to(&mut self, new_size: usize) -> Result<Self, TryReserveError> {
+        let new_node_storage = Self::try_with_size_in(new_size, self.allocator().clone())?;
+        Ok(self.move_into(new_node_storage))
+    }
 
+    fn move_into(&mut self, mut new_node_storage: Self) -> Self {
         for mut node in self.nodes.drain(..) {
             if let Some((key, value, hash)) = node.take_key_value() {
                 new_node_storage.insert_new(key, value, hash);
@@ -1,6 +1,6 @@
 use core::ops::{Deref, DerefMut};
 
-use alloc::vec::Vec;
+use alloc::{collections::TryReserveError, vec::Vec};
 
 use crate::{Allocator, Global};
 
@@ -18,6 +18,15 @@ mod inner {
             Self(Vec::with_capacity(capacity), allocator)
         }
 
+        pub(crate) fn try_with_capacity_in(
+            capacity: usize,
+            allocator: A,
+        ) -> Result<Self, TryReserveError> {
+            let mut vec = Vec::new();
+            vec.try_reserve_exact(capacity)?;
+            Ok(Self(vec, allocator))
+        }
+
         pub(crate) fn allocator(&self) -> &A {
             &self.1
         }
@@ -48,6 +57,15 @@ mod inner {
         pub(crate) fn with_capacity_in(capacity: usize, allocator: A) -> Self {
             Self(Vec::with_capacity_in(capacity, allocator))
         }
+
+        pub(crate) fn try_with_capacity_in(
+            capacity: usize,
+            allocator: A,
+        ) -> Result<Self, TryReserveError> {
+            let mut vec = Vec::new_in(allocator);
+            vec.try_reserve_exact(capacity)?;
+            Ok(Self(vec))
+        }
     }
 
     impl<T, A: Allocator> Deref for MyVec<T, A> {
@@ -0,0 +1,279 @@
+use core::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{Allocator, ClonableAllocator, Global, HashMap, HashType};
+
+impl<K, V, ALLOCATOR: ClonableAllocator> HashMap<K, V, ALLOCATOR> {
+    /// Creates a builder for looking up an entry using its hash and a function to find the
+    /// matching key, rather than a key which implements [`Hash`] and [`Eq`]. This is useful for
+    /// caches where you don't want to create the key until you know it isn't already in the map,
+    /// and you can't use [`entry_ref`](HashMap::entry_ref()) because the key can't be created
+    /// from a reference.
+    ///
+    /// The hash must be calculated using [`hasher`](HashMap::hasher()), and the key which is
+    /// eventually inserted must have the same hash and match the lookup. Breaking these rules
+    /// won't cause undefined behaviour, but the map will act as if it is full of keys which were
+    /// modified while they were in it. Lookups may fail, and keys which are `==` may end up in
+    /// the map more than once.
+    ///
+    /// This is similar to the unstable `raw_entry_mut` in the standard library.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use agb_hashmap::HashMap;
+    /// use core::hash::BuildHasher;
+    ///
+    /// let mut ids: HashMap<String, usize> = HashMap::new();
+    ///
+    /// for name in ["wizard", "hat", "wizard"] {
+    ///     let next_id = ids.len();
+    ///     let hash = ids.hasher().hash_one(name);
+    ///
+    ///     // a `String` is only allocated the first time each name is seen
+    ///     ids.raw_entry_mut()
+    ///         .from_hash(hash, |key| key == name)
+    ///         .or_insert_with(|| (name.to_string(), next_id));
+    /// }
+    ///
+    /// assert_eq!(ids.get("wizard"), Some(&0));
+    /// assert_eq!(ids.get("hat"), Some(&1));
+    /// ```
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V, ALLOCATOR> {
+        RawEntryBuilderMut { map: self }
+    }
+}
+
+/// A builder for looking up an entry in a [`HashMap`] by its hash. This is created by
+/// [`HashMap::raw_entry_mut`].
+pub struct RawEntryBuilderMut<'a, K: 'a, V: 'a, ALLOCATOR: Allocator = Global> {
+    map: &'a mut HashMap<K, V, ALLOCATOR>,
+}
+
+// the names match the ones in the standard library
+#[allow(clippy::wrong_self_convention)]
+impl<'a, K, V, ALLOCATOR: ClonableAllocator> RawEntryBuilderMut<'a, K, V, ALLOCATOR> {
+    /// Looks up the entry for the given key, hashing it using the map's hasher.
+    pub fn from_key<Q>(self, key: &Q) -> RawEntryMut<'a, K, V, ALLOCATOR>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.map.hasher.hash_one(key);
+        self.from_key_hashed_nocheck(hash, key)
+    }
+
+    /// Looks up the entry for the given key, using a hash which has already been calculated.
+    /// The hash must be the one [`hasher`](HashMap::hasher()) gives for the key.
+    pub fn from_key_hashed_nocheck<Q>(self, hash: u64, key: &Q) -> RawEntryMut<'a, K, V, ALLOCATOR>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.from_hash(hash, |stored_key| stored_key.borrow() == key)
+    }
+
+    /// Looks up the entry whose key has the given hash and for which `is_match` returns `true`.
+    /// `is_match` is only called with keys which could have the given hash.
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, ALLOCATOR>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        let hash = HashType::from_full_hash(hash);
+
+        if let Some(location) = self.map.nodes.location_matching(hash, is_match) {
+            RawEntryMut::Occupied(RawOccupiedEntryMut {
+                map: self.map,
+                location,
+            })
+        } else {
+            RawEntryMut::Vacant(RawVacantEntryMut { map: self.map })
+        }
+    }
+}
+
+/// A view into a single entry in a map found using [`HashMap::raw_entry_mut`], which may be
+/// vacant or occupied.
+pub enum RawEntryMut<'a, K: 'a, V: 'a, ALLOCATOR: Allocator = Global> {
+    /// An occupied entry
+    Occupied(RawOccupiedEntryMut<'a, K, V, ALLOCATOR>),
+    /// A vacant entry
+    Vacant(RawVacantEntryMut<'a, K, V, ALLOCATOR>),
+}
+
+impl<'a, K, V, ALLOCATOR: ClonableAllocator> RawEntryMut<'a, K, V, ALLOCATOR>
+where
+    K: Hash + Eq,
+{
+    /// Ensures a value is in the entry by inserting the given key and value if it is empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert(self, key: K, value: V) -> &'a mut V {
+        match self {
+            RawEntryMut::Occupied(e) => e.into_mut(),
+            RawEntryMut::Vacant(e) => e.insert(key, value),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the key and value returned by `f` if it is
+    /// empty, and returns a mutable reference to the value in the entry. `f` is only called if
+    /// the entry is empty.
+    pub fn or_insert_with<F>(self, f: F) -> &'a mut V
+    where
+        F: FnOnce() -> (K, V),
+    {
+        match self {
+            RawEntryMut::Occupied(e) => e.into_mut(),
+            RawEntryMut::Vacant(e) => {
+                let (key, value) = f();
+                e.insert(key, value)
+            }
+        }
+    }
+}
+
+/// A view into an occupied entry in a `HashMap` found using [`HashMap::raw_entry_mut`]. This is
+/// part of the [`RawEntryMut`] enum.
+pub struct RawOccupiedEntryMut<'a, K: 'a, V: 'a, ALLOCATOR: Allocator = Global> {
+    map: &'a mut HashMap<K, V, ALLOCATOR>,
+    location: usize,
+}
+
+impl<'a, K, V, ALLOCATOR: ClonableAllocator> RawOccupiedEntryMut<'a, K, V, ALLOCATOR> {
+    /// Gets a reference to the key in the entry.
+    #[must_use]
+    pub fn key(&self) -> &K {
+        // SAFETY: This can only be constructed with valid locations
+        unsafe {
+            self.map
+                .nodes
+                .node_at_unchecked(self.location)
+                .key_value_ref_unchecked()
+                .0
+        }
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[must_use]
+    pub fn get(&self) -> &V {
+        // SAFETY: This can only be constructed with valid locations
+        unsafe {
+            self.map
+                .nodes
+                .node_at_unchecked(self.location)
+                .value_ref_unchecked()
+        }
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        // SAFETY: This can only be constructed with valid locations
+        unsafe {
+            self.map
+                .nodes
+                .node_at_unchecked_mut(self.location)
+                .value_mut_unchecked()
+        }
+    }
+
+    /// Converts the entry into a mutable reference to the value in the entry with a lifetime
+    /// bound to the map itself.
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut V {
+        // SAFETY: This can only be constructed with valid locations
+        unsafe {
+            self.map
+                .nodes
+                .node_at_unchecked_mut(self.location)
+                .value_mut_unchecked()
+        }
+    }
+
+    /// Sets the value of the entry and returns the entry's old value.
+    pub fn insert(&mut self, value: V) -> V {
+        // SAFETY: This can only be constructed with valid locations
+        unsafe {
+            self.map
+                .nodes
+                .node_at_unchecked_mut(self.location)
+                .replace_value_unchecked(value)
+        }
+    }
+
+    /// Takes the value out of the entry and returns it.
+    #[must_use]
+    pub fn remove(self) -> V {
+        self.map.nodes.remove_from_location(self.location)
+    }
+}
+
+/// A view into a vacant entry in a `HashMap` found using [`HashMap::raw_entry_mut`]. This is
+/// part of the [`RawEntryMut`] enum.
+pub struct RawVacantEntryMut<'a, K: 'a, V: 'a, ALLOCATOR: Allocator = Global> {
+    map: &'a mut HashMap<K, V, ALLOCATOR>,
+}
+
+impl<'a, K, V, ALLOCATOR: ClonableAllocator> RawVacantEntryMut<'a, K, V, ALLOCATOR>
+where
+    K: Hash + Eq,
+{
+    /// Inserts the given key and value, hashing the key using the map's hasher, and returns a
+    /// mutable reference to the value. The key must match the lookup which found this entry.
+    pub fn insert(self, key: K, value: V) -> &'a mut V {
+        let hash = self.map.hasher.hash_one(&key);
+        self.insert_hashed_nocheck(hash, key, value)
+    }
+
+    /// Inserts the given key and value using a hash which has already been calculated, and
+    /// returns a mutable reference to the value. The hash must be the one
+    /// [`hasher`](HashMap::hasher()) gives for the key, and the key must match the lookup which
+    /// found this entry.
+    pub fn insert_hashed_nocheck(self, hash: u64, key: K, value: V) -> &'a mut V {
+        // SAFETY: this entry is vacant, so the key isn't in the map as long as it matches the
+        // lookup. If it doesn't then the map has duplicate keys, which is a logic error but is
+        // still memory safe.
+        unsafe {
+            self.map
+                .insert_new_and_get(key, value, HashType::from_full_hash(hash))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+
+    #[test]
+    fn can_insert_with_a_precomputed_hash_and_get_the_value_normally() {
+        let mut map: HashMap<String, i32> = HashMap::new();
+        map.insert("hat".to_string(), 1);
+
+        let hash = map.hasher().hash_one("wizard");
+        let entry = map.raw_entry_mut().from_key_hashed_nocheck(hash, "wizard");
+        let RawEntryMut::Vacant(vacant) = entry else {
+            panic!("wizard shouldn't be in the map yet");
+        };
+        *vacant.insert_hashed_nocheck(hash, "wizard".to_string(), 2) += 1;
+
+        assert_eq!(map.get("wizard"), Some(&3));
+        assert_eq!(map.len(), 2);
+
+        match map.raw_entry_mut().from_hash(hash, |key| key == "wizard") {
+            RawEntryMut::Occupied(mut occupied) => {
+                assert_eq!(occupied.key(), "wizard");
+                assert_eq!(occupied.insert(4), 3);
+            }
+            RawEntryMut::Vacant(_) => panic!("wizard should have been inserted"),
+        }
+        assert_eq!(map["wizard"], 4);
+
+        let RawEntryMut::Occupied(hat) = map.raw_entry_mut().from_key("hat") else {
+            panic!("hat should be in the map");
+        };
+        assert_eq!(hat.remove(), 1);
+        assert_eq!(map.get("hat"), None);
+    }
+}
@@ -162,13 +162,13 @@ mod test {
 
         assert_eq!(
             map,
-            HashMap::from_iter([("three".to_string(), 3), ("seven".to_string(), 7)])
+            HashMap::from([("three".to_string(), 3), ("seven".to_string(), 7)])
         );
     }
 
     #[test]
     fn serialize_map() {
-        let map = HashMap::from_iter([("three".to_string(), 3), ("seven".to_string(), 7)]);
+        let map = HashMap::from([("three".to_string(), 3), ("seven".to_string(), 7)]);
 
         let json = serde_json::to_string(&map).unwrap();
 
@@ -182,12 +182,12 @@ mod test {
         let json = "[1, 2, 5, 8, 9, 3, 4]";
         let set = serde_json::from_str::<HashSet<i32>>(json).unwrap();
 
-        assert_eq!(set, HashSet::from_iter([1, 2, 3, 4, 5, 8, 9]));
+        assert_eq!(set, HashSet::from([1, 2, 3, 4, 5, 8, 9]));
     }
 
     #[test]
     fn serialize_hashset() {
-        let set = HashSet::from_iter([1, 2, 3, 5, 8, 9, 10]);
+        let set = HashSet::from([1, 2, 3, 5, 8, 9, 10]);
         let serialized = serde_json::to_string(&set).unwrap();
 
         let mut deserialized = serde_json::from_str::<Vec<i32>>(&serialized).unwrap();
@@ -1,23 +1,32 @@
 use std::path::Path;
 
-use asefile::{AsepriteFile, Tag};
+use asefile::{AsepriteFile, Slice, Tag};
 use image::DynamicImage;
 
-pub fn generate_from_file(filename: &Path) -> (Vec<DynamicImage>, Vec<Tag>) {
+pub struct Frame {
+    pub image: DynamicImage,
+    /// How long the frame should be shown for in milliseconds
+    pub duration: u16,
+}
+
+pub fn generate_from_file(filename: &Path) -> (Vec<Frame>, Vec<Tag>, Vec<Slice>) {
     let ase = AsepriteFile::read_file(filename).expect("Aseprite file should exist");
 
-    let mut images = Vec::new();
+    let mut frames = Vec::new();
     let mut tags = Vec::new();
 
     for frame in 0..ase.num_frames() {
-        let image = ase.frame(frame).image();
+        let frame = ase.frame(frame);
 
-        images.push(DynamicImage::ImageRgba8(image))
+        frames.push(Frame {
+            image: DynamicImage::ImageRgba8(frame.image()),
+            duration: frame.duration().try_into().unwrap_or(u16::MAX),
+        })
     }
 
     for tag in 0..ase.num_tags() {
         tags.push(ase.tag(tag).clone())
     }
 
-    (images, tags)
+    (frames, tags, ase.slices().to_vec())
 }
@@ -12,4 +12,5 @@ pub(crate) trait Image {
     fn filename(&self) -> String;
     fn colours(&self) -> Colours;
     fn deduplicate(&self) -> bool;
+    fn property_map(&self) -> Option<String>;
 }
@@ -37,6 +37,7 @@ struct BackgroundGfxOption {
     file_name: String,
     colours: Colours,
     deduplicate: bool,
+    property_map: Option<String>,
 }
 
 impl config::Image for BackgroundGfxOption {
@@ -53,6 +54,12 @@ impl config::Image for BackgroundGfxOption {
     fn deduplicate(&self) -> bool {
         self.deduplicate
     }
+
+    fn property_map(&self) -> Option<String> {
+        self.property_map
+            .as_ref()
+            .map(|property_map| property_map.replace(OUT_DIR_TOKEN, &get_out_dir(property_map)))
+    }
 }
 
 impl Parse for BackgroundGfxOption {
@@ -98,11 +105,30 @@ impl Parse for BackgroundGfxOption {
 
         let file_name: syn::LitStr = input.parse()?;
 
+        let lookahead = input.lookahead1();
+
+        let property_map = if lookahead.peek(syn::Ident) {
+            let properties: syn::Ident = input.parse()?;
+
+            if properties != "properties" {
+                return Err(syn::Error::new_spanned(
+                    properties,
+                    "Must either be the literal properties followed by a file name or missing",
+                ));
+            }
+
+            let property_map: syn::LitStr = input.parse()?;
+            Some(property_map.value())
+        } else {
+            None
+        };
+
         Ok(Self {
             module_name: module_name.to_string(),
             file_name: file_name.value(),
             colours,
             deduplicate,
+            property_map,
         })
     }
 }
@@ -164,6 +190,42 @@ impl Parse for IncludeBackgroundGfxInput {
     }
 }
 
+struct AsepriteFileOption {
+    file_name: String,
+    tags: Option<Vec<LitStr>>,
+}
+
+impl Parse for AsepriteFileOption {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let file_name: syn::LitStr = input.parse()?;
+
+        let lookahead = input.lookahead1();
+
+        let tags = if lookahead.peek(syn::Ident) {
+            let tags: syn::Ident = input.parse()?;
+
+            if tags != "tags" {
+                return Err(syn::Error::new_spanned(
+                    tags,
+                    "Must either be the literal tags followed by a list of tag names or missing",
+                ));
+            }
+
+            let content;
+            syn::bracketed!(content in input);
+            let tags = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+            Some(tags.into_iter().collect())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            file_name: file_name.value(),
+            tags,
+        })
+    }
+}
+
 impl config::Config for IncludeBackgroundGfxInput {
     fn crate_prefix(&self) -> String {
         self.crate_prefix.clone()
@@ -330,7 +392,7 @@ pub fn include_colours_inner(input: TokenStream) -> TokenStream {
 pub fn include_aseprite_inner(input: TokenStream) -> TokenStream {
     let out_dir_path = get_out_dir(&input.to_string());
 
-    let parser = Punctuated::<LitStr, syn::Token![,]>::parse_terminated;
+    let parser = Punctuated::<AsepriteFileOption, syn::Token![,]>::parse_terminated;
     let parsed = match parser.parse(input) {
         Ok(e) => e,
         Err(e) => return e.to_compile_error().into(),
@@ -340,23 +402,65 @@ pub fn include_aseprite_inner(input: TokenStream) -> TokenStream {
 
     let mut optimiser = palette16::Palette16Optimiser::new(Some(transparent_colour));
     let mut images = Vec::new();
+    let mut durations = Vec::new();
     let mut tags = Vec::new();
+    let mut slices = Vec::new();
 
     let root = std::env::var("CARGO_MANIFEST_DIR").expect("Failed to get cargo manifest dir");
 
     let filenames: Vec<PathBuf> = parsed
         .iter()
-        .map(|s| s.value())
-        .map(|s| s.replace(OUT_DIR_TOKEN, &out_dir_path))
+        .map(|option| option.file_name.replace(OUT_DIR_TOKEN, &out_dir_path))
         .map(|s| Path::new(&root).join(&*s))
         .collect();
 
-    for filename in filenames.iter() {
-        let (frames, tag) = aseprite::generate_from_file(filename);
+    for (option, filename) in parsed.iter().zip(filenames.iter()) {
+        let (frames, mut tag, slice) = aseprite::generate_from_file(filename);
+
+        let included_frames = if let Some(wanted_tags) = &option.tags {
+            for wanted_tag in wanted_tags {
+                if !tag.iter().any(|tag| tag.name() == wanted_tag.value()) {
+                    return syn::Error::new_spanned(
+                        wanted_tag,
+                        format!(
+                            "Tag {} does not exist in {}",
+                            wanted_tag.value(),
+                            filename.display()
+                        ),
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            }
+
+            tag.retain(|tag| {
+                wanted_tags
+                    .iter()
+                    .any(|wanted| wanted.value() == tag.name())
+            });
+
+            frames_in_tags(
+                frames.len(),
+                tag.iter()
+                    .map(|tag| tag.from_frame() as usize..=tag.to_frame() as usize),
+            )
+        } else {
+            vec![true; frames.len()]
+        };
 
-        tags.push((tag, images.len()));
+        let frame_indices = new_frame_indices(&included_frames);
 
-        for frame in frames {
+        tags.push((tag, images.len(), frame_indices.clone()));
+        slices.push((slice, images.len(), frame_indices));
+
+        for aseprite::Frame {
+            image: frame,
+            duration,
+        } in frames
+            .into_iter()
+            .zip(included_frames)
+            .filter_map(|(frame, included)| included.then_some(frame))
+        {
             let width = frame.width();
             let height = frame.height();
             assert!(
@@ -376,6 +480,7 @@ pub fn include_aseprite_inner(input: TokenStream) -> TokenStream {
                 Some(transparent_colour),
             );
             images.push(image);
+            durations.push(duration);
         }
     }
 
@@ -415,21 +520,63 @@ pub fn include_aseprite_inner(input: TokenStream) -> TokenStream {
             }
         });
 
-    let tags = tags.iter().flat_map(|(tag, num_images)| {
+    let tags = tags.iter().flat_map(|(tag, num_images, frame_indices)| {
         tag.iter().map(move |tag| {
-            let start = tag.from_frame() as usize + num_images;
-            let end = tag.to_frame() as usize + num_images;
+            let start = frame_indices[tag.from_frame() as usize] + num_images;
+            let end = frame_indices[tag.to_frame() as usize] + num_images;
             let direction = tag.animation_direction() as usize;
 
             let name = tag.name();
             assert!(start <= end, "Tag {name} has start > end");
 
             quote! {
-                (#name, Tag::new(SPRITES, #start, #end, #direction))
+                (#name, Tag::new(SPRITES, FRAME_DURATIONS, SLICES, #start, #end, #direction))
             }
         })
     });
 
+    let slices = slices
+        .iter()
+        .flat_map(|(slices, num_images, frame_indices)| {
+            slices.iter().map(move |slice| {
+                let name = &slice.name;
+                let num_frames = frame_indices.len() - 1;
+
+                // Each key applies from its frame until the next key or the end of the file.
+                // Keys with no size mark frames where the slice is absent, and keys which only
+                // cover frames that weren't included have nothing to apply to, so both are skipped.
+                let keys = slice
+                    .keys
+                    .iter()
+                    .enumerate()
+                    .map(|(i, key)| {
+                        let next_key_frame = slice
+                            .keys
+                            .get(i + 1)
+                            .map_or(num_frames, |next| next.from_frame as usize);
+
+                        let start = frame_indices[key.from_frame as usize] + num_images;
+                        let end = frame_indices[next_key_frame] + num_images;
+
+                        (key, start, end)
+                    })
+                    .filter(|(key, start, end)| key.size.0 != 0 && key.size.1 != 0 && start != end)
+                    .map(|(key, start, end)| {
+                        let (x, y) = key.origin;
+                        let width = key.size.0 as i32;
+                        let height = key.size.1 as i32;
+
+                        quote! {
+                            SliceKey::new(#start, #end, #x, #y, #width, #height)
+                        }
+                    });
+
+                quote! {
+                    Slice::new(#name, &[#(#keys),*])
+                }
+            })
+        });
+
     let include_paths = filenames.iter().map(|s| {
         let s = s.as_os_str().to_string_lossy();
         quote! {
@@ -449,6 +596,14 @@ pub fn include_aseprite_inner(input: TokenStream) -> TokenStream {
             #(#sprites),*
         ];
 
+        static FRAME_DURATIONS: &[u16] = &[
+            #(#durations),*
+        ];
+
+        static SLICES: &[Slice] = &[
+            #(#slices),*
+        ];
+
         static TAGS: TagMap = TagMap::new(
             &[
                 #(#tags),*
@@ -472,6 +627,16 @@ fn convert_image(
     let image = Image::load_from_file(image_filename);
     let deduplicate = settings.deduplicate();
 
+    let property_map = settings.property_map().map(|property_map| {
+        let property_map_filename = parent.join(property_map);
+        let property_map_image = Image::load_from_file(&property_map_filename);
+
+        rust_generator::PropertyMap {
+            filename: property_map_filename.to_string_lossy().into_owned(),
+            tile_properties: tile_properties(&image, &property_map_image),
+        }
+    });
+
     rust_generator::generate_code(
         variable_name,
         optimisation_results,
@@ -480,9 +645,37 @@ fn convert_image(
         crate_prefix.to_owned(),
         assignment_offset,
         deduplicate,
+        property_map.as_ref(),
     )
 }
 
+/// The property of each 8x8 tile in `image`, read from the top left pixel of the same tile in
+/// `property_map` as `0xRRGGBB`. Transparent pixels have a property of 0.
+fn tile_properties(image: &Image, property_map: &Image) -> Vec<u32> {
+    if image.width != property_map.width || image.height != property_map.height {
+        panic!(
+            "Property map must be the same size as the image it describes, expected {}x{} but got {}x{}",
+            image.width, image.height, property_map.width, property_map.height
+        );
+    }
+
+    let tiles_x = image.width / 8;
+    let tiles_y = image.height / 8;
+
+    (0..tiles_y)
+        .flat_map(|y| (0..tiles_x).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let colour = property_map.colour(x * 8, y * 8);
+
+            if colour.is_transparent() {
+                0
+            } else {
+                u32::from_be_bytes([0, colour.r, colour.g, colour.b])
+            }
+        })
+        .collect()
+}
+
 fn add_to_optimiser(
     palette_optimiser: &mut palette16::Palette16Optimiser,
     image: &Image,
@@ -703,6 +896,31 @@ fn valid_sprite_size(width: u32, height: u32) -> bool {
     }
 }
 
+/// Which of a file's frames are part of at least one of the given tags
+fn frames_in_tags(
+    num_frames: usize,
+    tag_frames: impl Iterator<Item = std::ops::RangeInclusive<usize>>,
+) -> Vec<bool> {
+    let mut included = vec![false; num_frames];
+
+    for frames in tag_frames {
+        included[frames].fill(true);
+    }
+
+    included
+}
+
+/// Where each frame of a file ends up once only the included frames are kept. There is one more
+/// entry than the number of frames, so the end of a range of frames can be looked up too.
+fn new_frame_indices(included_frames: &[bool]) -> Vec<usize> {
+    iter::once(0)
+        .chain(included_frames.iter().scan(0, |count, &included| {
+            *count += usize::from(included);
+            Some(*count)
+        }))
+        .collect()
+}
+
 const OUT_DIR_TOKEN: &str = "$OUT_DIR";
 
 fn get_out_dir(raw_input: &str) -> String {
@@ -717,6 +935,39 @@ fn get_out_dir(raw_input: &str) -> String {
 mod tests {
     use asefile::AnimationDirection;
 
+    use super::*;
+
+    #[test]
+    fn tile_properties_come_from_the_top_left_of_each_tile() {
+        let image = Image::from_colour_data(vec![Colour::from_rgb(0, 0, 0, 255); 8 * 8 * 3]);
+
+        let mut property_colours = vec![Colour::from_rgb(0xff, 0xff, 0xff, 255); 8 * 8 * 3];
+        // first tile is transparent apart from its bottom right corner
+        property_colours[..64].fill(Colour::from_rgb(0, 0, 0, 0));
+        property_colours[63] = Colour::from_rgb(1, 2, 3, 255);
+        // top left of the second and third tiles
+        property_colours[64] = Colour::from_rgb(0, 0, 1, 255);
+        property_colours[128] = Colour::from_rgb(0xab, 0xcd, 0xef, 255);
+
+        let property_map = Image::from_colour_data(property_colours);
+
+        assert_eq!(tile_properties(&image, &property_map), [0, 1, 0xabcdef]);
+    }
+
+    #[test]
+    fn only_frames_in_the_included_tags_are_kept() {
+        let included = frames_in_tags(7, [0..=1, 4..=5, 5..=5].into_iter());
+        assert_eq!(included, [true, true, false, false, true, true, false]);
+
+        // the excluded frames are removed, and the included tags stay contiguous
+        assert_eq!(new_frame_indices(&included), [0, 1, 2, 2, 2, 3, 4, 4]);
+    }
+
+    #[test]
+    fn frame_indices_are_unchanged_if_every_frame_is_included() {
+        assert_eq!(new_frame_indices(&[true; 4]), [0, 1, 2, 3, 4]);
+    }
+
     #[test]
     // These directions defined in agb and have these values. This is important
     // when outputting code for agb. If more animation directions are added then
@@ -35,6 +35,12 @@ pub(crate) fn generate_palette_code(
     }
 }
 
+pub(crate) struct PropertyMap {
+    pub filename: String,
+    pub tile_properties: Vec<u32>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_code(
     output_variable_name: &str,
     results: &Palette16OptimisationResults,
@@ -43,6 +49,7 @@ pub(crate) fn generate_code(
     crate_prefix: String,
     assignment_offset: Option<usize>,
     deduplicate: bool,
+    property_map: Option<&PropertyMap>,
 ) -> TokenStream {
     let crate_prefix = format_ident!("{}", crate_prefix);
     let output_variable_name = format_ident!("{}", output_variable_name);
@@ -120,6 +127,23 @@ pub(crate) fn generate_code(
         quote! { #crate_prefix::display::tiled::TileFormat::EightBpp }
     };
 
+    let tile_data = if let Some(PropertyMap {
+        filename,
+        tile_properties,
+    }) = property_map
+    {
+        quote! {
+            const _: &[u8] = include_bytes!(#filename);
+
+            #crate_prefix::display::tile_data::TileData::new(TILE_SET, TILE_SETTINGS)
+                .with_tile_properties(&[#(#tile_properties),*])
+        }
+    } else {
+        quote! {
+            #crate_prefix::display::tile_data::TileData::new(TILE_SET, TILE_SETTINGS)
+        }
+    };
+
     quote! {
         #[allow(non_upper_case_globals)]
         pub static #output_variable_name: #crate_prefix::display::tile_data::TileData = {
@@ -145,7 +169,7 @@ pub(crate) fn generate_code(
                 #(#tile_settings),*
             ];
 
-            #crate_prefix::display::tile_data::TileData::new(TILE_SET, TILE_SETTINGS)
+            #tile_data
         };
     }
 }
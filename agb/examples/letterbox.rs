@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+use agb::display::tiled::{RegularBackgroundSize, TileFormat, TiledMap};
+use agb::display::window::Viewport;
+use agb::display::{example_logo, HEIGHT};
+use agb::fixnum::Vector2D;
+use agb::interrupt::VBlank;
+
+// the height of the cutscene once the bars have fully closed in
+const CUTSCENE_HEIGHT: i32 = 120;
+
+#[agb::entry]
+fn main(mut gba: agb::Gba) -> ! {
+    let (gfx, mut vram) = gba.display.video.tiled0();
+
+    let mut map = gfx.background(
+        agb::display::Priority::P0,
+        RegularBackgroundSize::Background32x32,
+        TileFormat::FourBpp,
+    );
+    example_logo::display_logo(&mut map, &mut vram);
+
+    // the bars show the backdrop colour
+    vram.set_background_palette_colour(0, 0, 0);
+
+    let mut windows = gba.display.window.get();
+    let vblank = VBlank::get();
+
+    let mut height = HEIGHT;
+    let mut camera: Vector2D<i32> = (0, 0).into();
+
+    loop {
+        // slowly close the bars in, then keep panning across the logo
+        if height > CUTSCENE_HEIGHT {
+            height -= 1;
+        }
+        camera.x += 1;
+
+        let viewport = Viewport::letterbox(height);
+        viewport.apply(&mut windows);
+
+        // keep the top of the logo at the top of the viewport as the bars move
+        let scroll = camera - viewport.offset();
+        map.set_scroll_pos((scroll.x as i16, scroll.y as i16));
+
+        vblank.wait_for_vblank();
+        windows.commit();
+        map.commit(&mut vram);
+    }
+}
@@ -110,7 +110,7 @@ fn all_tags(gfx: &OamManaged) {
         if count % 5 == 0 {
             image += 1;
             for (obj, tag) in objs.iter_mut() {
-                obj.set_sprite(gfx.sprite(tag.animation_sprite(image)));
+                obj.set_animation_frame(tag, image);
             }
             gfx.commit();
         }
@@ -22,6 +22,11 @@ use crate::{fixnum::Num, memory_mapped::set_bits};
 use super::tiled::BackgroundID;
 
 /// The layers, top layer will be blended into the bottom layer
+///
+/// Any of the four backgrounds, the objects and the backdrop can be enabled on either layer,
+/// and a pixel only blends if its topmost visible layer is a target of the top layer and the
+/// pixel underneath it is a target of the bottom layer. Fading to white or black only uses the
+/// top layer.
 #[derive(Clone, Copy, Debug)]
 pub enum Layer {
     /// Top layer gets blended into the bottom layer
@@ -98,6 +103,9 @@ const BLEND_ALPHAS: *mut u16 = 0x0400_0052 as *mut _;
 
 const BLEND_FADES: *mut u16 = 0x0400_0054 as *mut _;
 
+// the four backgrounds, the objects and the backdrop
+const ALL_TARGETS: u16 = 0b11_1111;
+
 impl<'gba> Blend<'gba> {
     pub(crate) fn new() -> Self {
         let blend = Self {
@@ -195,6 +203,23 @@ impl<'gba> Blend<'gba> {
         self
     }
 
+    /// Darkens everything on the screen by `amount`, where 0 leaves the screen unchanged and 1 is
+    /// entirely black. This sets every background, the objects and the backdrop as targets of the
+    /// top layer, so it replaces any other blend you had set up. Call this with an increasing
+    /// `amount` each frame to fade out a scene.
+    pub fn fade_out(&mut self, amount: Num<u8, 4>) -> &mut Self {
+        self.targets = set_bits(self.targets, ALL_TARGETS, 6, Layer::Top as usize * 8);
+        self.set_blend_mode(BlendMode::FadeToBlack)
+            .set_fade(amount.min(1.into()))
+    }
+
+    /// The reverse of [`fade_out`](Blend::fade_out), where an `amount` of 0 is entirely black
+    /// and 1 leaves the screen unchanged. Call this with an increasing `amount` each frame to
+    /// fade in a scene.
+    pub fn fade_in(&mut self, amount: Num<u8, 4>) -> &mut Self {
+        self.fade_out(Num::new(1) - amount.min(1.into()))
+    }
+
     /// Set the current blend mode
     pub fn set_blend_mode(&mut self, blend_mode: BlendMode) -> &mut Self {
         self.targets = set_bits(self.targets, blend_mode as u16, 2, 0x6);
@@ -218,3 +243,34 @@ impl Drop for Blend<'_> {
         self.reset().commit();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::fixnum::num;
+
+    #[test_case]
+    fn fade_out_targets_everything(gba: &mut crate::Gba) {
+        let mut blend = gba.display.blend.get();
+
+        blend.fade_out(num!(0.25));
+        assert_eq!(blend.targets, 0b1111_1111);
+        assert_eq!(blend.fade_weight, 4);
+
+        blend.fade_out(2.into());
+        assert_eq!(blend.fade_weight, 16);
+    }
+
+    #[test_case]
+    fn fade_in_is_the_reverse_of_fade_out(gba: &mut crate::Gba) {
+        let mut blend = gba.display.blend.get();
+
+        blend.fade_in(0.into());
+        assert_eq!(blend.fade_weight, 16);
+
+        blend.fade_in(num!(0.75));
+        assert_eq!(blend.fade_weight, 4);
+
+        blend.fade_in(1.into());
+        assert_eq!(blend.fade_weight, 0);
+    }
+}
@@ -108,6 +108,8 @@ impl Font {
             current_x_pos: 0,
             current_y_pos: 0,
             previous_character: None,
+            line_height: self.line_height,
+            max_width: None,
             font: self,
             tile_pos: tile_pos.into(),
             tiles: Default::default(),
@@ -120,6 +122,8 @@ pub struct TextRenderer<'a> {
     current_x_pos: i32,
     current_y_pos: i32,
     previous_character: Option<char>,
+    line_height: i32,
+    max_width: Option<i32>,
     font: &'a Font,
     tile_pos: Vector2D<u16>,
     tiles: HashMap<(i32, i32), DynamicTile<'a>>,
@@ -137,14 +141,16 @@ pub struct TextWriter<'a, 'b> {
 
 impl Write for TextWriter<'_, '_> {
     fn write_str(&mut self, text: &str) -> Result<(), Error> {
-        for c in text.chars() {
-            self.text_renderer.write_char(
-                c,
-                self.vram_manager,
-                self.foreground_colour,
-                self.background_colour,
-            );
-        }
+        self.text_renderer
+            .lay_out(text, |text_renderer, letter, position| {
+                text_renderer.render_letter(
+                    letter,
+                    position,
+                    self.vram_manager,
+                    self.foreground_colour,
+                    self.background_colour,
+                );
+            });
 
         Ok(())
     }
@@ -177,11 +183,29 @@ impl<'a, 'b> TextRenderer<'b> {
         }
     }
 
-    /// Renders a single character creating as many dynamic tiles as needed.
+    /// Sets the distance in pixels between the top of one line and the next.
+    /// Defaults to the line height of the font.
+    pub fn set_line_height(&mut self, line_height: i32) {
+        self.line_height = line_height;
+    }
+
+    /// Sets the width in pixels after which text wraps onto the next line,
+    /// or `None` to only start new lines on `'\n'`, which is the default.
+    ///
+    /// Text written through a [`TextWriter`] wraps before any word which
+    /// doesn't fit on the current line. Words longer than a whole line, and
+    /// characters written with [`write_char`](TextRenderer::write_char),
+    /// wrap before the first character which doesn't fit.
+    pub fn set_max_width(&mut self, max_width: Option<i32>) {
+        self.max_width = max_width;
+    }
+
+    /// Renders a single character at the given position creating as many dynamic tiles as needed.
     /// The foreground and background colour are palette indicies.
     fn render_letter(
         &mut self,
         letter: &FontLetter,
+        position: Vector2D<i32>,
         vram_manager: &mut VRamManager,
         foreground_colour: u8,
         background_colour: u8,
@@ -189,10 +213,9 @@ impl<'a, 'b> TextRenderer<'b> {
         assert!(foreground_colour < 16);
         assert!(background_colour < 16);
 
-        let x_start = (self.current_x_pos + i32::from(letter.xmin)).max(0);
-        let y_start = self.current_y_pos + self.font.ascent
-            - i32::from(letter.height)
-            - i32::from(letter.ymin);
+        let x_start = (position.x + i32::from(letter.xmin)).max(0);
+        let y_start =
+            position.y + self.font.ascent - i32::from(letter.height) - i32::from(letter.ymin);
 
         let x_tile_start = x_start / 8;
         let y_tile_start = y_start / 8;
@@ -270,26 +293,109 @@ impl<'a, 'b> TextRenderer<'b> {
         foreground_colour: u8,
         background_colour: u8,
     ) {
+        if let Some((letter, position)) = self.lay_out_char(c) {
+            self.render_letter(
+                letter,
+                position,
+                vram_manager,
+                foreground_colour,
+                background_colour,
+            );
+        }
+    }
+
+    /// Moves the cursor past each character in `text`, wrapping whole words
+    /// if there is a maximum width, and calls `render` with every letter and
+    /// the position it should be drawn at.
+    fn lay_out(
+        &mut self,
+        text: &str,
+        mut render: impl FnMut(&mut Self, &'static FontLetter, Vector2D<i32>),
+    ) {
+        for (i, c) in text.char_indices() {
+            if let Some(max_width) = self.max_width {
+                let starts_word =
+                    !c.is_whitespace() && self.previous_character.is_none_or(char::is_whitespace);
+
+                if starts_word
+                    && self.current_x_pos > 0
+                    && self.current_x_pos + self.word_width(&text[i..]) > max_width
+                {
+                    self.new_line();
+                }
+            }
+
+            if let Some((letter, position)) = self.lay_out_char(c) {
+                render(self, letter, position);
+            }
+        }
+    }
+
+    /// Moves the cursor past `c`, returning the letter to draw and where to
+    /// draw it unless it is a new line, or a space which was wrapped.
+    fn lay_out_char(&mut self, c: char) -> Option<(&'static FontLetter, Vector2D<i32>)> {
         if c == '\n' {
-            self.current_y_pos += self.font.line_height;
-            self.current_x_pos = 0;
-        } else {
-            let letter = self.font.letter(c);
+            self.new_line();
+            return None;
+        }
+
+        let letter = self.font.letter(c);
+        let advance_width = i32::from(letter.advance_width);
+        let mut kerning = self.previous_character.map_or(0, |previous_character| {
+            letter.kerning_amount(previous_character)
+        });
+
+        if let Some(max_width) = self.max_width {
+            if self.current_x_pos > 0 && self.current_x_pos + kerning + advance_width > max_width {
+                self.new_line();
+                kerning = 0;
 
-            if let Some(previous_character) = self.previous_character {
-                self.current_x_pos += letter.kerning_amount(previous_character);
+                // a space at the end of a line doesn't need to carry on to the next one
+                if c == ' ' {
+                    return None;
+                }
             }
-            self.previous_character = Some(c);
+        }
+
+        self.current_x_pos += kerning;
+        self.previous_character = Some(c);
+
+        let position = (self.current_x_pos, self.current_y_pos).into();
+        self.current_x_pos += advance_width;
+
+        Some((letter, position))
+    }
+
+    /// The width of the word at the start of `text`, including the kerning
+    /// with the character before it.
+    fn word_width(&self, text: &str) -> i32 {
+        let mut previous_character = self.previous_character;
+        let mut width = 0;
 
-            self.render_letter(letter, vram_manager, foreground_colour, background_colour);
-            self.current_x_pos += i32::from(letter.advance_width);
+        for c in text.chars().take_while(|c| !c.is_whitespace()) {
+            let letter = self.font.letter(c);
+
+            if let Some(previous_character) = previous_character {
+                width += letter.kerning_amount(previous_character);
+            }
+            width += i32::from(letter.advance_width);
+            previous_character = Some(c);
         }
+
+        width
+    }
+
+    fn new_line(&mut self) {
+        self.current_y_pos += self.line_height;
+        self.current_x_pos = 0;
+        self.previous_character = None;
     }
 
     /// Clear the text, removing the tiles from vram and resetting the cursor.
     pub fn clear(&mut self, vram_manager: &mut VRamManager) {
         self.current_x_pos = 0;
         self.current_y_pos = 0;
+        self.previous_character = None;
         let tiles = core::mem::take(&mut self.tiles);
 
         for (_, tile) in tiles.into_iter() {
@@ -302,6 +408,7 @@ impl<'a, 'b> TextRenderer<'b> {
 mod tests {
     use super::*;
     use crate::display::tiled::{TileFormat, TiledMap};
+    use alloc::vec::Vec;
     static FONT: Font = crate::include_font!("examples/font/yoster.ttf", 12);
 
     #[test_case]
@@ -360,4 +467,65 @@ mod tests {
             renderer.clear(&mut vram);
         }
     }
+
+    fn positions(renderer: &mut TextRenderer, text: &str) -> Vec<(char, Vector2D<i32>)> {
+        let mut positions = Vec::new();
+        renderer.lay_out(text, |_, letter, position| {
+            positions.push((letter.character, position));
+        });
+        positions
+    }
+
+    #[test_case]
+    fn letters_are_placed_at_their_advance_widths(_gba: &mut crate::Gba) {
+        let mut renderer = FONT.render_text((0u16, 0u16));
+
+        let mut expected = Vec::new();
+        let mut x = 0;
+        let mut previous_character = None;
+        for c in "AVAWiiimm".chars() {
+            let letter = FONT.letter(c);
+            if let Some(previous_character) = previous_character {
+                x += letter.kerning_amount(previous_character);
+            }
+            expected.push((c, (x, 0).into()));
+
+            x += i32::from(letter.advance_width);
+            previous_character = Some(c);
+        }
+
+        assert_eq!(positions(&mut renderer, "AVAWiiimm"), expected);
+        // the narrow and wide letters really do have different advances
+        assert!(FONT.letter('i').advance_width < FONT.letter('m').advance_width);
+    }
+
+    #[test_case]
+    fn words_wrap_at_the_max_width(_gba: &mut crate::Gba) {
+        let mut renderer = FONT.render_text((0u16, 0u16));
+        renderer.set_line_height(20);
+
+        let hello_width = renderer.word_width("hello");
+        renderer.set_max_width(Some(
+            hello_width + FONT.letter(' ').advance_width as i32 + 1,
+        ));
+
+        let positions = positions(&mut renderer, "hello hello\nhi");
+        let line_starts: Vec<_> = positions
+            .iter()
+            .filter(|(_, position)| position.x == 0)
+            .copied()
+            .collect();
+
+        assert_eq!(
+            line_starts,
+            [
+                ('h', (0, 0).into()),
+                ('h', (0, 20).into()),
+                ('h', (0, 40).into())
+            ]
+        );
+        assert!(positions.iter().all(|(c, position)| position.x
+            + FONT.letter(*c).advance_width as i32
+            <= hello_width + FONT.letter(' ').advance_width as i32 + 1));
+    }
 }
@@ -9,18 +9,22 @@
 //! harder to integrate into your games depending on how they are architectured.
 
 mod affine;
+mod animation;
 mod font;
 mod managed;
+mod pool;
 mod sprites;
 mod unmanaged;
 
 pub use sprites::{
-    include_aseprite, DynamicSprite, Graphics, PaletteVram, Size, Sprite, SpriteLoader, SpriteVram,
-    Tag, TagMap,
+    include_aseprite, DynamicSprite, Graphics, PaletteVram, Size, Slice, SliceKey, Sprite,
+    SpriteLoader, SpriteVram, Tag, TagMap,
 };
 
 pub use affine::AffineMatrixInstance;
+pub use animation::{AnimationController, AnimationMode};
 pub use managed::{OamManaged, Object};
+pub use pool::{ObjectPool, PooledObject};
 pub use unmanaged::{
     AffineMode, GraphicsMode, OamIterator, OamSlot, OamUnmanaged, ObjectUnmanaged,
 };
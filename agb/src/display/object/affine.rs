@@ -17,7 +17,9 @@ pub(crate) struct AffineMatrixVram(Rc<AffineMatrixData>);
 /// An affine matrix that can be used on objects.
 ///
 /// It is just in time copied to vram, so you can have as many as you like
-/// of these but you can only use up to 16 in one frame. They are reference
+/// of these but the hardware only has space for 32 in one frame, and trying
+/// to use more will panic. Objects sharing an instance only use up one of
+/// those slots. They are reference
 /// counted (Cloning is cheap) and immutable, if you want to change a matrix
 /// you must make a new one and set it
 /// on all your objects.
@@ -0,0 +1,220 @@
+use super::{OamManaged, Object, Sprite, Tag};
+
+/// Whether an [`AnimationController`] should repeat its animation or stop at the end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Restart the animation once it reaches the end.
+    Looping,
+    /// Play the animation through once and then stay on the final sprite.
+    Once,
+}
+
+/// Keeps track of which sprite of a [`Tag`] should currently be displayed.
+///
+/// Call [`update`](AnimationController::update) once per frame, and then use
+/// [`sprite`](AnimationController::sprite) to find the sprite to show. The
+/// direction of the tag set in aseprite (forward, backward or ping pong) is
+/// taken into account.
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # use agb::display::object::{AnimationController, AnimationMode, Graphics, Tag};
+/// static GRAPHICS: &Graphics = agb::include_aseprite!("examples/gfx/objects.aseprite");
+/// static EMU_WALK: &Tag = GRAPHICS.tags().get("emu-walk");
+///
+/// # fn foo(gba: &mut agb::Gba) {
+/// let oam = gba.display.object.get_managed();
+///
+/// let mut animation = AnimationController::new(EMU_WALK, 4, AnimationMode::Looping);
+/// let mut emu = oam.object_sprite(animation.sprite());
+///
+/// loop {
+///     animation.update();
+///     animation.update_object(&mut emu, &oam);
+///     # break;
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AnimationController {
+    tag: &'static Tag,
+    mode: AnimationMode,
+    frames_per_sprite: u16,
+
+    timer: u16,
+    frame: usize,
+    finished: bool,
+}
+
+impl AnimationController {
+    /// Creates a new controller for the given tag which moves on to the next
+    /// sprite every `frames_per_sprite` calls to [`update`](Self::update).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frames_per_sprite` is 0.
+    #[must_use]
+    pub fn new(tag: &'static Tag, frames_per_sprite: u16, mode: AnimationMode) -> Self {
+        assert!(
+            frames_per_sprite > 0,
+            "frames_per_sprite must be at least 1"
+        );
+
+        Self {
+            tag,
+            mode,
+            frames_per_sprite,
+
+            timer: 0,
+            frame: 0,
+            finished: false,
+        }
+    }
+
+    /// Advances the animation by one frame.
+    pub fn update(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        self.timer += 1;
+        if self.timer < self.frames_per_sprite {
+            return;
+        }
+
+        self.timer = 0;
+
+        match self.mode {
+            AnimationMode::Looping => {
+                self.frame = (self.frame + 1) % self.tag.animation_cycle_length();
+            }
+            AnimationMode::Once => {
+                if self.frame == self.tag.animation_last_frame() {
+                    self.finished = true;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+    }
+
+    /// Starts the animation again from the beginning.
+    pub fn reset(&mut self) {
+        self.timer = 0;
+        self.frame = 0;
+        self.finished = false;
+    }
+
+    /// Switches to a different animation, starting it from the beginning. Does
+    /// nothing if the tag is the one already being played.
+    pub fn set_tag(&mut self, tag: &'static Tag) {
+        if !core::ptr::eq(self.tag, tag) {
+            self.tag = tag;
+            self.reset();
+        }
+    }
+
+    /// Whether an [`AnimationMode::Once`] animation has shown its final sprite
+    /// for the full duration. Always false for looping animations.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The current position in the animation sequence, as would be passed to
+    /// [`Tag::animation_sprite`].
+    #[must_use]
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// The sprite which should currently be displayed.
+    #[must_use]
+    pub fn sprite(&self) -> &'static Sprite {
+        self.tag.animation_sprite(self.frame)
+    }
+
+    /// Sets the sprite of the object to the one which should currently be displayed.
+    pub fn update_object(&self, object: &mut Object<'_>, oam: &OamManaged<'_>) {
+        object.set_sprite(oam.sprite(self.sprite()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{display::object::Graphics, include_aseprite};
+
+    use super::*;
+
+    static TEST_SPRITES: &Graphics = include_aseprite!("examples/gfx/objects.aseprite");
+
+    static TEST_FRAMES: &[Sprite] = TEST_SPRITES.sprites().split_at(4).0;
+    static DURATIONS: &[u16] = &[100; 4];
+
+    static FORWARD: Tag = Tag::new(TEST_FRAMES, DURATIONS, &[], 0, 3, 0);
+    static PING_PONG: Tag = Tag::new(TEST_FRAMES, DURATIONS, &[], 0, 2, 2);
+    static SINGLE_PING_PONG: Tag = Tag::new(TEST_FRAMES, DURATIONS, &[], 1, 1, 2);
+
+    fn frames(animation: &mut AnimationController, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                let frame = animation.frame();
+                animation.update();
+                frame
+            })
+            .collect()
+    }
+
+    #[test_case]
+    fn looping_animation_repeats(_gba: &mut crate::Gba) {
+        let mut animation = AnimationController::new(&FORWARD, 2, AnimationMode::Looping);
+
+        assert_eq!(frames(&mut animation, 10), [0, 0, 1, 1, 2, 2, 3, 3, 0, 0]);
+        assert!(!animation.is_finished());
+    }
+
+    #[test_case]
+    fn one_shot_animation_finishes(_gba: &mut crate::Gba) {
+        let mut animation = AnimationController::new(&FORWARD, 1, AnimationMode::Once);
+
+        assert_eq!(frames(&mut animation, 3), [0, 1, 2]);
+        assert!(!animation.is_finished());
+
+        assert_eq!(frames(&mut animation, 3), [3, 3, 3]);
+        assert!(animation.is_finished());
+        assert!(core::ptr::eq(animation.sprite(), FORWARD.sprite(3)));
+
+        animation.reset();
+        assert_eq!(animation.frame(), 0);
+        assert!(!animation.is_finished());
+    }
+
+    #[test_case]
+    fn one_shot_ping_pong_ends_on_first_sprite(_gba: &mut crate::Gba) {
+        let mut animation = AnimationController::new(&PING_PONG, 1, AnimationMode::Once);
+
+        assert_eq!(frames(&mut animation, 6), [0, 1, 2, 3, 4, 4]);
+        assert!(animation.is_finished());
+        assert!(core::ptr::eq(animation.sprite(), PING_PONG.sprite(0)));
+    }
+
+    #[test_case]
+    fn single_sprite_ping_pong_stays_on_that_sprite(_gba: &mut crate::Gba) {
+        let mut animation = AnimationController::new(&SINGLE_PING_PONG, 1, AnimationMode::Looping);
+
+        assert_eq!(frames(&mut animation, 3), [0, 0, 0]);
+        assert!(core::ptr::eq(animation.sprite(), &TEST_FRAMES[1]));
+        assert!(core::ptr::eq(
+            SINGLE_PING_PONG.animation_sprite(5),
+            &TEST_FRAMES[1]
+        ));
+
+        let mut once = AnimationController::new(&SINGLE_PING_PONG, 1, AnimationMode::Once);
+        once.update();
+        assert!(once.is_finished());
+        assert!(core::ptr::eq(once.sprite(), &TEST_FRAMES[1]));
+    }
+}
@@ -1,6 +1,6 @@
 use core::cell::{Cell, UnsafeCell};
 
-use agb_fixnum::Vector2D;
+use agb_fixnum::{Rect, Vector2D};
 
 use crate::{
     arena::{Arena, ArenaKey},
@@ -9,7 +9,7 @@ use crate::{
 
 use super::{
     AffineMatrixInstance, AffineMode, OamUnmanaged, ObjectUnmanaged, Sprite, SpriteLoader,
-    SpriteVram,
+    SpriteVram, Tag,
 };
 
 type ObjectKey = ArenaKey;
@@ -29,6 +29,7 @@ struct ObjectItem {
 struct Store {
     store: UnsafeCell<Arena<ObjectItem>>,
     first_z: Cell<Option<ObjectKey>>,
+    sprite_loader: UnsafeCell<SpriteLoader>,
 }
 
 struct StoreIterator<'store> {
@@ -47,6 +48,24 @@ impl<'store> Iterator for StoreIterator<'store> {
 }
 
 impl Store {
+    /// SAFETY:
+    /// Do not reenter or recurse or otherwise use sprite loader cell during this.
+    unsafe fn do_work_with_sprite_loader<C, T>(&self, c: C) -> T
+    where
+        C: Fn(&mut SpriteLoader) -> T,
+    {
+        let sprite_loader = unsafe { &mut *self.sprite_loader.get() };
+
+        c(sprite_loader)
+    }
+
+    fn sprite(&self, sprite: &'static Sprite) -> SpriteVram {
+        // safety: not reentrant
+        unsafe {
+            self.do_work_with_sprite_loader(|sprite_loader| sprite_loader.get_vram_sprite(sprite))
+        }
+    }
+
     /// SAFETY: while this exists, no other store related operations should be
     /// performed. Notably this means you shouldn't drop the ObjectItem as this
     /// implementation will touch this.
@@ -136,7 +155,6 @@ impl Store {
 /// Otherwise I'd recommend using [`OamUnmanaged`].
 pub struct OamManaged<'gba> {
     object_store: Store,
-    sprite_loader: UnsafeCell<SpriteLoader>,
     unmanaged: UnsafeCell<OamUnmanaged<'gba>>,
 }
 
@@ -146,23 +164,12 @@ impl OamManaged<'_> {
             object_store: Store {
                 store: UnsafeCell::new(Arena::new()),
                 first_z: Cell::new(None),
+                sprite_loader: UnsafeCell::new(SpriteLoader::new()),
             },
-            sprite_loader: UnsafeCell::new(SpriteLoader::new()),
             unmanaged: UnsafeCell::new(OamUnmanaged::new()),
         }
     }
 
-    /// SAFETY:
-    /// Do not reenter or recurse or otherwise use sprite loader cell during this.
-    unsafe fn do_work_with_sprite_loader<C, T>(&self, c: C) -> T
-    where
-        C: Fn(&mut SpriteLoader) -> T,
-    {
-        let sprite_loader = unsafe { &mut *self.sprite_loader.get() };
-
-        c(sprite_loader)
-    }
-
     /// Commits all the visible objects. Call during vblank to make changes made
     /// to objects visible.
     pub fn commit(&self) {
@@ -179,7 +186,8 @@ impl OamManaged<'_> {
 
         // safety: not reentrant
         unsafe {
-            self.do_work_with_sprite_loader(SpriteLoader::garbage_collect);
+            self.object_store
+                .do_work_with_sprite_loader(SpriteLoader::garbage_collect);
         }
     }
 
@@ -191,10 +199,7 @@ impl OamManaged<'_> {
 
     /// Creates a sprite in vram from a static sprite from [`include_aseprite`][crate::include_aseprite].
     pub fn sprite(&self, sprite: &'static Sprite) -> SpriteVram {
-        // safety: not reentrant
-        unsafe {
-            self.do_work_with_sprite_loader(|sprite_loader| sprite_loader.get_vram_sprite(sprite))
-        }
+        self.object_store.sprite(sprite)
     }
 
     /// Creates a sprite in vram and uses it to make an object from a static sprite from [`include_aseprite`][crate::include_aseprite].
@@ -313,6 +318,13 @@ impl Object<'_> {
     /// Negative z is more towards the outside and positive z is further into
     /// the screen => an object with a more *negative* z is drawn on top of an
     /// object with a more *positive* z.
+    ///
+    /// When [committing](OamManaged::commit), objects are written to OAM in
+    /// order of increasing z, and the GBA draws objects in earlier OAM slots on
+    /// top of those in later slots. The z position does not change the
+    /// [priority](Self::set_priority) of the object, which is compared first:
+    /// an object with priority [`P0`](Priority::P0) is always drawn on top of
+    /// one with priority [`P1`](Priority::P1) regardless of their z positions.
     pub fn set_z(&mut self, z_index: i32) -> &mut Self {
         let my_object = &self.store.get_object(self.me);
 
@@ -387,6 +399,14 @@ impl Object<'_> {
         self
     }
 
+    /// Display the sprite in Normal mode if any part of it would be on screen,
+    /// otherwise hide it. Returns whether the object is now shown. See
+    /// [`ObjectUnmanaged::show_if_visible`].
+    pub fn show_if_visible(&mut self) -> bool {
+        // safety: only have one of these, doesn't modify slotmap
+        unsafe { self.object().show_if_visible() }
+    }
+
     /// Display the sprite in Affine mode.
     pub fn show_affine(&mut self, affine_mode: AffineMode) -> &mut Self {
         // safety: only have one of these, doesn't modify slotmap
@@ -503,6 +523,14 @@ impl Object<'_> {
         unsafe { self.object_shared().position() }
     }
 
+    /// Returns the area covered by the object's sprite. See
+    /// [`ObjectUnmanaged::bounding_box`].
+    #[must_use]
+    pub fn bounding_box(&self) -> Rect<i32> {
+        // safety: only have one of these, doesn't modify slotmap
+        unsafe { self.object_shared().bounding_box() }
+    }
+
     /// Sets the affine matrix. This only has an affect in Affine mode.
     pub fn set_affine_matrix(&mut self, affine_matrix: AffineMatrixInstance) -> &mut Self {
         // safety: only have one of these, doesn't modify slotmap
@@ -518,13 +546,34 @@ impl Object<'_> {
 
         self
     }
+
+    /// Sets the current sprite for the object to the sprite at index `frame` of the tag,
+    /// loading it into vram if needed. Indexes past the end of the tag wrap around to the
+    /// start, so a frame counter can be passed in directly. This ignores the animation
+    /// direction of the tag, see [`set_animation_frame`](Self::set_animation_frame) to use it.
+    pub fn set_frame(&mut self, tag: &Tag, frame: usize) -> &mut Self {
+        let sprite = self.store.sprite(tag.sprite(frame % tag.sprites().len()));
+        self.set_sprite(sprite)
+    }
+
+    /// Sets the current sprite for the object to frame `frame` of the tag's animation,
+    /// loading it into vram if needed. The sprite is chosen using
+    /// [`Tag::animation_sprite`], so this follows the animation direction set in aseprite
+    /// and repeats the animation once `frame` goes past the end.
+    pub fn set_animation_frame(&mut self, tag: &Tag, frame: usize) -> &mut Self {
+        let sprite = self.store.sprite(tag.animation_sprite(frame));
+        self.set_sprite(sprite)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
 
-    use crate::{display::object::Graphics, include_aseprite};
+    use crate::{
+        display::object::{Graphics, OBJECT_ATTRIBUTE_MEMORY},
+        include_aseprite,
+    };
 
     use super::*;
 
@@ -558,4 +607,109 @@ mod tests {
             );
         }
     }
+
+    fn oam_slot_x(slot: usize) -> u16 {
+        unsafe { OBJECT_ATTRIBUTE_MEMORY.add(slot * 4 + 1).read_volatile() & 0x1ff }
+    }
+
+    fn oam_slot_tile(slot: usize) -> u16 {
+        unsafe { OBJECT_ATTRIBUTE_MEMORY.add(slot * 4 + 2).read_volatile() & 0x3ff }
+    }
+
+    #[test_case]
+    fn lower_z_is_committed_to_earlier_slots(gba: &mut crate::Gba) {
+        let managed = gba.display.object.get_managed();
+
+        let sprite = managed.sprite(TEST_SPRITE);
+
+        let mut objects: Vec<_> = [(10, 5), (20, -5), (30, 0)]
+            .into_iter()
+            .map(|(x, z)| {
+                let mut object = managed.object(sprite.clone());
+                object.set_x(x).set_z(z).show();
+                object
+            })
+            .collect();
+
+        managed.commit();
+
+        assert_eq!(
+            [oam_slot_x(0), oam_slot_x(1), oam_slot_x(2)],
+            [20, 30, 10],
+            "objects with a lower z should be drawn in front, so come first in OAM"
+        );
+
+        objects[0].set_z(-10);
+        managed.commit();
+
+        assert_eq!([oam_slot_x(0), oam_slot_x(1), oam_slot_x(2)], [10, 20, 30]);
+
+        drop(objects);
+        managed.commit();
+    }
+
+    static OBJECTS: &Graphics = include_aseprite!("examples/gfx/objects.aseprite");
+
+    #[test_case]
+    fn frames_past_the_end_of_the_tag_wrap_around(gba: &mut crate::Gba) {
+        let managed = gba.display.object.get_managed();
+
+        let tag = OBJECTS.tags().get("emu-walk");
+        let len = tag.sprites().len();
+
+        let mut object = managed.object_sprite(tag.sprite(0));
+        object.show();
+
+        for frame in [0, 1, len - 1, len, len + 1, 3 * len + 2] {
+            object.set_frame(tag, frame);
+            managed.commit();
+            assert_eq!(
+                oam_slot_tile(0),
+                managed.sprite(tag.sprite(frame % len)).location(),
+                "frame {frame} should wrap to {}",
+                frame % len
+            );
+
+            object.set_animation_frame(tag, frame);
+            managed.commit();
+            assert_eq!(
+                oam_slot_tile(0),
+                managed.sprite(tag.animation_sprite(frame)).location()
+            );
+        }
+    }
+
+    #[test_case]
+    fn show_if_visible_accounts_for_sprite_size(gba: &mut crate::Gba) {
+        let managed = gba.display.object.get_managed();
+
+        // the test sprite is 16x32
+        let mut object = managed.object(managed.sprite(TEST_SPRITE));
+
+        for (position, expected) in [
+            ((0, 0), true),
+            ((239, 159), true),
+            ((240, 0), false),
+            ((0, 160), false),
+            ((-15, 0), true),
+            ((-16, 0), false),
+            ((0, -31), true),
+            ((0, -32), false),
+            ((-15, -31), true),
+            ((-16, 100), false),
+            ((300, 200), false),
+            ((0, 300), false),
+            ((600, 0), false),
+        ] {
+            object.set_position(position);
+
+            assert_eq!(
+                object.show_if_visible(),
+                expected,
+                "object at {position:?} should {}be visible",
+                if expected { "" } else { "not " }
+            );
+            assert_eq!(object.is_visible(), expected);
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use core::{
+    cell::RefCell,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+use alloc::vec::Vec;
+
+use super::{OamManaged, Object, SpriteVram};
+
+/// The number of objects which can be displayed at once.
+const OAM_SLOTS: usize = 128;
+
+/// A fixed number of [`Object`]s which are created up front and reused, for things like
+/// particles and bullets which would otherwise be created and dropped all the time.
+///
+/// [`acquire`](ObjectPool::acquire()) hands out objects until all of them are in use, and then
+/// returns `None`, so the pool also acts as a cap on how many of them there can be. An object
+/// goes back into the pool when its [`PooledObject`] is dropped.
+///
+/// Create a pool with [`OamManaged::object_pool`].
+pub struct ObjectPool<'controller> {
+    free: RefCell<Vec<Object<'controller>>>,
+    capacity: usize,
+}
+
+/// An [`Object`] which has been taken from an [`ObjectPool`]. This can be used like an
+/// [`Object`], and returns the object to the pool when dropped.
+///
+/// Objects come out of the pool hidden, and are hidden again when they are returned. Anything
+/// else, such as their sprite, position and z index, is left as it was by whatever last used
+/// the object, so set everything you need after acquiring it.
+pub struct PooledObject<'pool, 'controller> {
+    object: ManuallyDrop<Object<'controller>>,
+    pool: &'pool ObjectPool<'controller>,
+}
+
+impl OamManaged<'_> {
+    /// Creates a pool of `size` hidden objects which all start with the given sprite. See
+    /// [`ObjectPool`] for details.
+    ///
+    /// Panics if `size` is greater than 128, the number of objects the GBA can display at once.
+    /// Objects from different pools and outside of any pool all share these 128 slots, and only
+    /// the first 128 visible objects in z order are displayed.
+    pub fn object_pool(&self, sprite: &SpriteVram, size: usize) -> ObjectPool<'_> {
+        assert!(
+            size <= OAM_SLOTS,
+            "object pools can't hold more than {OAM_SLOTS} objects, but tried to create one with {size}"
+        );
+
+        let mut free = Vec::with_capacity(size);
+        free.extend((0..size).map(|_| {
+            let mut object = self.object(sprite.clone());
+            object.hide();
+            object
+        }));
+
+        ObjectPool {
+            free: RefCell::new(free),
+            capacity: size,
+        }
+    }
+}
+
+impl<'controller> ObjectPool<'controller> {
+    /// Takes an unused object out of the pool, or returns `None` if they are all in use.
+    #[must_use]
+    pub fn acquire(&self) -> Option<PooledObject<'_, 'controller>> {
+        let object = self.free.borrow_mut().pop()?;
+
+        Some(PooledObject {
+            object: ManuallyDrop::new(object),
+            pool: self,
+        })
+    }
+
+    /// The number of objects which are not currently in use.
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// The total number of objects in the pool, whether they are in use or not.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<'controller> Deref for PooledObject<'_, 'controller> {
+    type Target = Object<'controller>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl DerefMut for PooledObject<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.object
+    }
+}
+
+impl Drop for PooledObject<'_, '_> {
+    fn drop(&mut self) {
+        // SAFETY: the object isn't accessed again after being taken
+        let mut object = unsafe { ManuallyDrop::take(&mut self.object) };
+        object.hide();
+
+        // there is always room, since the pool started with this object in it
+        self.pool.free.borrow_mut().push(object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        display::object::{Graphics, Sprite},
+        include_aseprite,
+    };
+
+    static TEST_SPRITES: &Graphics = include_aseprite!("examples/gfx/tall.aseprite");
+    static TEST_SPRITE: &Sprite = &TEST_SPRITES.sprites()[0];
+
+    #[test_case]
+    fn pools_run_out_and_reuse_released_objects(gba: &mut crate::Gba) {
+        let managed = gba.display.object.get_managed();
+        let pool = managed.object_pool(&managed.sprite(TEST_SPRITE), 3);
+
+        let mut acquired: Vec<_> = (0..3).map(|_| pool.acquire().unwrap()).collect();
+        assert!(acquired.iter().all(|object| !object.is_visible()));
+        assert_eq!(pool.available(), 0);
+        assert!(pool.acquire().is_none(), "the pool should be exhausted");
+
+        acquired[0].set_position((10, 20)).show();
+        acquired.remove(0);
+        assert_eq!(pool.available(), 1);
+
+        let reused = pool
+            .acquire()
+            .expect("a released object should be available again");
+        assert!(!reused.is_visible(), "released objects should be hidden");
+        assert_eq!(reused.position(), (10, 20).into());
+        assert!(pool.acquire().is_none());
+
+        drop(reused);
+        drop(acquired);
+        assert_eq!(pool.available(), pool.capacity());
+    }
+}
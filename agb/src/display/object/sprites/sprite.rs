@@ -1,6 +1,9 @@
 use core::{alloc::Layout, slice};
 
-use crate::display::palette16::Palette16;
+use crate::{
+    display::palette16::Palette16,
+    fixnum::{Rect, Vector2D},
+};
 
 use super::BYTES_PER_TILE_4BPP;
 
@@ -32,6 +35,117 @@ impl Sprite {
     pub fn size(&self) -> Size {
         self.size
     }
+
+    #[must_use]
+    /// Gives the width and height of the sprite in pixels
+    pub fn dimensions(&self) -> Vector2D<u16> {
+        self.size.dimensions()
+    }
+
+    /// Whether the pixel at the given position is drawn, which is the case for
+    /// any pixel which isn't colour 0 of the palette. Positions outside of the
+    /// sprite are never opaque.
+    #[must_use]
+    pub fn is_pixel_opaque(&self, x: i32, y: i32) -> bool {
+        let (width, height) = self.size.to_width_height();
+        if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+            return false;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+
+        let tile = (y / 8) * (width / 8) + x / 8;
+        let pixel = (y % 8) * 8 + x % 8;
+        let byte = self.data[tile * BYTES_PER_TILE_4BPP + pixel / 2];
+
+        let colour = if pixel % 2 == 0 {
+            byte & 0xf
+        } else {
+            byte >> 4
+        };
+        colour != 0
+    }
+
+    /// Checks whether any opaque pixel of this sprite overlaps an opaque pixel
+    /// of `other`, when `other` is displayed `relative_offset` pixels away from
+    /// this sprite. See [`is_pixel_opaque`](Self::is_pixel_opaque) for which
+    /// pixels count as opaque.
+    ///
+    /// The overlap is checked one 8x8 tile at a time, and parts of it where
+    /// either sprite's tiles are completely transparent are skipped. Otherwise
+    /// every pixel of the overlap is checked, so the worst case is two 64x64
+    /// sprites directly on top of each other whose opaque pixels never meet,
+    /// which checks all 4096 pixels and can take a large part of a frame. Check
+    /// whether the bounding boxes overlap first, and only call this once you
+    /// know that they do.
+    #[must_use]
+    pub fn collides_pixel(&self, other: &Sprite, relative_offset: Vector2D<i32>) -> bool {
+        let (width, height) = self.size.to_width_height();
+        let (other_width, other_height) = other.size.to_width_height();
+
+        let this_rect = Rect::new((0, 0).into(), (width as i32, height as i32).into());
+        let other_rect = Rect::new(
+            relative_offset,
+            (other_width as i32, other_height as i32).into(),
+        );
+
+        let Some(overlap) = this_rect.overlapping_rect(other_rect) else {
+            return false;
+        };
+        if overlap.size.x <= 0 || overlap.size.y <= 0 {
+            return false;
+        }
+
+        let overlap_end = overlap.position + overlap.size;
+
+        for tile_y in overlap.position.y / 8..=(overlap_end.y - 1) / 8 {
+            for tile_x in overlap.position.x / 8..=(overlap_end.x - 1) / 8 {
+                let tile = Rect::new((tile_x * 8, tile_y * 8).into(), (8, 8).into());
+                let Some(area) = overlap.overlapping_rect(tile) else {
+                    continue;
+                };
+
+                if self.is_area_transparent(area)
+                    || other
+                        .is_area_transparent(Rect::new(area.position - relative_offset, area.size))
+                {
+                    continue;
+                }
+
+                for y in area.position.y..area.position.y + area.size.y {
+                    for x in area.position.x..area.position.x + area.size.x {
+                        if self.is_pixel_opaque(x, y)
+                            && other.is_pixel_opaque(x - relative_offset.x, y - relative_offset.y)
+                        {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Whether every tile which `area` touches is completely transparent. The
+    /// area must be non-empty and within the sprite.
+    fn is_area_transparent(&self, area: Rect<i32>) -> bool {
+        let tiles_per_row = self.size.to_width_height().0 / 8;
+        let area_end = area.position + area.size;
+
+        for tile_y in area.position.y / 8..=(area_end.y - 1) / 8 {
+            for tile_x in area.position.x / 8..=(area_end.x - 1) / 8 {
+                let tile = tile_y as usize * tiles_per_row + tile_x as usize;
+                let data = &self.data[tile * BYTES_PER_TILE_4BPP..(tile + 1) * BYTES_PER_TILE_4BPP];
+
+                if data.iter().any(|&byte| byte != 0) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
 }
 
 /// The sizes of sprite supported by the GBA.
@@ -92,6 +206,25 @@ macro_rules! align_bytes {
 /// name in code. You should ensure tags are unique as this is not enforced by
 /// aseprite.
 ///
+/// Slices are also included, so regions drawn in aseprite such as hitboxes can
+/// be looked up by name with [`Tag::slice`] and [`Tag::frame_slice`].
+///
+/// To save ROM, you can list the tags you need from a file after its name. Only those
+/// tags and the frames they cover are included, so frames which aren't part of any of the
+/// listed tags are left out. Listing a tag which isn't in the file is a compile error, and as
+/// [`TagMap::get`] is `const`, so is getting a tag which was left out in a `static`.
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # use agb::{display::object::{Graphics, Tag}, include_aseprite};
+/// static GRAPHICS: &Graphics = include_aseprite!(
+///     "examples/gfx/boss.aseprite",
+///     "examples/gfx/objects.aseprite" tags ["emu-walk"]
+/// );
+/// static EMU_WALK: &Tag = GRAPHICS.tags().get("emu-walk");
+/// ```
+///
 /// Including from the out directory is supported through the `$OUT_DIR` token.
 ///
 /// ```rust,ignore
@@ -105,13 +238,14 @@ macro_rules! align_bytes {
 ///
 #[macro_export]
 macro_rules! include_aseprite {
-    ($($aseprite_path: expr),*) => {{
+    ($($aseprite_file: tt)*) => {{
         #[allow(unused_imports)]
-        use $crate::display::object::{Size, Sprite, Tag, TagMap, Graphics};
+        use $crate::display::object::{Size, Sprite, Tag, TagMap, Graphics, Slice, SliceKey};
         use $crate::display::palette16::Palette16;
+        #[allow(unused_imports)]
         use $crate::align_bytes;
 
-        $crate::include_aseprite_inner!($($aseprite_path),*);
+        $crate::include_aseprite_inner!($($aseprite_file)*);
 
         &Graphics::new(SPRITES, &TAGS)
     }};
@@ -256,9 +390,58 @@ impl Direction {
     }
 }
 
+#[doc(hidden)]
+/// The bounds of a slice over a range of frames. Used internally by
+/// [include_aseprite] and should not really be used outside of it.
+pub struct SliceKey {
+    start: usize,
+    end: usize,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl SliceKey {
+    #[doc(hidden)]
+    /// Creates the bounds of a slice which apply from the frame `start` up to
+    /// but not including the frame `end`.
+    #[must_use]
+    pub const fn new(start: usize, end: usize, x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            start,
+            end,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[doc(hidden)]
+/// A named slice from aseprite. Used internally by [include_aseprite] and
+/// should not really be used outside of it. Use [`Tag::slice`] instead.
+pub struct Slice {
+    name: &'static str,
+    keys: &'static [SliceKey],
+}
+
+impl Slice {
+    #[doc(hidden)]
+    /// Creates a slice from its name and the bounds it has on each frame.
+    #[must_use]
+    pub const fn new(name: &'static str, keys: &'static [SliceKey]) -> Self {
+        Self { name, keys }
+    }
+}
+
 /// A sequence of sprites from aseprite.
 pub struct Tag {
     sprites: *const Sprite,
+    durations: *const u16,
+    slices: &'static [Slice],
+    from: usize,
     len: usize,
     direction: Direction,
 }
@@ -281,6 +464,60 @@ impl Tag {
         unsafe { &*self.sprites.add(idx) }
     }
 
+    /// How long the sprite at the given index should be shown for in milliseconds, as
+    /// set for that frame in aseprite.
+    #[must_use]
+    pub const fn frame_duration(&self, idx: usize) -> u16 {
+        if idx >= self.len {
+            panic!("out of bounds access to frame duration");
+        }
+        unsafe { *self.durations.add(idx) }
+    }
+
+    /// The individual sprites that make up the animation along with how long each
+    /// should be shown for in milliseconds. See [`frame_duration`](Self::frame_duration).
+    pub fn frames(&self) -> impl Iterator<Item = (&'static Sprite, u16)> {
+        let durations = unsafe { slice::from_raw_parts(self.durations, self.len) };
+        self.sprites().iter().zip(durations.iter().copied())
+    }
+
+    /// The bounds of the slice with the given name on the first sprite of the
+    /// animation. Slices can be drawn in aseprite to mark regions of a sprite,
+    /// such as a hitbox, so they don't need to be transcribed by hand. The
+    /// rectangle is in pixels relative to the top left of the sprite.
+    ///
+    /// Returns `None` if there is no slice with that name on the first sprite.
+    /// See [`frame_slice`](Self::frame_slice) for slices which change over the
+    /// course of the animation.
+    #[must_use]
+    pub fn slice(&self, name: &str) -> Option<Rect<i32>> {
+        self.frame_slice(0, name)
+    }
+
+    /// The bounds of the slice with the given name on the sprite at the given
+    /// index. Slices which are moved or resized on some frames in aseprite get
+    /// the bounds set for that frame.
+    ///
+    /// Returns `None` if there is no slice with that name on that sprite,
+    /// including if the slice has been removed from that frame in aseprite.
+    #[must_use]
+    pub fn frame_slice(&self, idx: usize, name: &str) -> Option<Rect<i32>> {
+        assert!(idx < self.len, "out of bounds access to frame slice");
+        let frame = self.from + idx;
+
+        self.slices
+            .iter()
+            .filter(|slice| slice.name == name)
+            .flat_map(|slice| slice.keys.iter())
+            .find(|key| (key.start..key.end).contains(&frame))
+            .map(|key| {
+                Rect::new(
+                    Vector2D::new(key.x, key.y),
+                    Vector2D::new(key.width, key.height),
+                )
+            })
+    }
+
     /// A sprite that follows the animation sequence. For instance, in aseprite
     /// tags can be specified to animate:
     /// * Forward
@@ -296,6 +533,8 @@ impl Tag {
         match self.direction {
             Direction::Forward => self.sprite(idx % self.len),
             Direction::Backward => self.sprite(len_sub_1 - (idx % self.len)),
+            // a single sprite has nothing to bounce between
+            Direction::PingPong if self.len == 1 => self.sprite(0),
             Direction::PingPong => self.sprite(
                 (((idx + len_sub_1) % (len_sub_1 * 2)) as isize - len_sub_1 as isize)
                     .unsigned_abs(),
@@ -303,15 +542,44 @@ impl Tag {
         }
     }
 
+    /// The number of frames before the animation sequence repeats.
+    pub(crate) const fn animation_cycle_length(&self) -> usize {
+        match self.direction {
+            Direction::Forward | Direction::Backward => self.len,
+            Direction::PingPong if self.len == 1 => 1,
+            Direction::PingPong => (self.len - 1) * 2,
+        }
+    }
+
+    /// The frame the animation sequence ends on if it is played once. Ping pong
+    /// animations finish back on their first sprite.
+    pub(crate) const fn animation_last_frame(&self) -> usize {
+        match self.direction {
+            Direction::Forward | Direction::Backward => self.len - 1,
+            Direction::PingPong => (self.len - 1) * 2,
+        }
+    }
+
     #[doc(hidden)]
     /// Creates a new sprite from it's constituent parts. Used internally by
     /// [include_aseprite] and should generally not be used elsewhere.
     #[must_use]
-    pub const fn new(sprites: &'static [Sprite], from: usize, to: usize, direction: usize) -> Self {
+    pub const fn new(
+        sprites: &'static [Sprite],
+        durations: &'static [u16],
+        slices: &'static [Slice],
+        from: usize,
+        to: usize,
+        direction: usize,
+    ) -> Self {
         assert!(from <= to);
         assert!(to < sprites.len());
+        assert!(sprites.len() == durations.len());
         Self {
             sprites: &sprites[from] as *const Sprite,
+            durations: &durations[from] as *const u16,
+            slices,
+            from,
             len: to - from + 1,
             direction: Direction::from_usize(direction),
         }
@@ -383,6 +651,11 @@ impl Size {
         }
     }
 
+    pub(crate) fn dimensions(self) -> Vector2D<u16> {
+        let (width, height) = self.to_width_height();
+        Vector2D::new(width as u16, height as u16)
+    }
+
     #[must_use]
     /// Returns the width and height of the size in pixels.
     pub const fn to_tiles_width_height(self) -> (usize, usize) {
@@ -390,3 +663,132 @@ impl Size {
         (wh.0 / 8, wh.1 / 8)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(align(2))]
+    struct AlignedData<const N: usize>([u8; N]);
+
+    static PALETTE: Palette16 = Palette16::new([0; 16]);
+
+    // opaque only at (9, 7)
+    static WIDE_DATA: AlignedData<64> = {
+        let mut data = [0; 64];
+        data[BYTES_PER_TILE_4BPP + 28] = 0x10;
+        AlignedData(data)
+    };
+    // opaque only at (0, 0)
+    static SQUARE_DATA: AlignedData<32> = {
+        let mut data = [0; 32];
+        data[0] = 0x01;
+        AlignedData(data)
+    };
+
+    static WIDE: Sprite = unsafe { Sprite::new(&PALETTE, &WIDE_DATA.0, Size::S16x8) };
+    static SQUARE: Sprite = unsafe { Sprite::new(&PALETTE, &SQUARE_DATA.0, Size::S8x8) };
+
+    #[test_case]
+    fn pixel_opacity_follows_tile_layout(_gba: &mut crate::Gba) {
+        assert!(WIDE.is_pixel_opaque(9, 7));
+        assert!(!WIDE.is_pixel_opaque(8, 7));
+        assert!(!WIDE.is_pixel_opaque(1, 7));
+        assert!(!WIDE.is_pixel_opaque(16, 7));
+
+        assert!(SQUARE.is_pixel_opaque(0, 0));
+        assert!(!SQUARE.is_pixel_opaque(1, 0));
+        assert!(!SQUARE.is_pixel_opaque(-1, 0));
+    }
+
+    #[test_case]
+    fn dimensions_come_from_the_sprite_size(_gba: &mut crate::Gba) {
+        assert_eq!(WIDE.dimensions(), Vector2D::new(16, 8));
+        assert_eq!(SQUARE.dimensions(), Vector2D::new(8, 8));
+    }
+
+    #[test_case]
+    fn pixel_collision_checks_opaque_pixels(_gba: &mut crate::Gba) {
+        assert!(WIDE.collides_pixel(&SQUARE, (9, 7).into()));
+        assert!(SQUARE.collides_pixel(&WIDE, (-9, -7).into()));
+
+        // bounding boxes overlap, but the opaque pixels don't
+        assert!(!WIDE.collides_pixel(&SQUARE, (8, 6).into()));
+        assert!(!WIDE.collides_pixel(&SQUARE, (20, 0).into()));
+    }
+
+    #[test_case]
+    fn pixel_collision_skips_transparent_tiles_without_missing_hits(_gba: &mut crate::Gba) {
+        // the opaque pixel of SQUARE lands in each of WIDE's tiles, only the second of which has
+        // an opaque pixel
+        assert!(!WIDE.collides_pixel(&SQUARE, (1, 7).into()));
+        assert!(WIDE.collides_pixel(&SQUARE, (9, 7).into()));
+
+        // just touching the edge of the sprite
+        assert!(!WIDE.collides_pixel(&SQUARE, (16, 0).into()));
+        assert!(!SQUARE.collides_pixel(&WIDE, (-9, -8).into()));
+    }
+
+    static OBJECTS: &Graphics = crate::include_aseprite!("examples/gfx/objects.aseprite");
+
+    #[test_case]
+    fn tags_include_frame_durations(_gba: &mut crate::Gba) {
+        // every frame in objects.aseprite is shown for 100ms
+        let emu_walk = OBJECTS.tags().get("emu-walk");
+
+        assert_eq!(emu_walk.frame_duration(0), 100);
+        assert_eq!(emu_walk.frames().count(), emu_walk.sprites().len());
+
+        for (i, (sprite, duration)) in emu_walk.frames().enumerate() {
+            assert!(core::ptr::eq(sprite, emu_walk.sprite(i)));
+            assert_eq!(duration, 100);
+        }
+    }
+
+    static EMU_WALK_ONLY: &Graphics =
+        crate::include_aseprite!("examples/gfx/objects.aseprite" tags ["emu-walk"]);
+
+    #[test_case]
+    fn only_the_listed_tags_are_included(_gba: &mut crate::Gba) {
+        let emu_walk = EMU_WALK_ONLY.tags().get("emu-walk");
+
+        assert_eq!(EMU_WALK_ONLY.tags().values().count(), 1);
+        assert_eq!(EMU_WALK_ONLY.sprites().len(), emu_walk.sprites().len());
+        assert!(EMU_WALK_ONLY.sprites().len() < OBJECTS.sprites().len());
+
+        let full_emu_walk = OBJECTS.tags().get("emu-walk");
+        assert_eq!(emu_walk.sprites().len(), full_emu_walk.sprites().len());
+        for (sprite, full_sprite) in emu_walk.sprites().iter().zip(full_emu_walk.sprites()) {
+            assert_eq!(sprite.size(), full_sprite.size());
+        }
+    }
+
+    static HITBOX: &Graphics = crate::include_aseprite!("gfx/hitbox.aseprite");
+
+    #[test_case]
+    fn tags_include_slices(_gba: &mut crate::Gba) {
+        let swing = HITBOX.tags().get("swing");
+
+        assert_eq!(
+            swing.slice("hitbox"),
+            Some(Rect::new((2, 4).into(), (12, 20).into()))
+        );
+        assert_eq!(swing.slice("hitbox"), swing.frame_slice(0, "hitbox"));
+        assert_eq!(
+            swing.frame_slice(1, "hitbox"),
+            Some(Rect::new((4, 6).into(), (10, 16).into()))
+        );
+        // the hitbox is removed on the last frame
+        assert_eq!(swing.frame_slice(2, "hitbox"), None);
+
+        // a slice with a single key applies to every frame
+        for i in 0..3 {
+            assert_eq!(
+                swing.frame_slice(i, "body"),
+                Some(Rect::new((1, 0).into(), (14, 32).into()))
+            );
+        }
+
+        assert_eq!(swing.slice("missing"), None);
+    }
+}
@@ -8,6 +8,7 @@ use alloc::{
 use crate::{
     agb_alloc::{block_allocator::BlockAllocator, bump_allocator::StartEnd, impl_zst_allocator},
     display::palette16::Palette16,
+    fixnum::Vector2D,
     hash_map::HashMap,
 };
 
@@ -193,6 +194,12 @@ impl SpriteVram {
         self.data.size
     }
 
+    #[must_use]
+    /// Gives the width and height of the sprite in pixels
+    pub fn dimensions(&self) -> Vector2D<u16> {
+        self.data.size.dimensions()
+    }
+
     pub(crate) fn palette_location(&self) -> u16 {
         self.data.palette.data.location.0 as u16
     }
@@ -304,6 +311,11 @@ impl Default for SpriteLoader {
 }
 
 /// Sprite data that can be used to create sprites in vram.
+///
+/// The pixels are stored directly in sprite vram at 4 bits per pixel, so a sprite takes up half
+/// a byte per pixel, or 32 bytes per 8x8 tile, out of the 32kB shared with every other sprite.
+/// That space is freed again once the [`DynamicSprite`], or the [`SpriteVram`] it was turned
+/// into with [`to_vram`](DynamicSprite::to_vram), is dropped.
 pub struct DynamicSprite {
     data: Box<[u16], SpriteAllocator>,
     size: Size,
@@ -404,3 +416,35 @@ impl DynamicSprite {
         unsafe { SpriteVram::from_location_size(data.cast(), self.size, palette) }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test_case]
+    fn dynamic_sprite_packs_pixels_into_tiles(_gba: &mut crate::Gba) {
+        let mut sprite = DynamicSprite::new(Size::S16x8);
+        assert!(sprite.data.iter().all(|&half_word| half_word == 0));
+
+        sprite.set_pixel(0, 0, 1);
+        sprite.set_pixel(3, 0, 0xf);
+        sprite.set_pixel(4, 1, 2);
+        // the first pixel of the second tile
+        sprite.set_pixel(8, 0, 3);
+        sprite.set_pixel(15, 7, 4);
+
+        let tile_half_words = BYTES_PER_TILE_4BPP / 2;
+        assert_eq!(sprite.data.len(), 2 * tile_half_words);
+
+        assert_eq!(sprite.data[0], 0xf001);
+        assert_eq!(sprite.data[3], 0x0002);
+        assert_eq!(sprite.data[tile_half_words], 0x0003);
+        assert_eq!(sprite.data[2 * tile_half_words - 1], 0x4000);
+
+        sprite.set_pixel(0, 0, 5);
+        assert_eq!(sprite.data[0], 0xf005);
+
+        sprite.clear(6);
+        assert!(sprite.data.iter().all(|&half_word| half_word == 0x6666));
+    }
+}
@@ -35,9 +35,13 @@ impl Default for Attributes {
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 /// The affine mode
 pub enum AffineMode {
-    /// Normal affine, this is where the area of the affine is equal to the sprite size
+    /// Normal affine, this is where the area of the affine is equal to the sprite size. Any part
+    /// of the sprite which is rotated or scaled outside of that area is clipped.
     Affine = 1,
-    /// Double affine, this is where the area of the affine is double that of the sprite
+    /// Double affine, this is where the area of the affine is double that of the sprite. This
+    /// avoids clipping when rotating or scaling up, but the sprite is drawn centred in the larger
+    /// area so appears offset by half its size compared to [`AffineMode::Affine`], and it counts
+    /// as a sprite of double the size for the number of objects which fit on a scanline.
     AffineDouble = 3,
 }
 
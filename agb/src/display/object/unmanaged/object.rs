@@ -1,6 +1,6 @@
 use core::{cell::UnsafeCell, marker::PhantomData};
 
-use agb_fixnum::Vector2D;
+use agb_fixnum::{Rect, Vector2D};
 use alloc::vec::Vec;
 
 use crate::display::{
@@ -8,7 +8,7 @@ use crate::display::{
         affine::AffineMatrixVram, sprites::SpriteVram, AffineMatrixInstance,
         OBJECT_ATTRIBUTE_MEMORY,
     },
-    Priority,
+    Priority, HEIGHT, WIDTH,
 };
 
 use super::attributes::{AffineMode, Attributes, GraphicsMode};
@@ -142,7 +142,7 @@ impl OamSlot<'_> {
         if affine_matrix.frame_count() != frame_data.frame {
             affine_matrix.set_frame_count(frame_data.frame);
             assert!(
-                frame_data.affine_matrix_count <= 32,
+                frame_data.affine_matrix_count < 32,
                 "too many affine matricies in one frame"
             );
             affine_matrix.set_location(frame_data.affine_matrix_count);
@@ -229,6 +229,7 @@ pub struct ObjectUnmanaged {
     attributes: Attributes,
     sprite: SpriteVram,
     affine_matrix: Option<AffineMatrixVram>,
+    position: Vector2D<i32>,
 }
 
 impl ObjectUnmanaged {
@@ -243,6 +244,7 @@ impl ObjectUnmanaged {
             attributes: Attributes::default(),
             sprite,
             affine_matrix: None,
+            position: Vector2D::new(0, 0),
         };
 
         sprite.attributes.set_sprite(sprite_location, shape, size);
@@ -265,6 +267,25 @@ impl ObjectUnmanaged {
         self
     }
 
+    /// Display the sprite in Normal mode if any part of it would be on screen,
+    /// otherwise hide it. The size of the current sprite is taken into account.
+    /// This uses the position given to [`set_position`](Self::set_position)
+    /// before it is wrapped, so objects far enough off screen that the GBA
+    /// would wrap them back onto it are hidden. Returns whether the object is
+    /// now shown.
+    pub fn show_if_visible(&mut self) -> bool {
+        let (width, height) = self.sprite.size().to_width_height();
+        let visible = is_on_screen(self.position, width as i32, height as i32);
+
+        if visible {
+            self.show();
+        } else {
+            self.hide();
+        }
+
+        visible
+    }
+
     /// Display the sprite in Affine mode.
     pub fn show_affine(&mut self, affine_mode: AffineMode) -> &mut Self {
         assert!(
@@ -336,6 +357,9 @@ impl ObjectUnmanaged {
     /// Use [set_position](Self::set_position) to set both `x` and `y`
     pub fn set_x(&mut self, x: u16) -> &mut Self {
         self.attributes.set_x(x);
+        // the GBA treats x as a 9 bit signed number
+        let x = i32::from(x.rem_euclid(1 << 9));
+        self.position.x = if x >= 1 << 8 { x - (1 << 9) } else { x };
 
         self
     }
@@ -352,6 +376,9 @@ impl ObjectUnmanaged {
     /// Use [set_position](Self::set_position) to set both `x` and `y`
     pub fn set_y(&mut self, y: u16) -> &mut Self {
         self.attributes.set_y(y);
+        // anything below the screen is treated as wrapping round to the top
+        let y = i32::from(y as u8);
+        self.position.y = if y >= HEIGHT { y - (1 << 8) } else { y };
 
         self
     }
@@ -368,6 +395,7 @@ impl ObjectUnmanaged {
     pub fn set_position(&mut self, position: Vector2D<i32>) -> &mut Self {
         self.set_y(position.y.rem_euclid(1 << 9) as u16);
         self.set_x(position.x.rem_euclid(1 << 9) as u16);
+        self.position = position;
 
         self
     }
@@ -379,6 +407,22 @@ impl ObjectUnmanaged {
         Vector2D::new(self.x() as i32, self.y() as i32)
     }
 
+    /// Returns the area covered by the object's sprite, using the position
+    /// given to [`set_position`](Self::set_position) and the dimensions of its
+    /// sprite. This doesn't take affine transformations into account.
+    ///
+    /// The position is the one before the GBA wraps it around, so an object
+    /// placed off the left or top of the screen has a negative position, and
+    /// one placed far off screen is not moved back onto it. This makes it
+    /// suitable for collision checks in the same coordinates as the game.
+    #[must_use]
+    pub fn bounding_box(&self) -> Rect<i32> {
+        let size = self.sprite.dimensions();
+        let size = Vector2D::new(i32::from(size.x), i32::from(size.y));
+
+        Rect::new(self.position, size)
+    }
+
     /// Sets the affine matrix. This only has an affect in Affine mode.
     pub fn set_affine_matrix(&mut self, affine_matrix: AffineMatrixInstance) -> &mut Self {
         let vram = affine_matrix.vram();
@@ -414,6 +458,15 @@ impl ObjectUnmanaged {
     }
 }
 
+/// Whether an object at the given position (before it is wrapped to fit in
+/// its attributes) with the given size would have any part of it on screen.
+fn is_on_screen(position: Vector2D<i32>, width: i32, height: i32) -> bool {
+    let x_visible = position.x < WIDTH && position.x + width > 0;
+    let y_visible = position.y < HEIGHT && position.y + height > 0;
+
+    x_visible && y_visible
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -448,4 +501,78 @@ mod tests {
             slot_a.set(&obj);
         }
     }
+
+    #[test_case]
+    fn bounding_box_uses_the_sprite_dimensions(gba: &mut crate::Gba) {
+        static GRAPHICS: &Graphics = include_aseprite!(
+            "../examples/the-purple-night/gfx/objects.aseprite",
+            "../examples/the-purple-night/gfx/boss.aseprite"
+        );
+
+        let (_gfx, mut loader) = gba.display.object.get_unmanaged();
+
+        let small = loader.get_vram_sprite(GRAPHICS.tags().get("Idle - longsword").sprite(0));
+        let boss = loader.get_vram_sprite(GRAPHICS.tags().get("Boss").sprite(0));
+        assert_eq!(small.dimensions(), Vector2D::new(16, 16));
+        assert_eq!(boss.dimensions(), Vector2D::new(32, 32));
+
+        let mut obj = ObjectUnmanaged::new(small);
+        for position in [(10, 20), (-5, -7), (230, 150), (-200, 0)] {
+            obj.set_position(position.into());
+            assert_eq!(
+                obj.bounding_box(),
+                Rect::new(position.into(), (16, 16).into())
+            );
+        }
+
+        obj.set_sprite(boss);
+        obj.set_position((-20, -31).into());
+        assert_eq!(
+            obj.bounding_box(),
+            Rect::new((-20, -31).into(), (32, 32).into())
+        );
+
+        obj.set_position((600, 300).into());
+        assert_eq!(
+            obj.bounding_box(),
+            Rect::new((600, 300).into(), (32, 32).into())
+        );
+    }
+
+    #[test_case]
+    fn show_if_visible_hides_objects_which_would_wrap_onto_the_screen(gba: &mut crate::Gba) {
+        static GRAPHICS: &Graphics = include_aseprite!(
+            "../examples/the-purple-night/gfx/objects.aseprite",
+            "../examples/the-purple-night/gfx/boss.aseprite"
+        );
+
+        let (_gfx, mut loader) = gba.display.object.get_unmanaged();
+
+        let small = loader.get_vram_sprite(GRAPHICS.tags().get("Idle - longsword").sprite(0));
+        let mut obj = ObjectUnmanaged::new(small);
+
+        for (position, expected) in [
+            ((10, 20), true),
+            ((0, 256), false),
+            ((0, 300), false),
+            ((10, 400), false),
+            ((512, 0), false),
+            ((600, 20), false),
+            ((-512, 0), false),
+            ((0, -256), false),
+        ] {
+            obj.set_position(position.into());
+            assert_eq!(
+                obj.show_if_visible(),
+                expected,
+                "object at {position:?} should {}be visible",
+                if expected { "" } else { "not " }
+            );
+            assert_eq!(obj.is_visible(), expected);
+        }
+
+        // positions set directly are already wrapped, so 500 is just off the left
+        obj.set_x(500).set_y(250);
+        assert!(obj.show_if_visible());
+    }
 }
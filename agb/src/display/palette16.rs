@@ -1,5 +1,7 @@
 use core::alloc::Layout;
 
+use crate::fixnum::Num;
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct Palette16 {
@@ -28,6 +30,26 @@ impl Palette16 {
     }
 }
 
+/// Linearly interpolates between two 15-bit BGR colours, where an `amount` of 0
+/// gives `from` and an `amount` of 1 gives `to`. Each component is interpolated
+/// separately.
+pub(crate) fn interpolate_colour(from: u16, to: u16, amount: Num<i32, 8>) -> u16 {
+    const MASK: u16 = 0b11111;
+
+    let amount = amount.clamp(0.into(), 1.into());
+
+    let mut result = 0;
+    for shift in [0, 5, 10] {
+        let from_component = Num::<i32, 8>::new(i32::from((from >> shift) & MASK));
+        let to_component = Num::<i32, 8>::new(i32::from((to >> shift) & MASK));
+
+        let component = from_component + (to_component - from_component) * amount;
+        result |= (component.floor() as u16 & MASK) << shift;
+    }
+
+    result
+}
+
 #[macro_export]
 macro_rules! include_palette {
     ($palette:literal) => {
@@ -4,6 +4,9 @@ use super::tiled::{TileSet, TileSetting};
 pub struct TileData {
     pub tiles: TileSet<'static>,
     pub tile_settings: &'static [TileSetting],
+    /// One property per tile in the original image, in the same order as `tile_settings`.
+    /// Empty unless a property map was given to [`include_background_gfx!`](crate::include_background_gfx).
+    pub tile_properties: &'static [u32],
 }
 
 impl TileData {
@@ -12,6 +15,15 @@ impl TileData {
         TileData {
             tiles,
             tile_settings,
+            tile_properties: &[],
+        }
+    }
+
+    #[must_use]
+    pub const fn with_tile_properties(self, tile_properties: &'static [u32]) -> Self {
+        TileData {
+            tile_properties,
+            ..self
         }
     }
 }
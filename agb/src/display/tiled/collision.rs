@@ -0,0 +1,543 @@
+use crate::fixnum::{Num, Rect, Vector2D};
+
+/// The property value which [`TileCollision`] treats as solid by default.
+pub const COLLISION_PROPERTY: u32 = 1;
+/// The property value which [`TileCollision`] treats as deadly by default.
+pub const KILL_PROPERTY: u32 = 2;
+
+const TILE_SIZE: i32 = 8;
+
+/// Answers collision queries against a grid of 8x8 tiles, as you would draw
+/// with a [`RegularMap`](super::RegularMap) or an
+/// [`InfiniteScrolledMap`](super::InfiniteScrolledMap).
+///
+/// Each tile in the grid is an index into a property lookup, and it is the
+/// property of a tile which decides whether it is solid, deadly or anything
+/// else your game needs. By default a property of [`COLLISION_PROPERTY`] is
+/// solid and a property of [`KILL_PROPERTY`] is deadly, but these can be
+/// changed with [`set_collision_property`](TileCollision::set_collision_property)
+/// and [`set_kill_property`](TileCollision::set_kill_property).
+///
+/// Anything outside of the grid is treated as solid, so that entities can't
+/// leave the level. Use
+/// [`set_out_of_bounds_property`](TileCollision::set_out_of_bounds_property)
+/// to change this.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use agb::display::tiled::TileCollision;
+/// use agb::fixnum::{num, Vector2D};
+///
+/// // a 3x2 level with a floor along the bottom, and a spike in the middle
+/// static TILES: &[u16] = &[0, 0, 0, 1, 2, 1];
+/// static TILE_PROPERTIES: &[u32] = &[0, 1, 2];
+///
+/// # fn test() {
+/// let level = TileCollision::new(Vector2D::new(3, 2), TILES, TILE_PROPERTIES);
+///
+/// assert!(level.collides(Vector2D::new(num!(4.5), num!(12.))).is_some());
+/// assert!(level.kills(Vector2D::new(num!(12.), num!(12.))));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TileCollision<'a> {
+    dimensions: Vector2D<u32>,
+    tiles: &'a [u16],
+    properties: &'a [u32],
+
+    collision_property: u32,
+    kill_property: u32,
+    out_of_bounds_property: u32,
+}
+
+impl<'a> TileCollision<'a> {
+    /// Creates a new tile collision grid which is `dimensions` tiles in size.
+    /// `tiles` is in row major order and each entry is an index into
+    /// `properties`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tiles` doesn't contain exactly enough tiles to fill the
+    /// given dimensions.
+    #[must_use]
+    pub fn new(dimensions: Vector2D<u32>, tiles: &'a [u16], properties: &'a [u32]) -> Self {
+        assert_eq!(
+            tiles.len(),
+            (dimensions.x * dimensions.y) as usize,
+            "tiles must exactly fill the dimensions of the grid"
+        );
+
+        Self {
+            dimensions,
+            tiles,
+            properties,
+
+            collision_property: COLLISION_PROPERTY,
+            kill_property: KILL_PROPERTY,
+            out_of_bounds_property: COLLISION_PROPERTY,
+        }
+    }
+
+    /// Sets which property value counts as solid for [`collides`](Self::collides).
+    /// This also changes the out of bounds property if it was the same as the
+    /// previous collision property.
+    pub fn set_collision_property(&mut self, property: u32) -> &mut Self {
+        if self.out_of_bounds_property == self.collision_property {
+            self.out_of_bounds_property = property;
+        }
+
+        self.collision_property = property;
+
+        self
+    }
+
+    /// Sets which property value counts as deadly for [`kills`](Self::kills).
+    pub fn set_kill_property(&mut self, property: u32) -> &mut Self {
+        self.kill_property = property;
+
+        self
+    }
+
+    /// Sets the property to use for any position outside of the grid.
+    pub fn set_out_of_bounds_property(&mut self, property: u32) -> &mut Self {
+        self.out_of_bounds_property = property;
+
+        self
+    }
+
+    /// The property of the tile at tile coordinates (`x`, `y`). Tiles with no
+    /// entry in the property lookup have a property of 0. Anything outside of
+    /// the grid has the out of bounds property.
+    #[must_use]
+    pub fn property_at(&self, x: i32, y: i32) -> u32 {
+        if x < 0 || x >= self.dimensions.x as i32 || y < 0 || y >= self.dimensions.y as i32 {
+            return self.out_of_bounds_property;
+        }
+
+        let tile = self.tiles[(y * self.dimensions.x as i32 + x) as usize];
+        self.properties.get(tile as usize).copied().unwrap_or(0)
+    }
+
+    /// If the tile containing the pixel at `world_pos` is solid, returns the
+    /// area covered by that tile in pixels.
+    #[must_use]
+    pub fn collides(&self, world_pos: Vector2D<Num<i32, 8>>) -> Option<Rect<Num<i32, 8>>> {
+        let tile = tile_containing(world_pos);
+
+        (self.property_at(tile.x, tile.y) == self.collision_property).then(|| {
+            Rect::new(
+                (tile * TILE_SIZE).change_base(),
+                Vector2D::new(TILE_SIZE, TILE_SIZE).change_base(),
+            )
+        })
+    }
+
+    /// Whether the tile containing the pixel at `world_pos` is deadly.
+    #[must_use]
+    pub fn kills(&self, world_pos: Vector2D<Num<i32, 8>>) -> bool {
+        let tile = tile_containing(world_pos);
+
+        self.property_at(tile.x, tile.y) == self.kill_property
+    }
+}
+
+fn tile_containing(world_pos: Vector2D<Num<i32, 8>>) -> Vector2D<i32> {
+    Vector2D::new(
+        world_pos.x.floor().div_euclid(TILE_SIZE),
+        world_pos.y.floor().div_euclid(TILE_SIZE),
+    )
+}
+
+/// The result of a [`sweep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionInfo {
+    /// How far through the movement the first collision happened, from 0 at
+    /// the start of the movement to 1 if nothing was hit.
+    pub time: Num<i32, 8>,
+    /// The normal of the surface which was hit, pointing away from that
+    /// surface. This is zero if nothing was hit.
+    pub normal: Vector2D<i32>,
+}
+
+impl CollisionInfo {
+    /// Whether anything was hit during the sweep.
+    #[must_use]
+    pub fn collided(&self) -> bool {
+        self.normal.x != 0 || self.normal.y != 0
+    }
+}
+
+/// Moves the box `start` by `velocity`, stopping at the first solid tile in
+/// the way. Returns how far the box can actually move along with information
+/// about what it hit.
+///
+/// Unlike moving the box and then checking for collisions, which is done
+/// separately for each axis in many games, this finds every tile the box
+/// passes over during the movement. So fast moving objects won't tunnel
+/// through thin walls.
+///
+/// `solids` is called with the centre of every 8x8 tile which the box could
+/// pass over and should return the area of that tile if it is solid, exactly
+/// like [`TileCollision::collides`] does. A box which is exactly touching a
+/// solid isn't stopped unless it is moving into it, so you can slide along
+/// floors and walls. Solids which the box already overlaps are ignored.
+///
+/// When a collision does happen, the box is moved so that it exactly touches
+/// the solid along the normal. Games which want to slide along what they hit
+/// should sweep again with the remaining velocity with the component along the
+/// normal removed.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use agb::display::tiled::{sweep, TileCollision};
+/// use agb::fixnum::{num, Rect, Vector2D};
+///
+/// # fn test(level: &TileCollision) {
+/// let player = Rect::new(Vector2D::new(num!(10.), num!(20.)), Vector2D::new(num!(8.), num!(16.)));
+/// let velocity = Vector2D::new(num!(30.), num!(2.5));
+///
+/// let (movement, collision) = sweep(player, velocity, |pos| level.collides(pos));
+///
+/// let player = Rect::new(player.position + movement, player.size);
+/// if collision.collided() && collision.normal.y < 0 {
+///     // landed on the ground
+/// }
+/// # }
+/// ```
+pub fn sweep(
+    start: Rect<Num<i32, 8>>,
+    velocity: Vector2D<Num<i32, 8>>,
+    solids: impl Fn(Vector2D<Num<i32, 8>>) -> Option<Rect<Num<i32, 8>>>,
+) -> (Vector2D<Num<i32, 8>>, CollisionInfo) {
+    let end = start.position + velocity;
+    let min_tile = tile_containing(Vector2D::new(
+        start.position.x.min(end.x),
+        start.position.y.min(end.y),
+    ));
+    let max_tile = tile_containing(Vector2D::new(
+        start.position.x.max(end.x) + start.size.x,
+        start.position.y.max(end.y) + start.size.y,
+    ));
+
+    let mut movement = velocity;
+    let mut info = CollisionInfo {
+        time: 1.into(),
+        normal: (0, 0).into(),
+    };
+
+    for tile_y in min_tile.y..=max_tile.y {
+        for tile_x in min_tile.x..=max_tile.x {
+            let tile_centre =
+                Vector2D::new(tile_x, tile_y) * TILE_SIZE + (TILE_SIZE / 2, TILE_SIZE / 2).into();
+            let Some(solid) = solids(tile_centre.change_base()) else {
+                continue;
+            };
+
+            if let Some((hit_movement, hit)) = sweep_against(start, velocity, solid) {
+                if hit.time < info.time {
+                    movement = hit_movement;
+                    info = hit;
+                }
+            }
+        }
+    }
+
+    (movement, info)
+}
+
+enum AxisOverlap {
+    Never,
+    Always,
+    Between {
+        entry_distance: Num<i32, 8>,
+        entry: Num<i32, 8>,
+        exit: Num<i32, 8>,
+    },
+}
+
+/// When a line of `length` at `start` moving by `velocity` overlaps the line
+/// of `solid_length` at `solid_start`, in terms of the fraction of the movement.
+fn axis_overlap(
+    start: Num<i32, 8>,
+    length: Num<i32, 8>,
+    velocity: Num<i32, 8>,
+    solid_start: Num<i32, 8>,
+    solid_length: Num<i32, 8>,
+) -> AxisOverlap {
+    let solid_end = solid_start + solid_length;
+
+    let (entry_distance, exit_distance) = if velocity > 0.into() {
+        (solid_start - (start + length), solid_end - start)
+    } else if velocity < 0.into() {
+        (solid_end - start, solid_start - (start + length))
+    } else if start < solid_end && start + length > solid_start {
+        return AxisOverlap::Always;
+    } else {
+        return AxisOverlap::Never;
+    };
+
+    AxisOverlap::Between {
+        entry_distance,
+        entry: entry_distance / velocity,
+        exit: exit_distance / velocity,
+    }
+}
+
+fn sweep_against(
+    start: Rect<Num<i32, 8>>,
+    velocity: Vector2D<Num<i32, 8>>,
+    solid: Rect<Num<i32, 8>>,
+) -> Option<(Vector2D<Num<i32, 8>>, CollisionInfo)> {
+    let x = axis_overlap(
+        start.position.x,
+        start.size.x,
+        velocity.x,
+        solid.position.x,
+        solid.size.x,
+    );
+    let y = axis_overlap(
+        start.position.y,
+        start.size.y,
+        velocity.y,
+        solid.position.y,
+        solid.size.y,
+    );
+
+    let x_hit = |entry_distance, entry| {
+        (
+            Vector2D::new(entry_distance, velocity.y * entry),
+            CollisionInfo {
+                time: entry,
+                normal: (-velocity.x.to_raw().signum(), 0).into(),
+            },
+        )
+    };
+    let y_hit = |entry_distance, entry| {
+        (
+            Vector2D::new(velocity.x * entry, entry_distance),
+            CollisionInfo {
+                time: entry,
+                normal: (0, -velocity.y.to_raw().signum()).into(),
+            },
+        )
+    };
+
+    let (exit, (movement, info)) = match (x, y) {
+        (AxisOverlap::Never, _)
+        | (_, AxisOverlap::Never)
+        | (AxisOverlap::Always, AxisOverlap::Always) => return None,
+        (
+            AxisOverlap::Between {
+                entry_distance,
+                entry,
+                exit,
+            },
+            AxisOverlap::Always,
+        ) => (exit, x_hit(entry_distance, entry)),
+        (
+            AxisOverlap::Always,
+            AxisOverlap::Between {
+                entry_distance,
+                entry,
+                exit,
+            },
+        ) => (exit, y_hit(entry_distance, entry)),
+        (
+            AxisOverlap::Between {
+                entry_distance: x_entry_distance,
+                entry: x_entry,
+                exit: x_exit,
+            },
+            AxisOverlap::Between {
+                entry_distance: y_entry_distance,
+                entry: y_entry,
+                exit: y_exit,
+            },
+        ) => {
+            let exit = x_exit.min(y_exit);
+            if x_entry > y_entry {
+                (exit, x_hit(x_entry_distance, x_entry))
+            } else {
+                (exit, y_hit(y_entry_distance, y_entry))
+            }
+        }
+    };
+
+    (info.time >= 0.into() && info.time < 1.into() && info.time < exit).then_some((movement, info))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixnum::num;
+
+    use super::*;
+
+    // 0 1 2
+    // 1 0 3
+    static TILES: &[u16] = &[0, 1, 2, 1, 0, 3];
+    static PROPERTIES: &[u32] = &[0, COLLISION_PROPERTY, KILL_PROPERTY];
+
+    fn level() -> TileCollision<'static> {
+        TileCollision::new(Vector2D::new(3, 2), TILES, PROPERTIES)
+    }
+
+    #[test_case]
+    fn property_at_in_bounds_tiles(_gba: &mut crate::Gba) {
+        let level = level();
+
+        assert_eq!(level.property_at(0, 0), 0);
+        assert_eq!(level.property_at(1, 0), COLLISION_PROPERTY);
+        assert_eq!(level.property_at(2, 0), KILL_PROPERTY);
+        assert_eq!(level.property_at(0, 1), COLLISION_PROPERTY);
+        // tile 3 has no entry in the property lookup
+        assert_eq!(level.property_at(2, 1), 0);
+    }
+
+    #[test_case]
+    fn out_of_bounds_tiles_are_solid(_gba: &mut crate::Gba) {
+        let mut level = level();
+
+        for (x, y) in [(-1, 0), (0, -1), (3, 0), (0, 2), (3, 2), (-1, -1)] {
+            assert_eq!(level.property_at(x, y), COLLISION_PROPERTY, "({x}, {y})");
+        }
+
+        level.set_out_of_bounds_property(KILL_PROPERTY);
+        assert!(level.kills(Vector2D::new(num!(-0.5), num!(4.))));
+        assert!(level
+            .collides(Vector2D::new(num!(-0.5), num!(4.)))
+            .is_none());
+    }
+
+    #[test_case]
+    fn collides_at_tile_boundaries(_gba: &mut crate::Gba) {
+        let level = level();
+
+        // the last pixel of tile (0, 0) is empty, the next one is in the solid tile (1, 0)
+        assert_eq!(level.collides(Vector2D::new(num!(7.99), num!(0.))), None);
+        assert_eq!(
+            level.collides(Vector2D::new(num!(8.), num!(0.))),
+            Some(Rect::new(
+                Vector2D::new(8, 0).change_base(),
+                Vector2D::new(8, 8).change_base()
+            ))
+        );
+
+        // the right edge of the grid is out of bounds, and so solid
+        assert_eq!(
+            level.collides(Vector2D::new(num!(24.), num!(8.))),
+            Some(Rect::new(
+                Vector2D::new(24, 8).change_base(),
+                Vector2D::new(8, 8).change_base()
+            ))
+        );
+        assert_eq!(level.collides(Vector2D::new(num!(23.99), num!(8.))), None);
+
+        // just above the grid is out of bounds
+        assert!(level
+            .collides(Vector2D::new(num!(1.), num!(-0.01)))
+            .is_some());
+    }
+
+    #[test_case]
+    fn kills_only_on_kill_tiles(_gba: &mut crate::Gba) {
+        let level = level();
+
+        assert!(level.kills(Vector2D::new(num!(16.), num!(7.5))));
+        assert!(!level.kills(Vector2D::new(num!(16.), num!(8.))));
+        assert!(!level.kills(Vector2D::new(num!(8.), num!(0.))));
+    }
+
+    #[test_case]
+    fn changing_the_collision_property_moves_out_of_bounds(_gba: &mut crate::Gba) {
+        let mut level = level();
+        level.set_collision_property(KILL_PROPERTY);
+
+        assert!(level.collides(Vector2D::new(num!(16.), num!(0.))).is_some());
+        assert!(level.collides(Vector2D::new(num!(8.), num!(0.))).is_none());
+        assert_eq!(level.property_at(-1, 0), KILL_PROPERTY);
+    }
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect<Num<i32, 8>> {
+        Rect::new(
+            Vector2D::new(x, y).change_base(),
+            Vector2D::new(width, height).change_base(),
+        )
+    }
+
+    #[test_case]
+    fn fast_projectile_does_not_tunnel_through_thin_wall(_gba: &mut crate::Gba) {
+        // a single tile wall from x = 40 to x = 48
+        static CORRIDOR: &[u16] = &[0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let level = TileCollision::new(Vector2D::new(16, 1), CORRIDOR, PROPERTIES);
+
+        // this moves further than the wall is wide, and would end up past it
+        let velocity = Vector2D::new(num!(100.), num!(0.));
+        let (movement, collision) = sweep(rect(0, 0, 8, 8), velocity, |pos| level.collides(pos));
+
+        assert_eq!(movement, Vector2D::new(num!(32.), num!(0.)));
+        assert_eq!(collision.normal, Vector2D::new(-1, 0));
+        assert!(collision.time > num!(0.31) && collision.time <= num!(0.32));
+
+        // and the same going the other way
+        let (movement, collision) = sweep(rect(80, 0, 8, 8), -velocity, |pos| level.collides(pos));
+
+        assert_eq!(movement, Vector2D::new(num!(-32.), num!(0.)));
+        assert_eq!(collision.normal, Vector2D::new(1, 0));
+    }
+
+    #[test_case]
+    fn sweep_without_collision_moves_the_whole_way(_gba: &mut crate::Gba) {
+        static EMPTY: &[u16] = &[0; 16];
+        let level = TileCollision::new(Vector2D::new(4, 4), EMPTY, PROPERTIES);
+
+        let velocity = Vector2D::new(num!(12.5), num!(-3.));
+        let (movement, collision) = sweep(rect(4, 8, 8, 8), velocity, |pos| level.collides(pos));
+
+        assert_eq!(movement, velocity);
+        assert!(!collision.collided());
+        assert_eq!(collision.time, num!(1.));
+    }
+
+    #[test_case]
+    fn sweep_lands_on_floor_and_slides_along_it(_gba: &mut crate::Gba) {
+        // floor along the bottom row
+        static ROOM: &[u16] = &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1];
+        let level = TileCollision::new(Vector2D::new(4, 4), ROOM, PROPERTIES);
+
+        let (movement, collision) = sweep(
+            rect(0, 0, 8, 8),
+            Vector2D::new(num!(5.), num!(40.)),
+            |pos| level.collides(pos),
+        );
+
+        assert_eq!(movement.y, num!(16.));
+        assert!(movement.x > num!(1.9) && movement.x <= num!(2.));
+        assert_eq!(collision.normal, Vector2D::new(0, -1));
+
+        // once on the floor, moving sideways isn't blocked by it
+        let (movement, collision) = sweep(
+            rect(0, 16, 8, 8),
+            Vector2D::new(num!(5.), num!(0.)),
+            |pos| level.collides(pos),
+        );
+
+        assert_eq!(movement, Vector2D::new(num!(5.), num!(0.)));
+        assert!(!collision.collided());
+
+        // but moving into it is
+        let (movement, collision) = sweep(
+            rect(0, 16, 8, 8),
+            Vector2D::new(num!(5.), num!(1.)),
+            |pos| level.collides(pos),
+        );
+
+        assert_eq!(movement, Vector2D::new(num!(0.), num!(0.)));
+        assert_eq!(collision.normal, Vector2D::new(0, -1));
+    }
+}
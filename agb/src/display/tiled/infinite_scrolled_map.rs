@@ -7,7 +7,7 @@ use super::{
 
 use crate::{
     display::{self, Priority},
-    fixnum::{Rect, Vector2D},
+    fixnum::{Num, Rect, Vector2D},
 };
 
 /// The infinite scrolled map allows you to create a game space larger than a single GBA background.
@@ -17,6 +17,8 @@ use crate::{
 /// onto and a function which takes a `Vector2D<i32>` position and returns which tile should be rendered there.
 ///
 /// The passed function should handle being out of bounds, as the scrolled map does buffer around the edges slightly.
+/// If your map is a fixed size, you can instead use [`.set_bounds()`](`InfiniteScrolledMap::set_bounds`) so that
+/// the function is only ever called with positions inside the map.
 ///
 /// Note that nothing is copied to video memory until you call [`.commit()`](`InfiniteScrolledMap::commit`), and you
 /// must call [`.clear()`](`InfiniteScrolledMap::clear`) before dropping the infinite scrolled map or you will leak video RAM.
@@ -75,8 +77,11 @@ use crate::{
 pub struct InfiniteScrolledMap<'a> {
     map: MapLoan<'a, RegularMap>,
     tile: Box<dyn Fn(Vector2D<i32>) -> (&'a TileSet<'a>, TileSetting) + 'a>,
+    bounds: Option<(Vector2D<i32>, MapEdge<'a>)>,
 
     current_pos: Vector2D<i32>,
+    // the fraction of a pixel moved by scroll_by which hasn't made it into current_pos yet
+    sub_pixel: Vector2D<Num<i32, 8>>,
     offset: Vector2D<i32>,
 
     copied_up_to: i32,
@@ -88,6 +93,17 @@ pub enum PartialUpdateStatus {
     Continue,
 }
 
+/// What an [`InfiniteScrolledMap`] shows past the edges of a map with a fixed size, set with
+/// [`.set_bounds()`](`InfiniteScrolledMap::set_bounds`).
+#[derive(Clone, Copy)]
+pub enum MapEdge<'a> {
+    /// The map repeats forever in every direction, so scrolling off the right hand side brings
+    /// you back to the left hand side.
+    Wrap,
+    /// Every tile outside of the map is the given tile, for example a blank tile or a wall.
+    Fill(&'a TileSet<'a>, TileSetting),
+}
+
 impl<'a> InfiniteScrolledMap<'a> {
     /// Creates a new infinite scrolled map wrapping the provided background using the given function to
     /// position tiles.
@@ -103,12 +119,52 @@ impl<'a> InfiniteScrolledMap<'a> {
         Self {
             map,
             tile,
+            bounds: None,
             current_pos: (0, 0).into(),
+            sub_pixel: Vector2D::default(),
             offset: (0, 0).into(),
             copied_up_to: 0,
         }
     }
 
+    /// Makes the map `size` tiles big, with the top left tile at (0, 0), and sets what is shown
+    /// past its edges. After this, the tile function is only ever called with positions inside
+    /// the map, so it doesn't need to handle being out of bounds itself.
+    ///
+    /// This is separate from the size of the background itself, which the GBA always wraps
+    /// around. The background is only used to hold the tiles which are on screen, so the map can
+    /// be any size, and [`MapEdge::Wrap`] repeats the map every `size` tiles rather than every 32
+    /// or 64 tiles like the hardware does.
+    ///
+    /// This only changes tiles drawn after it is called, so call it before
+    /// [`.init()`](`InfiniteScrolledMap::init`).
+    ///
+    /// Panics if either dimension of `size` isn't positive.
+    pub fn set_bounds(&mut self, size: Vector2D<i32>, edge: MapEdge<'a>) {
+        assert!(
+            size.x > 0 && size.y > 0,
+            "map must be at least 1 tile in each direction, got {size:?}"
+        );
+
+        self.bounds = Some((size, edge));
+    }
+
+    fn tile_at(&self, pos: Vector2D<i32>) -> (&'a TileSet<'a>, TileSetting) {
+        match self.bounds {
+            None => (self.tile)(pos),
+            Some((size, MapEdge::Wrap)) => {
+                (self.tile)((pos.x.rem_euclid(size.x), pos.y.rem_euclid(size.y)).into())
+            }
+            Some((size, MapEdge::Fill(tileset, tile_setting))) => {
+                if (0..size.x).contains(&pos.x) && (0..size.y).contains(&pos.y) {
+                    (self.tile)(pos)
+                } else {
+                    (tileset, tile_setting)
+                }
+            }
+        }
+    }
+
     /// Initialises the map and fills it, calling the between_updates occasionally to allow you to ensure that
     /// music keeps playing without interruption.
     ///
@@ -249,6 +305,7 @@ impl<'a> InfiniteScrolledMap<'a> {
         pos: Vector2D<i32>,
     ) -> PartialUpdateStatus {
         self.current_pos = pos;
+        self.sub_pixel = Vector2D::default();
 
         let x_start = div_floor(self.current_pos.x, 8);
         let y_start = div_floor(self.current_pos.y, 8);
@@ -268,8 +325,7 @@ impl<'a> InfiniteScrolledMap<'a> {
             ((y_start + copy_from)..(y_end.min(y_start + copy_from + ROWS_TO_COPY))).enumerate()
         {
             for (x_idx, x) in (x_start..x_end).enumerate() {
-                let pos = (x, y).into();
-                let (tileset, tile_setting) = (self.tile)(pos);
+                let (tileset, tile_setting) = self.tile_at((x, y).into());
 
                 self.map.set_tile(
                     vram,
@@ -291,12 +347,17 @@ impl<'a> InfiniteScrolledMap<'a> {
 
     /// Set the top left corner of the map. You may need to call this method multiple times if
     /// [`PartialUpdateStatus::Continue`] is returned.
+    ///
+    /// Only the tiles which scroll into view are requested, so moving by a few pixels at a time
+    /// is cheap. Moving by more than 10 tiles redraws the whole map, which is when
+    /// [`PartialUpdateStatus::Continue`] can be returned.
     pub fn set_pos(
         &mut self,
         vram: &mut VRamManager,
         new_pos: Vector2D<i32>,
     ) -> PartialUpdateStatus {
         let old_pos = self.current_pos;
+        self.sub_pixel = Vector2D::default();
 
         let difference = new_pos - old_pos;
 
@@ -371,7 +432,7 @@ impl<'a> InfiniteScrolledMap<'a> {
             .iter()
             .chain(horizontal_rect_to_update.iter())
         {
-            let (tileset, tile_setting) = (self.tile)((tile_x, tile_y).into());
+            let (tileset, tile_setting) = self.tile_at((tile_x, tile_y).into());
 
             self.map.set_tile(
                 vram,
@@ -392,6 +453,35 @@ impl<'a> InfiniteScrolledMap<'a> {
         PartialUpdateStatus::Done
     }
 
+    /// Moves the map by `delta` pixels relative to where it currently is, returning the same as
+    /// [`.set_pos()`](`InfiniteScrolledMap::set_pos`).
+    ///
+    /// The map can only be displayed at whole pixel positions, so the fractional part of the
+    /// movement is remembered and added on to the next call. This means that moving by lots of
+    /// small amounts ends up in the same place as moving by their sum, rather than drifting
+    /// because each movement was rounded. Calling [`.set_pos()`](`InfiniteScrolledMap::set_pos`)
+    /// or [`.init()`](`InfiniteScrolledMap::init`) forgets the fractional part.
+    pub fn scroll_by(
+        &mut self,
+        vram: &mut VRamManager,
+        delta: Vector2D<Num<i32, 8>>,
+    ) -> PartialUpdateStatus {
+        let (whole_pixels, sub_pixel) = accumulate_sub_pixel(self.sub_pixel, delta);
+
+        let status = self.set_pos(vram, self.current_pos + whole_pixels);
+        self.sub_pixel = sub_pixel;
+
+        status
+    }
+
+    /// Returns the range of tiles which are currently visible on screen, in tile
+    /// coordinates of the underlying tile source. This includes the tiles on the
+    /// border which are only partially visible.
+    #[must_use]
+    pub fn visible_tile_range(&self) -> Rect<i32> {
+        visible_tile_range(self.current_pos)
+    }
+
     /// Sets wether the map is visible  
     /// Use [is_visible](Self::is_visible) to get the value
     pub fn set_visible(&mut self, visible: bool) {
@@ -420,7 +510,8 @@ impl<'a> InfiniteScrolledMap<'a> {
         self.map.priority()
     }
 
-    /// Copies data to vram. Needs to be called during vblank if possible
+    /// Copies data to vram. Needs to be called during vblank if possible. Only the tiles which
+    /// changed since the last commit are copied, unless a lot of them have changed.
     pub fn commit(&mut self, vram: &mut VRamManager) {
         self.map.commit(vram);
     }
@@ -443,6 +534,28 @@ impl<'a> InfiniteScrolledMap<'a> {
     }
 }
 
+fn visible_tile_range(pos: Vector2D<i32>) -> Rect<i32> {
+    let start = Vector2D::new(div_floor(pos.x, 8), div_floor(pos.y, 8));
+    let end = Vector2D::new(
+        div_ceil(pos.x + display::WIDTH, 8),
+        div_ceil(pos.y + display::HEIGHT, 8),
+    );
+
+    Rect::new(start, end - start)
+}
+
+/// Splits the movement into whole pixels and the remaining fraction of a pixel, which is always
+/// between 0 (inclusive) and 1 (exclusive)
+fn accumulate_sub_pixel(
+    sub_pixel: Vector2D<Num<i32, 8>>,
+    delta: Vector2D<Num<i32, 8>>,
+) -> (Vector2D<i32>, Vector2D<Num<i32, 8>>) {
+    let total = sub_pixel + delta;
+    let whole_pixels = total.floor();
+
+    (whole_pixels, total - whole_pixels.change_base())
+}
+
 fn div_floor(x: i32, y: i32) -> i32 {
     if x > 0 && y < 0 {
         (x - 1) / y - 1
@@ -462,3 +575,241 @@ fn div_ceil(x: i32, y: i32) -> i32 {
         x / y
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::cell::RefCell;
+
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{
+        display::tiled::{RegularBackgroundSize, Tile, TileFormat},
+        fixnum::num,
+    };
+
+    #[test_case]
+    fn visible_tile_range_at_tile_boundary(_gba: &mut crate::Gba) {
+        assert_eq!(
+            visible_tile_range((16, -8).into()),
+            Rect::new((2, -1).into(), (30, 20).into())
+        );
+    }
+
+    #[test_case]
+    fn visible_tile_range_includes_partial_tiles(_gba: &mut crate::Gba) {
+        assert_eq!(
+            visible_tile_range((-3, 5).into()),
+            Rect::new((-1, 0).into(), (31, 21).into())
+        );
+    }
+
+    #[test_case]
+    fn small_scrolls_accumulate_without_drifting(_gba: &mut crate::Gba) {
+        let deltas: [Vector2D<Num<i32, 8>>; 3] = [
+            (num!(0.3), num!(-0.7)).into(),
+            (num!(0.05), num!(0.2)).into(),
+            (num!(-0.15), num!(-0.01)).into(),
+        ];
+
+        let mut position = Vector2D::<i32>::default();
+        let mut sub_pixel = Vector2D::default();
+        let mut expected = Vector2D::<Num<i32, 8>>::default();
+
+        for i in 0..1000 {
+            let delta = deltas[i % deltas.len()];
+            expected += delta;
+
+            let (whole_pixels, remainder) = accumulate_sub_pixel(sub_pixel, delta);
+            position += whole_pixels;
+            sub_pixel = remainder;
+
+            assert!(sub_pixel.x >= 0.into() && sub_pixel.x < 1.into());
+            assert!(sub_pixel.y >= 0.into() && sub_pixel.y < 1.into());
+            assert_eq!(position.change_base() + sub_pixel, expected);
+        }
+
+        assert_eq!(position, expected.floor());
+    }
+
+    #[test_case]
+    fn scroll_by_moves_the_map_by_whole_pixels(gba: &mut crate::Gba) {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+
+        let mut map = InfiniteScrolledMap::new(
+            gfx.background(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            Box::new(|_| (&tile_set, dynamic_tile.tile_setting())),
+        );
+
+        map.init(&mut vram, (10, 20).into(), &mut || {});
+        let initial_scroll = map.map.scroll_pos();
+
+        for _ in 0..10 {
+            map.scroll_by(&mut vram, (num!(0.25), num!(-0.75)).into());
+        }
+
+        // moved by (2.5, -7.5), and the half pixel left over isn't shown yet
+        assert_eq!(map.current_pos, (12, 12).into());
+        assert_eq!(map.map.scroll_pos(), initial_scroll + (2i16, -8).into());
+
+        map.scroll_by(&mut vram, (num!(0.5), num!(0.5)).into());
+        assert_eq!(map.current_pos, (13, 13).into());
+        assert_eq!(map.map.scroll_pos(), initial_scroll + (3i16, -7).into());
+
+        // setting the position forgets the fraction of a pixel
+        map.scroll_by(&mut vram, (num!(0.75), num!(0.75)).into());
+        map.set_pos(&mut vram, (0, 0).into());
+        map.scroll_by(&mut vram, (num!(0.5), num!(0.5)).into());
+        assert_eq!(map.current_pos, (0, 0).into());
+
+        map.clear(&mut vram);
+        drop(map);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+    }
+
+    /// The tile the background holds for the given position in the map, which must be on screen
+    fn tile_on_screen(map: &InfiniteScrolledMap<'_>, pos: Vector2D<i32>) -> Tile {
+        let size = map.map.size();
+
+        map.map.tile(
+            (
+                size.tile_pos_x(pos.x - map.offset.x),
+                size.tile_pos_y(pos.y - map.offset.y),
+            )
+                .into(),
+        )
+    }
+
+    fn tiles_requested_past_the_edges(
+        gba: &mut crate::Gba,
+        edge: impl for<'a> Fn(&'a TileSet<'a>, TileSetting) -> MapEdge<'a>,
+        inspect: impl FnOnce(&InfiniteScrolledMap<'_>),
+    ) -> Vec<Vector2D<i32>> {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+        let requested = RefCell::new(Vec::new());
+
+        let mut map = InfiniteScrolledMap::new(
+            gfx.background(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            Box::new(|pos| {
+                requested.borrow_mut().push(pos);
+                (&tile_set, dynamic_tile.tile_setting())
+            }),
+        );
+
+        // a 40x30 tile map, scrolled so that it goes past the bottom right corner
+        map.set_bounds(
+            (40, 30).into(),
+            edge(&tile_set, dynamic_tile.tile_setting()),
+        );
+        map.init(&mut vram, (200, 150).into(), &mut || {});
+        map.set_pos(&mut vram, (240, 180).into());
+        inspect(&map);
+
+        map.clear(&mut vram);
+        drop(map);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+
+        requested.into_inner()
+    }
+
+    fn is_in_map(pos: Vector2D<i32>) -> bool {
+        (0..40).contains(&pos.x) && (0..30).contains(&pos.y)
+    }
+
+    #[test_case]
+    fn small_scrolls_only_request_the_newly_visible_tiles(gba: &mut crate::Gba) {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+        let requested = RefCell::new(Vec::new());
+
+        let mut map = InfiniteScrolledMap::new(
+            gfx.background(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            Box::new(|pos| {
+                requested.borrow_mut().push(pos);
+                (&tile_set, dynamic_tile.tile_setting())
+            }),
+        );
+
+        map.init(&mut vram, (7, 3).into(), &mut || {});
+        requested.borrow_mut().clear();
+
+        // still within the same tiles, so nothing new is visible
+        map.set_pos(&mut vram, (6, 3).into());
+        assert_eq!(*requested.borrow(), []);
+
+        // the next column of tiles comes into view on the right
+        map.set_pos(&mut vram, (8, 3).into());
+        let column = requested.borrow_mut().split_off(0);
+        assert_eq!(column.len(), 22);
+        assert!(column.iter().all(|pos| pos.x == 31));
+
+        map.set_pos(&mut vram, (8, 9).into());
+        let row = requested.borrow_mut().split_off(0);
+        assert_eq!(row.len(), 32);
+        assert!(row.iter().all(|pos| pos.y == 21));
+
+        map.clear(&mut vram);
+        drop(map);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+    }
+
+    #[test_case]
+    fn wrapping_maps_repeat_past_their_edges(gba: &mut crate::Gba) {
+        let requested = tiles_requested_past_the_edges(gba, |_, _| MapEdge::Wrap, |_| {});
+
+        assert!(requested.iter().copied().all(is_in_map));
+        // the top left of the map is visible again past the bottom right
+        assert!(requested.contains(&(0, 0).into()));
+        assert!(requested.contains(&(39, 29).into()));
+    }
+
+    #[test_case]
+    fn filled_maps_only_ask_for_tiles_inside_the_map(gba: &mut crate::Gba) {
+        // the same tile as inside the map but flipped, so the two can be told apart
+        let requested = tiles_requested_past_the_edges(
+            gba,
+            |tile_set, tile_setting| MapEdge::Fill(tile_set, tile_setting.hflip(true).vflip(true)),
+            |map| {
+                let visible = map.visible_tile_range();
+                assert!(visible.iter().any(|(x, y)| !is_in_map((x, y).into())));
+
+                for (x, y) in visible.iter() {
+                    let flips = (tile_on_screen(map, (x, y).into()).0 >> 10) & 0b11;
+                    let expected = if is_in_map((x, y).into()) { 0 } else { 0b11 };
+
+                    assert_eq!(flips, expected, "wrong tile at ({x}, {y})");
+                }
+            },
+        );
+
+        assert!(requested.iter().copied().all(is_in_map));
+        assert!(requested.contains(&(39, 29).into()));
+        assert!(
+            !requested.contains(&(0, 0).into()),
+            "the top left is off screen and shouldn't wrap around"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use crate::display::affine::AffineMatrixBackground;
 use crate::display::tile_data::TileData;
 use crate::display::{Priority, DISPLAY_CONTROL};
 use crate::dma;
-use crate::fixnum::Vector2D;
+use crate::fixnum::{Rect, Vector2D};
 use crate::memory_mapped::MemoryMapped;
 
 use super::{
@@ -127,6 +127,10 @@ impl TiledMap for RegularMap {
 
             *tile = Default::default();
         }
+
+        // the freed tiles may be reused, so none of the old ones can be left in video memory
+        self.tiles_dirty = true;
+        self.changed_tiles.clear();
     }
 
     /// Sets wether the map is visible
@@ -157,7 +161,17 @@ impl TiledMap for RegularMap {
                     self.map_size().num_tiles(),
                 );
             }
+        } else {
+            for &pos in &self.changed_tiles {
+                let pos = pos as usize;
+                unsafe {
+                    screenblock_memory
+                        .add(pos)
+                        .write_volatile(self.tiles[pos].0);
+                }
+            }
         }
+        self.changed_tiles.clear();
 
         let tile_colour_flag: u16 = (self.colours() == TileFormat::EightBpp).into();
 
@@ -191,10 +205,16 @@ pub struct RegularMap {
 
     tiles: Vec<Tile>,
     tiles_dirty: bool,
+    // tiles which have changed since the last commit, so that only those get copied when just a
+    // few change, such as when scrolling. Unused while tiles_dirty is set.
+    changed_tiles: Vec<u16>,
 }
 
 pub(crate) const TRANSPARENT_TILE_INDEX: u16 = (1 << 10) - 1;
 
+// past this many changed tiles, copying the whole map in one go is quicker
+const MAX_CHANGED_TILES: usize = 128;
+
 impl TiledMapTypes for RegularMap {
     type Size = RegularBackgroundSize;
 }
@@ -247,6 +267,7 @@ impl RegularMap {
 
             tiles: vec![Default::default(); size.num_tiles()],
             tiles_dirty: true,
+            changed_tiles: Vec::new(),
         }
     }
 
@@ -278,6 +299,76 @@ impl RegularMap {
         }
     }
 
+    /// Sets every tile in the background to the same tile.
+    pub fn fill_with_tile(
+        &mut self,
+        vram: &mut VRamManager,
+        tileset: &TileSet<'_>,
+        tile_setting: TileSetting,
+    ) {
+        let size = self.map_size();
+        let rect = Rect::new(
+            Vector2D::new(0, 0),
+            (size.width() as u16, size.height() as u16).into(),
+        );
+
+        self.fill_rect(vram, rect, tileset, tile_setting);
+    }
+
+    /// Sets every tile within `rect` to the same tile. Positions outside of the
+    /// background wrap around in the same way as [`set_tile`](Self::set_tile).
+    pub fn fill_rect(
+        &mut self,
+        vram: &mut VRamManager,
+        rect: Rect<u16>,
+        tileset: &TileSet<'_>,
+        tile_setting: TileSetting,
+    ) {
+        assert_eq!(
+            tileset.format(),
+            self.colours(),
+            "Cannot set a {:?} colour tile on a {:?} colour background",
+            tileset.format(),
+            self.colours()
+        );
+
+        let colours = self.colours();
+        let size = self.map_size();
+
+        let tile_index = tile_setting.index();
+
+        // Only look the tile up once. We hold on to this reference until the end so that
+        // replacing a tile with itself never frees it in the middle of the fill.
+        let new_tile = if tile_index != TRANSPARENT_TILE_INDEX {
+            let new_tile_idx = vram.add_tile(tileset, tile_index);
+            Tile::new(new_tile_idx, tile_setting)
+        } else {
+            Tile::default()
+        };
+
+        for (x, y) in rect.iter() {
+            let pos = size.gba_offset((x, y).into());
+
+            let old_tile = self.tiles[pos];
+            if old_tile != Tile::default() {
+                vram.remove_tile(old_tile.tile_index(colours));
+            }
+
+            if new_tile != Tile::default() {
+                vram.add_tile_reference(new_tile.tile_index(colours));
+            }
+
+            if old_tile != new_tile {
+                self.tiles[pos] = new_tile;
+                self.mark_tile_changed(pos);
+            }
+        }
+
+        if new_tile != Tile::default() {
+            vram.remove_tile(new_tile.tile_index(colours));
+        }
+    }
+
     pub fn set_tile(
         &mut self,
         vram: &mut VRamManager,
@@ -324,7 +415,20 @@ impl RegularMap {
         }
 
         self.tiles_mut()[pos] = new_tile;
-        *self.tiles_dirty() = true;
+        self.mark_tile_changed(pos);
+    }
+
+    fn mark_tile_changed(&mut self, pos: usize) {
+        if self.tiles_dirty {
+            return;
+        }
+
+        if self.changed_tiles.len() < MAX_CHANGED_TILES {
+            self.changed_tiles.push(pos as u16);
+        } else {
+            self.tiles_dirty = true;
+            self.changed_tiles.clear();
+        }
     }
 
     /// Returns the latest map priority set  
@@ -351,11 +455,21 @@ impl RegularMap {
         self.scroll = pos.into();
     }
 
+    #[cfg(test)]
+    pub(super) fn tile(&self, pos: Vector2D<u16>) -> Tile {
+        self.tiles[self.map_size().gba_offset(pos)]
+    }
+
     #[must_use]
     pub fn x_scroll_dma(&self) -> dma::DmaControllable<i16> {
         dma::DmaControllable::new(self.x_register().as_ptr())
     }
 
+    #[must_use]
+    pub fn y_scroll_dma(&self) -> dma::DmaControllable<i16> {
+        dma::DmaControllable::new(self.y_register().as_ptr())
+    }
+
     fn x_register(&self) -> MemoryMapped<i16> {
         unsafe { MemoryMapped::new(0x0400_0010 + 4 * self.background_id as usize) }
     }
@@ -546,3 +660,154 @@ mod private {
     impl Sealed for super::RegularMap {}
     impl Sealed for super::AffineMap {}
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test_case]
+    fn fill_with_tile_sets_every_tile(gba: &mut crate::Gba) {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let mut map = gfx.background(
+            Priority::P0,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+
+        map.fill_with_tile(&mut vram, &tile_set, dynamic_tile.tile_setting());
+
+        let filled_tile = map.tiles[0];
+        assert_ne!(filled_tile, Tile::default());
+        for pos in [(0u16, 0u16), (31, 0), (0, 31), (31, 31), (12, 17)] {
+            let offset = map.map_size().gba_offset(pos.into());
+            assert_eq!(map.tiles[offset], filled_tile);
+        }
+
+        map.fill_rect(
+            &mut vram,
+            Rect::new(Vector2D::new(4, 4), Vector2D::new(2, 3)),
+            &tile_set,
+            TileSetting::BLANK,
+        );
+
+        for (pos, expected) in [
+            ((4u16, 4u16), Tile::default()),
+            ((5, 6), Tile::default()),
+            ((6, 4), filled_tile),
+            ((4, 7), filled_tile),
+        ] {
+            let offset = map.map_size().gba_offset(pos.into());
+            assert_eq!(map.tiles[offset], expected);
+        }
+
+        map.clear(&mut vram);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+    }
+
+    #[test_case]
+    fn commit_only_copies_the_tiles_which_changed(gba: &mut crate::Gba) {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let mut map = gfx.background(
+            Priority::P0,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+
+        map.fill_with_tile(&mut vram, &tile_set, dynamic_tile.tile_setting());
+        map.commit(&mut vram);
+
+        let screenblock_at = |map: &RegularMap, pos: (u16, u16)| unsafe {
+            map.screenblock_memory()
+                .add(map.map_size().gba_offset(pos.into()))
+        };
+
+        // isn't in the map, so this shows whether the tile was copied over
+        const MARKER: u16 = 0x1234;
+        unsafe { screenblock_at(&map, (20, 10)).write_volatile(MARKER) };
+
+        map.set_tile(&mut vram, (3u16, 4u16), &tile_set, TileSetting::BLANK);
+        map.commit(&mut vram);
+
+        assert_eq!(unsafe { screenblock_at(&map, (3, 4)).read_volatile() }, 0);
+        assert_eq!(
+            unsafe { screenblock_at(&map, (20, 10)).read_volatile() },
+            MARKER,
+            "unchanged tiles shouldn't be copied"
+        );
+
+        // changing lots of tiles copies the whole map instead
+        for x in 0..32u16 {
+            for y in 0..6u16 {
+                map.set_tile(&mut vram, (x, y), &tile_set, TileSetting::BLANK);
+            }
+        }
+        map.commit(&mut vram);
+
+        assert_ne!(
+            unsafe { screenblock_at(&map, (20, 10)).read_volatile() },
+            MARKER,
+            "every tile should have been copied"
+        );
+
+        map.clear(&mut vram);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+    }
+
+    #[test_case]
+    fn commit_after_clear_removes_the_old_tiles(gba: &mut crate::Gba) {
+        let (gfx, mut vram) = gba.display.video.tiled0();
+
+        let mut map = gfx.background(
+            Priority::P0,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        let dynamic_tile = vram.new_dynamic_tile().fill_with(1);
+        let tile_set = dynamic_tile.tile_set();
+
+        map.set_tile(
+            &mut vram,
+            (20u16, 10u16),
+            &tile_set,
+            dynamic_tile.tile_setting(),
+        );
+        map.commit(&mut vram);
+
+        let screenblock_at = |map: &RegularMap, pos: (u16, u16)| unsafe {
+            map.screenblock_memory()
+                .add(map.map_size().gba_offset(pos.into()))
+        };
+        assert_ne!(unsafe { screenblock_at(&map, (20, 10)).read_volatile() }, 0);
+
+        map.clear(&mut vram);
+        map.set_tile(
+            &mut vram,
+            (3u16, 4u16),
+            &tile_set,
+            dynamic_tile.tile_setting(),
+        );
+        map.commit(&mut vram);
+
+        assert_eq!(
+            unsafe { screenblock_at(&map, (20, 10)).read_volatile() },
+            0,
+            "cleared tiles should be removed from video memory"
+        );
+        assert_ne!(unsafe { screenblock_at(&map, (3, 4)).read_volatile() }, 0);
+
+        map.clear(&mut vram);
+        vram.remove_dynamic_tile(dynamic_tile);
+        vram.gc();
+    }
+}
@@ -1,3 +1,4 @@
+mod collision;
 mod infinite_scrolled_map;
 mod map;
 mod tiled0;
@@ -8,8 +9,9 @@ mod vram_manager;
 use crate::bitarray::Bitarray;
 use crate::display::Priority;
 use agb_fixnum::Vector2D;
+pub use collision::{sweep, CollisionInfo, TileCollision, COLLISION_PROPERTY, KILL_PROPERTY};
 use core::cell::RefCell;
-pub use infinite_scrolled_map::{InfiniteScrolledMap, PartialUpdateStatus};
+pub use infinite_scrolled_map::{InfiniteScrolledMap, MapEdge, PartialUpdateStatus};
 pub use map::{AffineMap, MapLoan, RegularMap, TiledMap};
 pub use tiled0::Tiled0;
 pub use tiled1::Tiled1;
@@ -188,6 +190,20 @@ impl TileSetting {
         Self(self.0 ^ ((should_flip as u16) << 11))
     }
 
+    /// Sets whether this tile is flipped horizontally, regardless of its current state.
+    /// Unlike [`hflip`](Self::hflip), calling this twice with `true` leaves the tile flipped.
+    #[must_use]
+    pub const fn with_hflip(self, hflip: bool) -> Self {
+        Self((self.0 & !(1 << 10)) | ((hflip as u16) << 10))
+    }
+
+    /// Sets whether this tile is flipped vertically, regardless of its current state.
+    /// Unlike [`vflip`](Self::vflip), calling this twice with `true` leaves the tile flipped.
+    #[must_use]
+    pub const fn with_vflip(self, vflip: bool) -> Self {
+        Self((self.0 & !(1 << 11)) | ((vflip as u16) << 11))
+    }
+
     #[must_use]
     pub const fn palette(self, palette_id: u8) -> Self {
         Self(self.0 ^ ((palette_id as u16) << 12))
@@ -369,4 +385,19 @@ mod test {
             assert_eq!(size.tile_pos_x(-17 - width * 8), (size.width() - 17) as u16);
         }
     }
+
+    #[test_case]
+    fn tile_setting_flip_bits(_gba: &mut crate::Gba) {
+        let setting = TileSetting::new(5, false, false, 3);
+
+        let flipped = setting.with_hflip(true).with_vflip(true);
+        assert_eq!(flipped.0, 5 | (1 << 10) | (1 << 11) | (3 << 12));
+
+        // setting the flips is idempotent, unlike toggling them
+        assert_eq!(flipped.with_hflip(true).0, flipped.0);
+        assert_eq!(flipped.with_hflip(false).with_vflip(false).0, setting.0);
+
+        assert_eq!(setting.with_vflip(true).index(), 5);
+        assert_eq!(setting.with_vflip(true).setting(), (1 << 11) | (3 << 12));
+    }
 }
@@ -1,4 +1,4 @@
-use core::{alloc::Layout, ptr::NonNull};
+use core::{alloc::Layout, ops::Range, ptr::NonNull};
 
 use alloc::{slice, vec::Vec};
 
@@ -6,6 +6,7 @@ use crate::{
     agb_alloc::{block_allocator::BlockAllocator, bump_allocator::StartEnd},
     display::palette16,
     dma,
+    fixnum::Num,
     hash_map::{Entry, HashMap},
     memory_mapped::MemoryMapped1DArray,
 };
@@ -310,6 +311,12 @@ impl VRamManager {
         index
     }
 
+    /// Adds another reference to a tile which must already be in video RAM
+    pub(crate) fn add_tile_reference(&mut self, tile_index: TileIndex) {
+        let key = tile_index.refcount_key();
+        self.reference_counts[key].increment_reference_count();
+    }
+
     pub(crate) fn remove_tile(&mut self, tile_index: TileIndex) {
         let key = tile_index.refcount_key();
 
@@ -464,6 +471,86 @@ impl VRamManager {
         }
     }
 
+    /// Sets the given background palette to a blend of the `from` and `to` palettes. An `amount`
+    /// of 0 gives `from` and an `amount` of 1 gives `to`, with the red, green and blue components
+    /// of each colour interpolated separately. Good for fading between palettes, such as for a
+    /// sunrise effect.
+    pub fn interpolate_palette(
+        &mut self,
+        pal_index: usize,
+        from: &palette16::Palette16,
+        to: &palette16::Palette16,
+        amount: Num<i32, 8>,
+    ) {
+        assert!(pal_index < 16);
+
+        for (colour_index, (&from, &to)) in from.colours.iter().zip(&to.colours).enumerate() {
+            PALETTE_BACKGROUND.set(
+                colour_index + 16 * pal_index,
+                palette16::interpolate_colour(from, to, amount),
+            );
+        }
+    }
+
+    /// Sets the backdrop colour, which is shown wherever no background or object covers the
+    /// screen. This is the first colour of the first background palette, so it is also
+    /// overwritten by [`set_background_palette`](Self::set_background_palette) with a `pal_index`
+    /// of 0. Takes effect immediately.
+    pub fn set_backdrop_colour(&mut self, colour: u16) {
+        PALETTE_BACKGROUND.set(0, colour);
+    }
+
+    /// The current backdrop colour
+    #[must_use]
+    pub fn backdrop_colour(&self) -> u16 {
+        PALETTE_BACKGROUND.get(0)
+    }
+
+    /// Sets the background palettes to `palettes` darkened towards black, where an `amount` of 0
+    /// gives `palettes` unchanged and an `amount` of 1 is entirely black. The backdrop is the
+    /// first colour of the first palette, so it fades along with everything else. Pass the
+    /// original palettes every frame of the transition rather than fading what is already in
+    /// palette ram, since the fade isn't reversible.
+    ///
+    /// This only affects backgrounds. To also fade objects, use
+    /// [`Blend::set_fade`](crate::display::blend::Blend::set_fade) instead.
+    pub fn fade_to_black(&mut self, palettes: &[palette16::Palette16], amount: Num<i32, 8>) {
+        for (pal_index, palette) in palettes.iter().enumerate() {
+            for (colour_index, &colour) in palette.colours.iter().enumerate() {
+                PALETTE_BACKGROUND.set(
+                    colour_index + 16 * pal_index,
+                    palette16::interpolate_colour(colour, 0, amount),
+                );
+            }
+        }
+    }
+
+    /// Rotates the colours in the given range of colour indices of a background palette by
+    /// `step` places, so the colour at index `i` moves to index `i + step`, wrapping around within
+    /// the range. A negative `step` rotates the other way. Calling this every few frames gives
+    /// the classic palette cycling effect for water or lava.
+    pub fn cycle_palette(&mut self, pal_index: usize, colours: Range<usize>, step: i32) {
+        assert!(pal_index < 16);
+        assert!(colours.start <= colours.end && colours.end <= 16);
+
+        let mut palette = [0; 16];
+        for i in colours.clone() {
+            palette[i] = PALETTE_BACKGROUND.get(16 * pal_index + i);
+        }
+
+        let cycled = &mut palette[colours.clone()];
+        if cycled.is_empty() {
+            return;
+        }
+
+        let step = step.rem_euclid(cycled.len() as i32) as usize;
+        cycled.rotate_right(step);
+
+        for i in colours {
+            PALETTE_BACKGROUND.set(16 * pal_index + i, palette[i]);
+        }
+    }
+
     /// Gets the index of the colour for a given background palette, or None if it doesn't exist
     #[must_use]
     pub fn find_colour_index_16(&self, palette_index: usize, colour: u16) -> Option<usize> {
@@ -478,3 +565,110 @@ impl VRamManager {
         (0..256).find(|&i| PALETTE_BACKGROUND.get(i) == colour)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::display::palette16::Palette16;
+
+    const BLACK: u16 = 0;
+    const WHITE: u16 = 0x7fff;
+    const RED: u16 = 0b11111;
+    const BLUE: u16 = 0b11111 << 10;
+
+    fn background_colour(pal_index: usize, colour_index: usize) -> u16 {
+        PALETTE_BACKGROUND.get(16 * pal_index + colour_index)
+    }
+
+    #[test_case]
+    fn interpolate_palette_endpoints(gba: &mut crate::Gba) {
+        let (_gfx, mut vram) = gba.display.video.tiled0();
+
+        let from = Palette16::new([BLACK, WHITE, RED, BLUE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let to = Palette16::new([WHITE, BLACK, BLUE, RED, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        vram.interpolate_palette(3, &from, &to, 0.into());
+        for i in 0..16 {
+            assert_eq!(background_colour(3, i), from.colour(i));
+        }
+
+        vram.interpolate_palette(3, &from, &to, 1.into());
+        for i in 0..16 {
+            assert_eq!(background_colour(3, i), to.colour(i));
+        }
+    }
+
+    #[test_case]
+    fn interpolate_palette_mid_point(gba: &mut crate::Gba) {
+        let (_gfx, mut vram) = gba.display.video.tiled0();
+
+        let from = Palette16::new([BLACK, WHITE, RED, BLUE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let to = Palette16::new([WHITE, BLACK, BLUE, RED, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        vram.interpolate_palette(3, &from, &to, Num::new(1) / 2);
+
+        // components going down must not underflow
+        assert_eq!(background_colour(3, 0), 0b01111_01111_01111);
+        assert_eq!(background_colour(3, 1), 0b01111_01111_01111);
+        assert_eq!(background_colour(3, 2), 0b01111_00000_01111);
+        assert_eq!(background_colour(3, 3), 0b01111_00000_01111);
+    }
+
+    #[test_case]
+    fn cycle_palette_rotates_colours(gba: &mut crate::Gba) {
+        let (_gfx, mut vram) = gba.display.video.tiled0();
+
+        let colours = core::array::from_fn(|i| i as u16);
+        vram.set_background_palette(2, &Palette16::new(colours));
+
+        vram.cycle_palette(2, 4..8, 1);
+        let expected = [0, 1, 2, 3, 7, 4, 5, 6, 8, 9, 10, 11, 12, 13, 14, 15];
+        for (i, &colour) in expected.iter().enumerate() {
+            assert_eq!(background_colour(2, i), colour);
+        }
+
+        vram.cycle_palette(2, 4..8, -2);
+        let expected = [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11, 12, 13, 14, 15];
+        for (i, &colour) in expected.iter().enumerate() {
+            assert_eq!(background_colour(2, i), colour);
+        }
+    }
+
+    #[test_case]
+    fn backdrop_colour_is_first_background_colour(gba: &mut crate::Gba) {
+        let (_gfx, mut vram) = gba.display.video.tiled0();
+
+        vram.set_backdrop_colour(RED);
+        assert_eq!(vram.backdrop_colour(), RED);
+        assert_eq!(background_colour(0, 0), RED);
+
+        vram.set_background_palette_colour(0, 0, BLUE);
+        assert_eq!(vram.backdrop_colour(), BLUE);
+    }
+
+    #[test_case]
+    fn fade_to_black_darkens_palettes_and_backdrop(gba: &mut crate::Gba) {
+        let (_gfx, mut vram) = gba.display.video.tiled0();
+
+        let palettes = [
+            Palette16::new([WHITE, RED, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Palette16::new([0, BLUE, WHITE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+        ];
+
+        vram.fade_to_black(&palettes, 0.into());
+        assert_eq!(vram.backdrop_colour(), WHITE);
+        assert_eq!(background_colour(1, 1), BLUE);
+
+        vram.fade_to_black(&palettes, Num::new(1) / 2);
+        assert_eq!(vram.backdrop_colour(), 0b01111_01111_01111);
+        assert_eq!(background_colour(0, 1), 0b01111);
+        assert_eq!(background_colour(1, 2), 0b01111_01111_01111);
+
+        vram.fade_to_black(&palettes, 1.into());
+        for i in 0..16 {
+            assert_eq!(background_colour(0, i), BLACK);
+            assert_eq!(background_colour(1, i), BLACK);
+        }
+    }
+}
@@ -2,7 +2,11 @@
 //! The window feature of the GBA.
 use core::marker::PhantomData;
 
-use crate::{dma, fixnum::Rect, memory_mapped::MemoryMapped};
+use crate::{
+    dma,
+    fixnum::{Rect, Vector2D},
+    memory_mapped::MemoryMapped,
+};
 
 use super::{tiled::BackgroundID, DISPLAY_CONTROL, HEIGHT, WIDTH};
 
@@ -11,6 +15,14 @@ use super::{tiled::BackgroundID, DISPLAY_CONTROL, HEIGHT, WIDTH};
 /// The windows feature can selectively display backgrounds or objects on the screen
 /// and can selectively enable and disable effects. This gives out references and
 /// holds changes before they can be committed.
+///
+/// The hardware has exactly two rectangular windows, [WinIn::Win0] and [WinIn::Win1], plus the
+/// object window, which covers every pixel drawn by an object in
+/// [GraphicsMode::Window](super::object::GraphicsMode::Window). Where they overlap, win0 takes
+/// priority over win1 which takes priority over the object window, and anything outside all of
+/// them uses [Windows::win_out]. The rectangles can't be rotated or shaped, but changing the
+/// horizontal position every scanline with [MovableWindow::horizontal_position_dma] gives other
+/// shapes such as circles.
 pub struct Windows<'gba> {
     wins: [MovableWindow; 2],
     out: Window,
@@ -279,3 +291,146 @@ impl MovableWindow {
         dma::DmaControllable::new(unsafe { REG_HORIZONTAL_BASE.add(self.id) })
     }
 }
+
+/// A centred area of the screen which everything is drawn in, with the rest of the screen left
+/// blank. This is useful for cinematic black bars in cutscenes, with
+/// [letterboxing](Viewport::letterbox()) adding bars at the top and bottom of the screen and
+/// [pillarboxing](Viewport::pillarbox()) adding them at the sides.
+///
+/// The viewport is drawn using [`WinIn::Win0`], so [`apply`](Viewport::apply()) takes over that
+/// window and [`Windows::win_out`]. The bars display the backdrop, which is the first colour
+/// of the background palettes, so set that to black if you want black bars.
+///
+/// # Scrolling
+///
+/// Windows don't move anything, they only hide what is outside of them, so backgrounds and
+/// objects are still positioned relative to the top left of the screen rather than the
+/// viewport. To keep positions in your game world relative to the viewport, position objects
+/// at [`to_screen`](Viewport::to_screen()) of where they should be in the viewport, and
+/// subtract the [`offset`](Viewport::offset()) from the position you scroll backgrounds to.
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # fn foo(gba: &mut agb::Gba) {
+/// use agb::display::window::Viewport;
+///
+/// // a 240x120 viewport with 20 pixel bars at the top and the bottom of the screen
+/// let viewport = Viewport::letterbox(120);
+///
+/// let mut windows = gba.display.window.get();
+/// viewport.apply(&mut windows);
+/// windows.commit();
+///
+/// // something at the top left of the viewport is drawn 20 pixels down the screen
+/// assert_eq!(viewport.to_screen((0, 0)), (0, 20).into());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    rect: Rect<i32>,
+}
+
+impl Viewport {
+    /// A viewport of the given size in the centre of the screen.
+    ///
+    /// Panics if the size is negative or bigger than the screen.
+    #[must_use]
+    pub fn new(size: impl Into<Vector2D<i32>>) -> Self {
+        let size = size.into();
+        assert!(
+            (0..=WIDTH).contains(&size.x) && (0..=HEIGHT).contains(&size.y),
+            "viewport must fit on the {WIDTH}x{HEIGHT} screen, but is {}x{}",
+            size.x,
+            size.y
+        );
+
+        let offset = (Vector2D::new(WIDTH, HEIGHT) - size) / 2;
+        Self {
+            rect: Rect::new(offset, size),
+        }
+    }
+
+    /// A viewport the full width of the screen and `height` pixels tall, with bars above and
+    /// below it.
+    ///
+    /// Panics if `height` is negative or taller than the screen.
+    #[must_use]
+    pub fn letterbox(height: i32) -> Self {
+        Self::new((WIDTH, height))
+    }
+
+    /// A viewport the full height of the screen and `width` pixels wide, with bars to the left
+    /// and right of it.
+    ///
+    /// Panics if `width` is negative or wider than the screen.
+    #[must_use]
+    pub fn pillarbox(width: i32) -> Self {
+        Self::new((width, HEIGHT))
+    }
+
+    /// The area of the screen covered by the viewport.
+    #[must_use]
+    pub fn rect(&self) -> Rect<i32> {
+        self.rect
+    }
+
+    /// The position of the top left of the viewport on the screen.
+    #[must_use]
+    pub fn offset(&self) -> Vector2D<i32> {
+        self.rect.position
+    }
+
+    /// The width and height of the viewport.
+    #[must_use]
+    pub fn size(&self) -> Vector2D<i32> {
+        self.rect.size
+    }
+
+    /// Converts a position relative to the top left of the viewport to a position on the
+    /// screen.
+    #[must_use]
+    pub fn to_screen(&self, position: impl Into<Vector2D<i32>>) -> Vector2D<i32> {
+        position.into() + self.offset()
+    }
+
+    /// Sets up [`WinIn::Win0`] to display every background and objects inside of the viewport,
+    /// and [`Windows::win_out`] to display nothing outside of it. Blending is left enabled
+    /// inside of the viewport. You must call [`Windows::commit`] for this change to be seen.
+    pub fn apply(&self, windows: &mut Windows) {
+        let inside = windows.win_in(WinIn::Win0);
+        inside
+            .reset()
+            .set_position(&self.rect)
+            .set_object_enable(true)
+            .set_blend_enable(true)
+            .enable();
+        for background in 0..4 {
+            inside.set_background_enable(BackgroundID(background), true);
+        }
+
+        windows.win_out().reset().enable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn viewports_are_centred_on_the_screen(_gba: &mut crate::Gba) {
+        let letterbox = Viewport::letterbox(120);
+        assert_eq!(
+            letterbox.rect(),
+            Rect::new((0, 20).into(), (240, 120).into())
+        );
+        assert_eq!(letterbox.to_screen((10, 10)), (10, 30).into());
+
+        let pillarbox = Viewport::pillarbox(160);
+        assert_eq!(pillarbox.offset(), (40, 0).into());
+        assert_eq!(pillarbox.size(), (160, 160).into());
+
+        assert_eq!(Viewport::new((200, 100)).offset(), (20, 30).into());
+        assert_eq!(Viewport::new((WIDTH, HEIGHT)).offset(), (0, 0).into());
+    }
+}
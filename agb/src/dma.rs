@@ -57,6 +57,64 @@ impl Dma {
         unsafe { MemoryMapped::new(dma_control_addr(self.number)) }.set(0);
     }
 
+    /// Copies `src` into `dest` using this DMA channel, blocking until the copy is complete.
+    /// The CPU is paused while the copy happens, but it is much faster than copying with the CPU
+    /// so is useful for copying large amounts of data such as tiles.
+    ///
+    /// `dest` and `src` must have the same length. `dma3` can copy up to 65536 half words at once
+    /// and `dma0` up to 16384. `dma0` cannot copy from the cartridge ROM, so use `dma3` for copying
+    /// data from there.
+    ///
+    /// This takes `&mut self`, so it cannot be used on a channel which currently has a
+    /// [`hblank_transfer`](Self::hblank_transfer) running. A `hblank_transfer` on a higher
+    /// priority channel (`dma0` is higher priority than `dma3`) still happens during the copy.
+    pub fn copy_u16(&mut self, dest: &mut [u16], src: &[u16]) {
+        self.copy(dest, src);
+    }
+
+    /// Copies `src` into `dest` using this DMA channel a word at a time, blocking until the copy
+    /// is complete. This has the same restrictions as [`copy_u16`](Self::copy_u16), with the
+    /// maximum lengths being in words rather than half words.
+    pub fn copy_u32(&mut self, dest: &mut [u32], src: &[u32]) {
+        self.copy(dest, src);
+    }
+
+    fn copy<T: Copy>(&mut self, dest: &mut [T], src: &[T]) {
+        assert_eq!(
+            dest.len(),
+            src.len(),
+            "source and destination of a dma copy must be the same length"
+        );
+
+        if src.is_empty() {
+            // a count of 0 would copy the maximum amount
+            return;
+        }
+
+        let max_len = if self.number == 3 { 0x1_0000 } else { 0x4000 };
+        assert!(
+            src.len() <= max_len,
+            "dma{} can copy at most {max_len} items at once",
+            self.number
+        );
+
+        self.source_addr.set(src.as_ptr() as u32);
+        self.dest_addr.set(dest.as_mut_ptr() as u32);
+
+        self.ctrl_addr.set(
+            // (0b00 << 0x15) | // increment the destination address each time
+            // (0b00 << 0x17) | // increment the source address each time
+            (u32::from(size_of::<T>() == 4) << 0x1a) | // copy in words or half words
+            // (0b00 << 0x1c) | // copy immediately
+            (1 << 0x1f) | // enable the dma
+            (src.len() as u32 & 0xffff), // the number of items to copy, where 0 is the maximum
+        );
+
+        // The CPU is paused during the copy, but it only starts a couple of cycles after it
+        // is enabled. The enable bit is cleared once the copy is complete.
+        while self.ctrl_addr.get() & (1 << 0x1f) != 0 {}
+    }
+
     /// Triggers a transfer from `values` to `location` to happen on every horizontal blank.
     ///
     /// This is useful for doing things like circular windows, wobbly backgrounds or fades
@@ -66,6 +124,9 @@ impl Dma {
     /// and the first value will be copied to the target address immediately since the items are only
     /// transferred at the _end_ of the hblank line rather than the beginning.
     ///
+    /// Each transfer has to fit within the horizontal blank, which is only around 270 cycles long, so
+    /// this is only suitable for changing a single register or colour per line.
+    ///
     /// It is recommended that you set this up as soon after the vblank interrupt as possible, and don't
     /// drop the DmaTransferHandler return value until the next vblank interrupt to ensure that you
     /// a continuous effect.
@@ -211,3 +272,38 @@ pub(crate) fn dma3_exclusive<R>(f: impl FnOnce() -> R) -> R {
         ret
     })
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use crate::Gba;
+
+    #[test_case]
+    fn dma_copy_u16_copies_the_source(gba: &mut Gba) {
+        let src: alloc::vec::Vec<u16> = (0..1000).map(|i| i * 7).collect();
+        let mut dest = vec![0u16; 1000];
+
+        let mut dmas = gba.dma.dma();
+        dmas.dma3.copy_u16(&mut dest, &src);
+        assert_eq!(dest, src);
+
+        let mut dest = vec![0u16; 1000];
+        dmas.dma0.copy_u16(&mut dest, &src);
+        assert_eq!(dest, src);
+    }
+
+    #[test_case]
+    fn dma_copy_u32_copies_the_source(gba: &mut Gba) {
+        let src: alloc::vec::Vec<u32> = (0..1000).map(|i| i * 0x0101_0101).collect();
+        let mut dest = vec![0u32; 1000];
+
+        gba.dma.dma().dma3.copy_u32(&mut dest, &src);
+        assert_eq!(dest, src);
+    }
+
+    #[test_case]
+    fn dma_copy_of_nothing_does_nothing(gba: &mut Gba) {
+        gba.dma.dma().dma3.copy_u16(&mut [], &[]);
+    }
+}
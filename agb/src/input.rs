@@ -236,4 +236,213 @@ impl ButtonController {
         let keys = keys.bits();
         ((current & keys) == 0) && ((previous & keys) != 0)
     }
+
+    /// Returns true on the frame that every button in `keys` is held at once, such as `Button::A | Button::B`.
+    ///
+    /// The buttons don't need to be pressed on exactly the same frame, since that is hard for players to do. This is true
+    /// as soon as the last of them is pressed while the others are still held, and then false until they are all held again.
+    #[must_use]
+    pub fn is_chord_just_pressed(&self, keys: Button) -> bool {
+        let keys = keys.bits();
+        let all_pressed = |state: u16| (u32::from(state) & keys) == keys;
+
+        all_pressed(self.current) && !all_pressed(self.previous)
+    }
+
+    fn pressed_in(state: u16, keys: Button) -> Button {
+        Button::from_bits_truncate(u32::from(state)) & keys
+    }
+}
+
+/// Detects when a sequence of button presses has been completed, such as a cheat code or a special move in a fighting
+/// game.
+///
+/// Each step of the sequence is the exact set of buttons to hold, for example down, down and forward, and then forward
+/// and A for a fireball motion:
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// use agb::input::{Button, ButtonController, SequenceMatcher};
+///
+/// # fn main() {
+/// let fireball = [Button::DOWN, Button::DOWN | Button::RIGHT, Button::RIGHT | Button::A];
+/// let mut matcher = SequenceMatcher::new(&fireball, 10);
+/// let mut input = ButtonController::new();
+///
+/// loop {
+///     input.update();
+///
+///     if matcher.update(&input) {
+///         // throw a fireball
+///     }
+/// }
+/// # }
+/// ```
+///
+/// A step is reached on the frame that the buttons used anywhere in the sequence change to be exactly that step's
+/// buttons, and any other buttons are ignored. Holding down the buttons of a step doesn't count it again, so repeated
+/// steps like the up, up at the start of the Konami code need the button to be released in between. The player has
+/// `window` frames after each step to reach the next one, otherwise they have to start the sequence again.
+pub struct SequenceMatcher<'a> {
+    sequence: &'a [Button],
+    window: u32,
+    relevant: Button,
+
+    progress: usize,
+    frames_since_step: u32,
+}
+
+impl<'a> SequenceMatcher<'a> {
+    /// Creates a matcher for `sequence` which allows up to `window` frames between each step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sequence` is empty.
+    #[must_use]
+    pub fn new(sequence: &'a [Button], window: u32) -> Self {
+        assert!(!sequence.is_empty(), "sequence must have at least one step");
+
+        let relevant = sequence
+            .iter()
+            .fold(Button::empty(), |relevant, &step| relevant | step);
+
+        Self {
+            sequence,
+            window,
+            relevant,
+
+            progress: 0,
+            frames_since_step: 0,
+        }
+    }
+
+    /// Call this once per frame after [`ButtonController::update`]. Returns true on the frame that the sequence is
+    /// completed, after which it starts matching from the beginning again.
+    pub fn update(&mut self, input: &ButtonController) -> bool {
+        self.frames_since_step = self.frames_since_step.saturating_add(1);
+        if self.progress > 0 && self.frames_since_step > self.window {
+            self.reset();
+        }
+
+        let current = ButtonController::pressed_in(input.current, self.relevant);
+        let previous = ButtonController::pressed_in(input.previous, self.relevant);
+
+        if current == previous {
+            return false;
+        }
+
+        if current != self.sequence[self.progress] {
+            // the player may have restarted the sequence part way through
+            if self.progress > 0 && current == self.sequence[0] {
+                self.progress = 0;
+            } else {
+                return false;
+            }
+        }
+
+        self.progress += 1;
+        self.frames_since_step = 0;
+
+        if self.progress == self.sequence.len() {
+            self.reset();
+            return true;
+        }
+
+        false
+    }
+
+    /// Forgets any progress through the sequence.
+    pub fn reset(&mut self) {
+        self.progress = 0;
+        self.frames_since_step = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    impl ButtonController {
+        fn press(&mut self, keys: Button) {
+            self.previous = self.current;
+            self.current = keys.bits() as u16;
+        }
+    }
+
+    fn controller() -> ButtonController {
+        ButtonController {
+            previous: 0,
+            current: 0,
+        }
+    }
+
+    #[test_case]
+    fn chords_do_not_need_to_be_pressed_on_the_same_frame(_gba: &mut crate::Gba) {
+        let mut input = controller();
+        let chord = Button::A | Button::B;
+
+        input.press(Button::A);
+        assert!(!input.is_chord_just_pressed(chord));
+
+        input.press(Button::A | Button::B);
+        assert!(input.is_chord_just_pressed(chord));
+
+        input.press(Button::A | Button::B | Button::L);
+        assert!(!input.is_chord_just_pressed(chord));
+    }
+
+    #[test_case]
+    fn sequence_matcher_detects_completion(_gba: &mut crate::Gba) {
+        let fireball = [
+            Button::DOWN,
+            Button::DOWN | Button::RIGHT,
+            Button::RIGHT | Button::A,
+        ];
+        let mut matcher = SequenceMatcher::new(&fireball, 5);
+        let mut input = controller();
+
+        let frames = [
+            Button::DOWN,
+            Button::DOWN,
+            Button::DOWN | Button::RIGHT,
+            Button::RIGHT,
+            Button::RIGHT | Button::A,
+        ];
+        let completed: alloc::vec::Vec<_> = frames
+            .iter()
+            .map(|&frame| {
+                input.press(frame);
+                matcher.update(&input)
+            })
+            .collect();
+
+        assert_eq!(completed, [false, false, false, false, true]);
+    }
+
+    #[test_case]
+    fn sequence_matcher_times_out(_gba: &mut crate::Gba) {
+        let code = [Button::UP, Button::UP, Button::DOWN];
+        let mut matcher = SequenceMatcher::new(&code, 3);
+        let mut input = controller();
+
+        for frame in [Button::UP, Button::empty(), Button::UP] {
+            input.press(frame);
+            assert!(!matcher.update(&input));
+        }
+
+        // waiting too long before the last step means starting again
+        for _ in 0..4 {
+            input.press(Button::empty());
+            assert!(!matcher.update(&input));
+        }
+
+        input.press(Button::DOWN);
+        assert!(!matcher.update(&input));
+
+        for frame in [Button::UP, Button::empty(), Button::UP, Button::DOWN] {
+            input.press(frame);
+            let completed = matcher.update(&input);
+            assert_eq!(completed, frame == Button::DOWN);
+        }
+    }
 }
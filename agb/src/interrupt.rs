@@ -6,6 +6,10 @@ use portable_atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use crate::{display::DISPLAY_STATUS, memory_mapped::MemoryMapped, util::SyncUnsafeCell};
 
+mod scheduler;
+
+pub use scheduler::{CallbackId, Scheduler};
+
 #[derive(Clone, Copy)]
 pub enum Interrupt {
     VBlank = 0,
@@ -302,6 +306,22 @@ unsafe impl critical_section::Impl for MyCriticalSection {
 static NUM_VBLANKS: AtomicUsize = AtomicUsize::new(0); // overflows after 2.27 years
 static HAS_CREATED_INTERRUPT: AtomicBool = AtomicBool::new(false);
 
+/// Starts counting vblanks in [`NUM_VBLANKS`] if nothing has done so already, and returns the
+/// current count.
+fn count_vblanks() -> usize {
+    if !HAS_CREATED_INTERRUPT.swap(true, Ordering::SeqCst) {
+        // safety: we don't allocate in the interrupt
+        let handler = unsafe {
+            add_interrupt_handler(Interrupt::VBlank, |_| {
+                NUM_VBLANKS.store(NUM_VBLANKS.load(Ordering::SeqCst) + 1, Ordering::SeqCst);
+            })
+        };
+        core::mem::forget(handler);
+    }
+
+    NUM_VBLANKS.load(Ordering::SeqCst)
+}
+
 #[non_exhaustive]
 pub struct VBlank {
     last_waited_number: Cell<usize>,
@@ -312,18 +332,8 @@ impl VBlank {
     /// interrupt syscall.
     #[must_use]
     pub fn get() -> Self {
-        if !HAS_CREATED_INTERRUPT.swap(true, Ordering::SeqCst) {
-            // safety: we don't allocate in the interrupt
-            let handler = unsafe {
-                add_interrupt_handler(Interrupt::VBlank, |_| {
-                    NUM_VBLANKS.store(NUM_VBLANKS.load(Ordering::SeqCst) + 1, Ordering::SeqCst);
-                })
-            };
-            core::mem::forget(handler);
-        }
-
         VBlank {
-            last_waited_number: Cell::new(NUM_VBLANKS.load(Ordering::SeqCst)),
+            last_waited_number: Cell::new(count_vblanks()),
         }
     }
     /// Pauses CPU until vblank interrupt is triggered where code execution is
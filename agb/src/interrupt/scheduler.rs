@@ -0,0 +1,261 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::count_vblanks;
+
+/// Runs callbacks after a number of frames, or every so many frames, so you don't need to keep
+/// your own frame counters for things like spawning waves of enemies.
+///
+/// Frames are counted using the vblank interrupt, but the callbacks themselves are only ever
+/// run from [`update`](Scheduler::update()), which you call once per frame from your game loop.
+/// Each callback is given the `context` passed to `update`, which is normally your game state,
+/// so that it can change it without having to borrow it for as long as the scheduler is alive.
+///
+/// # Reentrancy
+///
+/// * Callbacks run in the main loop rather than in the interrupt, so unlike interrupt handlers
+///   they may allocate and use anything else in agb.
+/// * Callbacks can't add or cancel callbacks, since the scheduler is borrowed while they run.
+///   If a callback needs to schedule something, have it record that in the context and do it
+///   after `update` returns.
+/// * Callbacks which are due on the same frame run in the order they were added.
+/// * If more than one frame has passed since the last `update`, such as after loading a level,
+///   each of those frames is caught up in turn, so a callback registered with
+///   [`every`](Scheduler::every()) may run several times in a single `update`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # fn foo() {
+/// use agb::interrupt::{Scheduler, VBlank};
+///
+/// struct Game {
+///     enemies: u32,
+/// }
+///
+/// let mut game = Game { enemies: 0 };
+/// let mut scheduler = Scheduler::new();
+///
+/// // a new wave of enemies every 2 seconds
+/// scheduler.every(120, |game: &mut Game| game.enemies += 5);
+/// scheduler.after(60, |_: &mut Game| agb::println!("Get ready!"));
+///
+/// let vblank = VBlank::get();
+/// loop {
+///     vblank.wait_for_vblank();
+///     scheduler.update(&mut game);
+/// }
+/// # }
+/// ```
+pub struct Scheduler<'a, T> {
+    callbacks: Vec<ScheduledCallback<'a, T>>,
+    next_id: u32,
+    last_vblank: usize,
+}
+
+/// Identifies a callback added to a [`Scheduler`] so that it can be
+/// [cancelled](Scheduler::cancel()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackId(u32);
+
+struct ScheduledCallback<'a, T> {
+    id: CallbackId,
+    frames_remaining: u32,
+    callback: Callback<'a, T>,
+}
+
+type OnceCallback<'a, T> = Box<dyn FnOnce(&mut T) + 'a>;
+
+enum Callback<'a, T> {
+    // only None while it is being run
+    Once(Option<OnceCallback<'a, T>>),
+    Every {
+        period: u32,
+        callback: Box<dyn FnMut(&mut T) + 'a>,
+    },
+}
+
+impl<'a, T> Scheduler<'a, T> {
+    /// Creates a scheduler with no callbacks. Frames are counted from when this is created.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            callbacks: Vec::new(),
+            next_id: 0,
+            last_vblank: count_vblanks(),
+        }
+    }
+
+    /// Runs `callback` once, `frames` frames from now. A delay of 0 runs it on the next
+    /// [`update`](Scheduler::update()), the same as a delay of 1.
+    pub fn after(&mut self, frames: u32, callback: impl FnOnce(&mut T) + 'a) -> CallbackId {
+        self.add(frames, Callback::Once(Some(Box::new(callback))))
+    }
+
+    /// Runs `callback` every `frames` frames, starting `frames` frames from now, until it is
+    /// [cancelled](Scheduler::cancel()).
+    ///
+    /// Panics if `frames` is 0.
+    pub fn every(&mut self, frames: u32, callback: impl FnMut(&mut T) + 'a) -> CallbackId {
+        assert!(frames > 0, "callbacks must be at least 1 frame apart");
+
+        self.add(
+            frames,
+            Callback::Every {
+                period: frames,
+                callback: Box::new(callback),
+            },
+        )
+    }
+
+    /// Stops a callback from running again. Returns `false` if it had already been cancelled or
+    /// was added with [`after`](Scheduler::after()) and has already run.
+    pub fn cancel(&mut self, id: CallbackId) -> bool {
+        let Some(index) = self.callbacks.iter().position(|callback| callback.id == id) else {
+            return false;
+        };
+
+        self.callbacks.remove(index);
+        true
+    }
+
+    /// Removes every callback.
+    pub fn clear(&mut self) {
+        self.callbacks.clear();
+    }
+
+    /// Whether there are no callbacks waiting to run.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    /// Runs any callbacks which are due in the frames since the last call to `update`, passing
+    /// each of them `context`. Call this once per frame, normally straight after
+    /// [`wait_for_vblank`](super::VBlank::wait_for_vblank()).
+    pub fn update(&mut self, context: &mut T) {
+        let now = count_vblanks();
+        let frames = now.wrapping_sub(self.last_vblank);
+        self.last_vblank = now;
+
+        self.advance(frames, context);
+    }
+
+    fn advance(&mut self, frames: usize, context: &mut T) {
+        for _ in 0..frames {
+            if self.callbacks.is_empty() {
+                return;
+            }
+
+            self.callbacks.retain_mut(|scheduled| {
+                scheduled.frames_remaining = scheduled.frames_remaining.saturating_sub(1);
+                if scheduled.frames_remaining > 0 {
+                    return true;
+                }
+
+                match &mut scheduled.callback {
+                    Callback::Once(callback) => {
+                        if let Some(callback) = callback.take() {
+                            callback(context);
+                        }
+
+                        false
+                    }
+                    Callback::Every { period, callback } => {
+                        callback(context);
+                        scheduled.frames_remaining = *period;
+
+                        true
+                    }
+                }
+            });
+        }
+    }
+
+    fn add(&mut self, frames: u32, callback: Callback<'a, T>) -> CallbackId {
+        let id = CallbackId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        self.callbacks.push(ScheduledCallback {
+            id,
+            frames_remaining: frames,
+            callback,
+        });
+
+        id
+    }
+}
+
+impl<T> Default for Scheduler<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+
+    #[test_case]
+    fn callbacks_run_on_the_right_frames(_gba: &mut crate::Gba) {
+        // the current frame, and the frames each callback ran on
+        type Fired = (u32, Vec<(&'static str, u32)>);
+
+        let mut scheduler = Scheduler::new();
+        scheduler.after(3, |(frame, fired): &mut Fired| {
+            fired.push(("after", *frame));
+        });
+        scheduler.every(2, |(frame, fired): &mut Fired| {
+            fired.push(("every", *frame));
+        });
+
+        let mut fired: Fired = (0, vec![]);
+        for frame in 1..=6 {
+            fired.0 = frame;
+            scheduler.advance(1, &mut fired);
+        }
+
+        assert_eq!(
+            fired.1,
+            [("every", 2), ("after", 3), ("every", 4), ("every", 6)]
+        );
+    }
+
+    #[test_case]
+    fn missed_frames_are_caught_up(_gba: &mut crate::Gba) {
+        let mut scheduler = Scheduler::new();
+        scheduler.every(5, |count: &mut u32| *count += 1);
+
+        let mut count = 0;
+        scheduler.advance(23, &mut count);
+        assert_eq!(count, 4);
+
+        scheduler.advance(2, &mut count);
+        assert_eq!(count, 5);
+    }
+
+    #[test_case]
+    fn cancelled_callbacks_stop_running(_gba: &mut crate::Gba) {
+        let mut scheduler = Scheduler::new();
+        let every = scheduler.every(1, |count: &mut u32| *count += 1);
+        let after = scheduler.after(0, |count: &mut u32| *count += 100);
+
+        let mut count = 0;
+        scheduler.advance(3, &mut count);
+        assert_eq!(count, 103);
+
+        assert!(scheduler.cancel(every));
+        assert!(!scheduler.cancel(every));
+        assert!(
+            !scheduler.cancel(after),
+            "one off callbacks are removed once they've run"
+        );
+        assert!(scheduler.is_empty());
+
+        scheduler.advance(3, &mut count);
+        assert_eq!(count, 103);
+    }
+}
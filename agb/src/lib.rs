@@ -97,6 +97,40 @@
 /// # }
 /// ```
 ///
+/// If your image contains repeated tiles, you can add `deduplicate` to only store each distinct tile once. For 16 colour
+/// backgrounds, tiles which are horizontal or vertical mirror images of each other are stored once too, and the
+/// generated `tile_settings` have the relevant flip bits set.
+///
+/// ```rust,no_run
+/// ##![no_std]
+/// ##![no_main]
+/// agb::include_background_gfx!(water_tiles, tiles => deduplicate "examples/water_tiles.png");
+/// ```
+///
+/// You can attach a property to every tile by giving a second image of the same size after `properties`. The top left
+/// pixel of each 8x8 tile in that image is read as `0xRRGGBB` and a transparent pixel means 0. The results end up in
+/// `tile_properties`, one per tile of the image in the same order as `tile_settings`. By default
+/// [`TileCollision`][crate::display::tiled::TileCollision] treats a property of 1 as solid, so a pixel of colour
+/// `#000001` marks a tile as solid.
+///
+/// `TileCollision` takes each tile of the grid as an index into its property table, so to use `tile_properties`
+/// directly the index of each tile is just its position in the image.
+///
+/// ```rust,ignore
+/// # #![no_std]
+/// # #![no_main]
+/// # extern crate alloc;
+/// use agb::display::tiled::TileCollision;
+/// use agb::fixnum::Vector2D;
+/// use alloc::vec::Vec;
+///
+/// agb::include_background_gfx!(level, tiles => deduplicate "gfx/level.png" properties "gfx/level_properties.png");
+///
+/// // gfx/level.png is 30x20 tiles
+/// let tile_indices: Vec<u16> = (0..level::tiles.tile_properties.len() as u16).collect();
+/// let collision = TileCollision::new(Vector2D::new(30, 20), &tile_indices, level::tiles.tile_properties);
+/// ```
+///
 /// Including from the out directory is supported through the `$OUT_DIR` token.
 ///
 /// ```rust,ignore
@@ -1,3 +1,7 @@
+use core::ops::Range;
+
+use crate::fixnum::Num;
+
 use portable_atomic::{AtomicU128, Ordering};
 
 /// A fast pseudo-random number generator. Note that the output of the
@@ -29,6 +33,40 @@ impl RandomNumberGenerator {
         Self { state: seed }
     }
 
+    /// Produces a random number generator from a single 64 bit seed. Any seed is
+    /// valid, including 0, and generators created with the same seed produce the
+    /// same sequence of numbers. Useful for deterministic replays or procedural
+    /// generation which should be the same every time.
+    #[must_use]
+    pub const fn from_seed(seed: u64) -> Self {
+        // splitmix64 spreads the seed over the whole state. Zero values are
+        // skipped since none of the state can be 0.
+        const fn splitmix64(state: u64) -> u64 {
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        }
+
+        const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+        let mut state = [0; 4];
+        let mut counter = seed;
+        let mut i = 0;
+        while i < 4 {
+            let mut value = 0;
+            while value == 0 {
+                counter = counter.wrapping_add(GOLDEN_GAMMA);
+                value = (splitmix64(counter) >> 32) as u32;
+            }
+
+            state[i] = value;
+            i += 1;
+        }
+
+        Self::new_with_seed(state)
+    }
+
     /// Returns the next value for the random number generator
     pub fn gen(&mut self) -> i32 {
         let result = (self.state[0].wrapping_add(self.state[3]))
@@ -46,6 +84,45 @@ impl RandomNumberGenerator {
 
         result as i32
     }
+
+    /// Returns the next value for the random number generator as an unsigned
+    /// integer. This advances the generator in the same way as [`gen`](Self::gen).
+    pub fn next_u32(&mut self) -> u32 {
+        self.gen() as u32
+    }
+
+    /// Returns a random number in the given range. Panics if the range is empty.
+    ///
+    /// Every number in the range is equally likely. Prefer this over taking the
+    /// remainder of [`gen`](Self::gen), which makes smaller numbers more likely
+    /// for ranges whose length isn't a power of 2.
+    pub fn gen_range(&mut self, range: Range<i32>) -> i32 {
+        assert!(range.start < range.end, "range must not be empty");
+
+        let len = range.end.abs_diff(range.start);
+
+        // Lemire's multiply and shift method. The low half of the product tells
+        // us if this value lands in one of the few outputs which would cause bias,
+        // in which case another value is generated.
+        let mut product = u64::from(self.next_u32()) * u64::from(len);
+        if (product as u32) < len {
+            let threshold = len.wrapping_neg() % len;
+            while (product as u32) < threshold {
+                product = u64::from(self.next_u32()) * u64::from(len);
+            }
+        }
+
+        range.start.wrapping_add_unsigned((product >> 32) as u32)
+    }
+
+    /// Returns `true` with the given probability, where a probability of 0 or less
+    /// is never `true` and 1 or more is always `true`. The probability is exact up to
+    /// the precision of the fixed point number.
+    pub fn gen_bool<const N: usize>(&mut self, probability: Num<i32, N>) -> bool {
+        // a random fixed point number in the range 0 to 1 with N fractional bits
+        let random = (u64::from(self.next_u32()) << N) >> 32;
+        random < probability.to_raw().max(0) as u64
+    }
 }
 
 impl Default for RandomNumberGenerator {
@@ -58,13 +135,11 @@ static GLOBAL_RNG: AtomicU128 = AtomicU128::new(unsafe {
     core::mem::transmute::<[u32; 4], u128>(RandomNumberGenerator::new().state)
 });
 
-/// Using a global random number generator, provides the next random number
-#[must_use]
-pub fn gen() -> i32 {
+fn with_global_rng<T>(f: impl FnOnce(&mut RandomNumberGenerator) -> T) -> T {
     let data: u128 = GLOBAL_RNG.load(Ordering::SeqCst);
     let data_u32: [u32; 4] = unsafe { core::mem::transmute(data) };
     let mut rng = RandomNumberGenerator { state: data_u32 };
-    let value = rng.gen();
+    let value = f(&mut rng);
     GLOBAL_RNG.store(
         unsafe { core::mem::transmute::<[u32; 4], u128>(rng.state) },
         Ordering::SeqCst,
@@ -72,6 +147,26 @@ pub fn gen() -> i32 {
     value
 }
 
+/// Using a global random number generator, provides the next random number
+#[must_use]
+pub fn gen() -> i32 {
+    with_global_rng(RandomNumberGenerator::gen)
+}
+
+/// Using a global random number generator, provides a random number in the given
+/// range with every number equally likely. See [`RandomNumberGenerator::gen_range`].
+#[must_use]
+pub fn gen_range(range: Range<i32>) -> i32 {
+    with_global_rng(|rng| rng.gen_range(range))
+}
+
+/// Using a global random number generator, returns `true` with the given probability.
+/// See [`RandomNumberGenerator::gen_bool`].
+#[must_use]
+pub fn gen_bool<const N: usize>(probability: Num<i32, N>) -> bool {
+    with_global_rng(|rng| rng.gen_bool(probability))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -109,4 +204,78 @@ mod test {
             );
         }
     }
+
+    #[test_case]
+    fn same_seed_gives_the_same_sequence(_gba: &mut Gba) {
+        let mut a = RandomNumberGenerator::from_seed(0x1234_5678_9abc_def0);
+        let mut b = RandomNumberGenerator::from_seed(0x1234_5678_9abc_def0);
+        let mut c = RandomNumberGenerator::from_seed(0x1234_5678_9abc_def1);
+
+        let mut all_same_as_c = true;
+        for _ in 0..100 {
+            let value = a.next_u32();
+            assert_eq!(value, b.next_u32());
+            all_same_as_c &= value == c.next_u32();
+        }
+
+        assert!(!all_same_as_c, "different seeds gave the same sequence");
+    }
+
+    #[test_case]
+    fn zero_seed_is_valid(_gba: &mut Gba) {
+        let mut rng = RandomNumberGenerator::from_seed(0);
+        assert!((0..10).any(|_| rng.gen() != 0));
+    }
+
+    #[test_case]
+    fn gen_range_stays_in_range(_gba: &mut Gba) {
+        let mut rng = RandomNumberGenerator::from_seed(42);
+
+        let mut seen = [false; 7];
+        for _ in 0..500 {
+            let value = rng.gen_range(-3..4);
+            assert!((-3..4).contains(&value), "{value} is out of range");
+            seen[(value + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+
+        for _ in 0..100 {
+            let value = rng.gen_range(i32::MIN..i32::MAX);
+            assert!(value < i32::MAX);
+        }
+    }
+
+    #[test_case]
+    fn gen_range_is_uniform(_gba: &mut Gba) {
+        const SAMPLES: i32 = 5000;
+        let mut counts = [0; 5];
+
+        let mut rng = RandomNumberGenerator::new();
+        for _ in 0..SAMPLES {
+            counts[rng.gen_range(0..5) as usize] += 1;
+        }
+
+        let expected = SAMPLES / 5;
+        let chi_squared: i32 = counts
+            .iter()
+            .map(|&count| (count - expected) * (count - expected))
+            .sum::<i32>()
+            / expected;
+
+        // the 99.9% critical value for 4 degrees of freedom is ~18.5
+        assert!(chi_squared < 18, "{counts:?} is not uniform enough");
+    }
+
+    #[test_case]
+    fn gen_bool_follows_the_probability(_gba: &mut Gba) {
+        let mut rng = RandomNumberGenerator::new();
+
+        let quarter: Num<i32, 8> = Num::new(1) / 4;
+        let trues = (0..1000).filter(|_| rng.gen_bool(quarter)).count();
+        assert!((200..300).contains(&trues), "{trues} was not close to 250");
+
+        assert!((0..100).all(|_| rng.gen_bool(Num::<i32, 8>::new(1))));
+        assert!((0..100).all(|_| !rng.gen_bool(Num::<i32, 8>::new(0))));
+        assert!((0..100).all(|_| !rng.gen_bool(Num::<i32, 8>::new(-1))));
+    }
 }
@@ -100,6 +100,7 @@ mod sw_mixer;
 
 pub use sw_mixer::ChannelId;
 pub use sw_mixer::Mixer;
+pub use sw_mixer::MAX_ECHO_DELAY_FRAMES;
 
 use crate::fixnum::Num;
 
@@ -143,7 +144,9 @@ pub enum Frequency {
 
 // list here: http://deku.gbadev.org/program/sound1.html
 impl Frequency {
-    pub(crate) fn frequency(self) -> i32 {
+    /// The frequency in Hz
+    #[must_use]
+    pub fn frequency(self) -> i32 {
         use Frequency::*;
 
         match self {
@@ -164,6 +167,87 @@ impl Frequency {
     }
 }
 
+/// How the [panning](SoundChannel::panning) of a mono sound is split between the left and
+/// right speakers. Set it for every channel with [`Mixer::set_pan_law`].
+///
+/// Stereo sounds ignore panning, so they aren't affected by this.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum PanLaw {
+    /// The volume of each side changes linearly with the panning. A sound panned to the centre
+    /// plays at half volume in each speaker, so it sounds a bit quieter than one panned fully
+    /// to one side.
+    ///
+    /// This costs a couple of additions per channel per frame.
+    #[default]
+    Linear,
+    /// The left and right volumes follow a quarter cosine and sine wave, so the total power
+    /// stays the same wherever the sound is panned. A sound panned to the centre plays at
+    /// about 0.7 times the volume in each speaker, so is more likely to clip if lots of sounds
+    /// are playing at once.
+    ///
+    /// This interpolates between entries of a 17 entry lookup table, which is a couple of
+    /// multiplications per channel per frame. It doesn't affect the per sample cost so is
+    /// still tiny compared to the mixing itself.
+    ConstantPower,
+}
+
+/// How mono sounds are resampled when they are played at a different
+/// [speed](SoundChannel::playback) to the mixer's frequency. Set it for every channel with
+/// [`Mixer::set_interpolation`].
+///
+/// Stereo sounds always play at the mixer's frequency, so they aren't affected by this.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Interpolation {
+    /// Each output sample is the nearest sample point before it. This can sound gritty when a
+    /// sound is played much slower than it was recorded, but is the cheapest option and is done
+    /// in hand written assembly.
+    #[default]
+    Nearest,
+    /// Each output sample is a linear interpolation between the two nearest sample points, which
+    /// smooths out sounds played at a lower speed.
+    ///
+    /// This is done in Rust rather than assembly, and needs an extra load and multiplication
+    /// per sample, so mixing each mono channel takes several times as long as with
+    /// [`Interpolation::Nearest`]. Use [`Mixer::last_frame_cycles`] to check whether you can
+    /// afford it.
+    Linear,
+}
+
+// sin(x * pi / 32) for x in 0..=16 as a Num<i16, 8>
+const QUARTER_SINE: [i16; 17] = [
+    0, 25, 50, 74, 98, 121, 142, 162, 181, 198, 213, 226, 237, 245, 251, 255, 256,
+];
+
+impl PanLaw {
+    /// Returns the (left, right) volumes for the given panning between -1 and 1
+    fn gains(self, panning: Num<i16, 8>) -> (Num<i16, 8>, Num<i16, 8>) {
+        match self {
+            PanLaw::Linear => ((-panning + 1) / 2, (panning + 1) / 2),
+            PanLaw::ConstantPower => {
+                // between 0 and 512 where 0 is fully left
+                let position = (panning + 1).to_raw().clamp(0, 512);
+
+                (quarter_sine(512 - position), quarter_sine(position))
+            }
+        }
+    }
+}
+
+fn quarter_sine(position: i16) -> Num<i16, 8> {
+    let index = (position / 32) as usize;
+    let fraction = position % 32;
+
+    let start = QUARTER_SINE[index];
+    if fraction == 0 {
+        return Num::from_raw(start);
+    }
+
+    let end = QUARTER_SINE[index + 1];
+    Num::from_raw(start + (end - start) * fraction / 32)
+}
+
 /// Describes one sound which should be playing. This could be a sound effect or
 /// the background music. Use the factory methods on this to modify how it is played.
 ///
@@ -229,6 +313,7 @@ pub struct SoundChannel {
     pos: Num<u32, 8>,
     should_loop: bool,
     restart_point: Num<u32, 8>,
+    loop_crossfade: u32,
 
     is_playing: bool,
     playback_speed: Num<u32, 8>,
@@ -240,6 +325,20 @@ pub struct SoundChannel {
     is_stereo: bool,
 
     priority: SoundPriority,
+
+    low_pass: Option<Num<i16, 8>>, // between 0 and 1
+    low_pass_state: [i32; 2],
+
+    fade: Option<Fade>,
+    fade_volume: Num<i16, 8>, // between 0 and 1
+}
+
+struct Fade {
+    from: Num<i16, 8>,
+    to: Num<i16, 8>,
+    frame: u32,
+    total_frames: u32,
+    stop_at_end: bool,
 }
 
 impl SoundChannel {
@@ -282,6 +381,11 @@ impl SoundChannel {
             volume: 1.into(),
             is_stereo: false,
             restart_point: 0.into(),
+            loop_crossfade: 0,
+            low_pass: None,
+            low_pass_state: [0; 2],
+            fade: None,
+            fade_volume: 1.into(),
         }
     }
 
@@ -327,6 +431,11 @@ impl SoundChannel {
             volume: 1.into(),
             is_stereo: false,
             restart_point: 0.into(),
+            loop_crossfade: 0,
+            low_pass: None,
+            low_pass_state: [0; 2],
+            fade: None,
+            fade_volume: 1.into(),
         }
     }
 
@@ -352,6 +461,24 @@ impl SoundChannel {
         self
     }
 
+    /// Smooths over the jump from the end of a looping sound back to its restart point, which
+    /// can be heard as a click if the sound doesn't line up at the loop. The last `length`
+    /// samples are blended into the samples just before the restart point, so the end of the
+    /// sound leads into the restart point. Does nothing unless you also call
+    /// [`should_loop()`](SoundChannel::should_loop()), and only works for mono sounds.
+    ///
+    /// The crossfade is limited to the number of samples before the restart point and the
+    /// length of the looping part, so it has no effect on sounds which loop from the start.
+    /// A few tens of samples is usually enough to remove a click. Longer crossfades are just
+    /// as cheap but change more of the end of the sound. Sounds with a crossfade are mixed in
+    /// Rust rather than assembly, so they take about as much CPU time as
+    /// [`Interpolation::Linear`]. Set the length to 0 to turn the crossfade off again.
+    #[inline(always)]
+    pub fn loop_crossfade(&mut self, length: u32) -> &mut Self {
+        self.loop_crossfade = length;
+        self
+    }
+
     /// Sets the speed at which this should channel should be played. Defaults
     /// to 1 with values between 0 and 1 being slower above 1 being faster.
     ///
@@ -395,6 +522,38 @@ impl SoundChannel {
         self
     }
 
+    /// Applies a low pass filter to the sound, which removes the higher frequencies
+    /// and makes it sound muffled. Useful for sounds which should be heard underwater
+    /// or through a wall.
+    ///
+    /// Must be a value between 0 (exclusive) and 1 (inclusive). Each output sample moves
+    /// this fraction of the way from the previous output sample towards the input, so
+    /// smaller values muffle the sound more and 1 leaves it unchanged.
+    ///
+    /// The filter is applied in software after mixing the channel, so a filtered channel
+    /// takes noticeably more CPU time in [`Mixer::frame()`] than an unfiltered one. The
+    /// first filtered channel also allocates an extra buffer the size of the mixer's
+    /// working buffer. Use [`disable_low_pass()`](SoundChannel::disable_low_pass()) to
+    /// remove the filter again.
+    #[inline(always)]
+    pub fn low_pass(&mut self, amount: impl Into<Num<i16, 8>>) -> &mut Self {
+        let amount = amount.into();
+
+        assert!(amount > Num::new(0), "low pass amount must be > 0");
+        assert!(amount <= Num::new(1), "low pass amount must be <= 1");
+
+        self.low_pass = Some(amount);
+        self
+    }
+
+    /// Removes the filter added by [`low_pass()`](SoundChannel::low_pass()).
+    #[inline(always)]
+    pub fn disable_low_pass(&mut self) -> &mut Self {
+        self.low_pass = None;
+        self.low_pass_state = [0; 2];
+        self
+    }
+
     /// Sets that the sound effect should be played in stereo. Not setting this
     /// will result in the sound playing at half speed and mono. Setting this on
     /// a mono sound will cause some interesting results (and play it at double speed).
@@ -411,6 +570,70 @@ impl SoundChannel {
         self.is_done = true;
     }
 
+    /// Gradually lowers the volume of the sound to 0 over the given number of frames
+    /// and then stops it. This avoids the click you can get from calling
+    /// [`.stop()`](SoundChannel::stop()) on a loud sound.
+    ///
+    /// A frame here is each time [`Mixer::frame()`] mixes a new buffer of sound, which
+    /// happens once per frame if you call it every frame. The fade is applied on top of the
+    /// [`volume`](SoundChannel::volume()), so you can still change that during the fade.
+    #[inline]
+    pub fn fade_out(&mut self, frames: u32) -> &mut Self {
+        self.start_fade(0.into(), frames, true);
+        self
+    }
+
+    /// Gradually raises the volume of the sound from 0 over the given number of frames.
+    /// See [`.fade_out()`](SoundChannel::fade_out()) for more details. This replaces any
+    /// fade out which is currently happening, so the sound will no longer be stopped.
+    #[inline]
+    pub fn fade_in(&mut self, frames: u32) -> &mut Self {
+        self.fade_volume = 0.into();
+        self.start_fade(1.into(), frames, false);
+        self
+    }
+
+    fn start_fade(&mut self, to: Num<i16, 8>, frames: u32, stop_at_end: bool) {
+        self.fade = Some(Fade {
+            from: self.fade_volume,
+            to,
+            frame: 0,
+            total_frames: frames,
+            stop_at_end,
+        });
+
+        // a fade over 0 frames should take effect immediately
+        if frames == 0 {
+            self.update_fade();
+        }
+    }
+
+    /// Moves any current fade on by a frame. Called by the mixer each time it mixes a buffer.
+    fn update_fade(&mut self) {
+        let Some(fade) = &mut self.fade else {
+            return;
+        };
+
+        fade.frame += 1;
+
+        if fade.frame >= fade.total_frames {
+            self.fade_volume = fade.to;
+            if fade.stop_at_end {
+                self.is_done = true;
+            }
+            self.fade = None;
+        } else {
+            let difference = i32::from((fade.to - fade.from).to_raw());
+            let progress = difference * fade.frame as i32 / fade.total_frames as i32;
+            self.fade_volume = fade.from + Num::from_raw(progress as i16);
+        }
+    }
+
+    /// The volume which the sound should currently be mixed at, including any fade.
+    fn effective_volume(&self) -> Num<i16, 8> {
+        self.volume * self.fade_volume
+    }
+
     /// Gets how far along the sound has played.
     #[inline]
     #[must_use]
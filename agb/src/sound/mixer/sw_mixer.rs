@@ -6,7 +6,7 @@ use alloc::vec::Vec;
 use critical_section::{CriticalSection, Mutex};
 
 use super::hw::LeftOrRight;
-use super::{hw, Frequency};
+use super::{hw, Frequency, Interpolation, PanLaw};
 use super::{SoundChannel, SoundPriority};
 
 use crate::InternalAllocator;
@@ -112,8 +112,14 @@ pub struct Mixer<'gba> {
     frequency: Frequency,
 
     working_buffer: Box<[Num<i16, 4>], InternalAllocator>,
+    // only allocated once a channel with a low pass filter is played
+    filter_buffer: Option<Box<[Num<i16, 4>], InternalAllocator>>,
+    echo: Option<Echo>,
+    pan_law: PanLaw,
+    interpolation: Interpolation,
 
     fifo_timer: Timer,
+    last_frame_cycles: u32,
 
     phantom: PhantomData<&'gba ()>,
 }
@@ -175,10 +181,6 @@ impl Mixer<'_> {
             })
         };
 
-        let mut working_buffer =
-            Vec::with_capacity_in(frequency.buffer_size() * 2, InternalAllocator);
-        working_buffer.resize(frequency.buffer_size() * 2, 0.into());
-
         Self {
             frequency,
             buffer,
@@ -188,8 +190,13 @@ impl Mixer<'_> {
             interrupt_timer,
             _interrupt_handler: interrupt_handler,
 
-            working_buffer: working_buffer.into_boxed_slice(),
+            working_buffer: working_buffer(frequency),
+            filter_buffer: None,
+            echo: None,
+            pan_law: PanLaw::default(),
+            interpolation: Interpolation::default(),
             fifo_timer,
+            last_frame_cycles: 0,
 
             phantom: PhantomData,
         }
@@ -237,8 +244,170 @@ impl Mixer<'_> {
             return;
         }
 
-        self.buffer
-            .write_channels(&mut self.working_buffer, self.channels.iter_mut().flatten());
+        let start = self.cycle_count();
+
+        for channel in self.channels.iter_mut().flatten() {
+            if !channel.is_done && channel.is_playing {
+                channel.update_fade();
+            }
+        }
+
+        if self.filter_buffer.is_none()
+            && self
+                .channels
+                .iter()
+                .flatten()
+                .any(|channel| channel.low_pass.is_some())
+        {
+            self.filter_buffer = Some(working_buffer(self.frequency));
+        }
+
+        self.buffer.write_channels(
+            &mut self.working_buffer,
+            self.filter_buffer.as_deref_mut().unwrap_or_default(),
+            self.echo.as_mut(),
+            self.pan_law,
+            self.interpolation,
+            self.channels.iter_mut().flatten(),
+        );
+
+        // free up channels which have finished so that nothing needs to keep hold of them
+        for channel in &mut self.channels {
+            if channel.as_ref().is_some_and(|channel| channel.is_done) {
+                *channel = None;
+            }
+        }
+
+        let buffer_cycles = self.frequency.buffer_size() as u32 * self.cycles_per_sample();
+        self.last_frame_cycles = (self.cycle_count() + buffer_cycles - start) % buffer_cycles;
+    }
+
+    /// The number of CPU cycles taken by the last call to [`frame()`](Mixer::frame()) which did
+    /// any mixing. A whole frame is 280896 cycles, so divide by that to work out how much of the
+    /// CPU the mixer is using.
+    ///
+    /// This is measured using the timers which the mixer already uses to play the sound, so it
+    /// costs only a few register reads per frame and is accurate to within a single cycle. The
+    /// measurement covers the fades, filters and echo as well as the mixing itself, but it also
+    /// includes the time spent in any interrupt handlers which run during the mixing. It will
+    /// be 0 until the mixer has been [enabled](Mixer::enable()), and if mixing takes longer than
+    /// a whole frame the reported value wraps around.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use agb::sound::mixer::*;
+    /// # use agb::*;
+    /// # fn foo(gba: &mut Gba) {
+    /// # let mut mixer = gba.mixer.mixer(agb::sound::mixer::Frequency::Hz10512);
+    /// mixer.frame();
+    /// let percent_cpu_used = mixer.last_frame_cycles() * 100 / 280896;
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn last_frame_cycles(&self) -> u32 {
+        self.last_frame_cycles
+    }
+
+    fn cycles_per_sample(&self) -> u32 {
+        (16777216 / self.frequency.frequency()) as u32
+    }
+
+    /// The number of cycles since the start of the buffer which is currently playing.
+    ///
+    /// The fifo timer overflows once per sample and the interrupt timer counts those overflows,
+    /// so between them they give the current position within the buffer to the cycle.
+    fn cycle_count(&self) -> u32 {
+        let (samples, cycles) = loop {
+            let samples = self.interrupt_timer.value();
+            let cycles = self.fifo_timer.value();
+
+            // if the fifo timer overflowed between the two reads, then it doesn't match the sample
+            if self.interrupt_timer.value() == samples {
+                break (samples, cycles);
+            }
+        };
+
+        let buffer_size = self.frequency.buffer_size() as u16;
+        let cycles_per_sample = self.cycles_per_sample();
+
+        let sample = u32::from(samples.wrapping_add(buffer_size));
+        let cycle = u32::from(cycles.wrapping_add(cycles_per_sample as u16));
+
+        sample * cycles_per_sample + cycle
+    }
+
+    /// Adds an echo to everything played by the mixer. The sound is repeated `delay_frames`
+    /// frames later at `mix` times the volume, and each repeat is fed back into the echo at
+    /// `feedback` times the volume so it slowly dies away.
+    ///
+    /// The delay must be between 1 and [`MAX_ECHO_DELAY_FRAMES`] frames, `feedback` must be
+    /// between 0 (inclusive) and 1 (exclusive), and `mix` must be at least 0.
+    ///
+    /// The echo keeps a copy of the last `delay_frames` frames of output in EWRAM, which
+    /// is about 2KB per frame of delay at 32768Hz, and adds an extra pass over the mixed
+    /// output every frame. Use [`clear_echo()`](Mixer::clear_echo()) to remove it again
+    /// and free that memory.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use agb::sound::mixer::*;
+    /// # use agb::*;
+    /// # fn foo(gba: &mut Gba) {
+    /// # let mut mixer = gba.mixer.mixer(agb::sound::mixer::Frequency::Hz10512);
+    /// mixer.set_echo(10, fixnum::num!(0.5), fixnum::num!(0.5));
+    /// # }
+    /// ```
+    pub fn set_echo(
+        &mut self,
+        delay_frames: usize,
+        feedback: impl Into<Num<i16, 8>>,
+        mix: impl Into<Num<i16, 8>>,
+    ) {
+        let feedback = feedback.into();
+        let mix = mix.into();
+
+        assert!(
+            (1..=MAX_ECHO_DELAY_FRAMES).contains(&delay_frames),
+            "echo delay must be between 1 and {MAX_ECHO_DELAY_FRAMES} frames"
+        );
+        assert!(
+            feedback >= 0.into() && feedback < 1.into(),
+            "echo feedback must be >= 0 and < 1"
+        );
+        assert!(mix >= 0.into(), "echo mix must be >= 0");
+
+        let frame_length = self.working_buffer.len();
+
+        match &mut self.echo {
+            Some(echo) if echo.buffer.len() == delay_frames * frame_length => {
+                echo.feedback = feedback;
+                echo.mix = mix;
+            }
+            _ => self.echo = Some(Echo::new(delay_frames, frame_length, feedback, mix)),
+        }
+    }
+
+    /// Removes the echo added by [`set_echo()`](Mixer::set_echo()).
+    pub fn clear_echo(&mut self) {
+        self.echo = None;
+    }
+
+    /// Sets how mono sounds are resampled when played at a different speed. See
+    /// [`Interpolation`] for the options and their cost. Defaults to [`Interpolation::Nearest`].
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Sets how the panning of mono sounds is split between the left and right speakers. See
+    /// [`PanLaw`] for the options. Defaults to [`PanLaw::Linear`].
+    pub fn set_pan_law(&mut self, pan_law: PanLaw) {
+        self.pan_law = pan_law;
     }
 
     /// Start playing a given [`SoundChannel`].
@@ -298,6 +467,30 @@ impl Mixer<'_> {
         panic!("Cannot play more than 8 sounds at once");
     }
 
+    /// Plays a sound effect once, for when you don't need to change or stop it later.
+    ///
+    /// The sound is played as a low priority mono sound with the default settings, and its
+    /// channel is freed by [`frame()`](Mixer::frame()) once it has finished. Returns whether there
+    /// was a free channel to play it in. Use [`play_sound()`](Mixer::play_sound()) and ignore the
+    /// returned [`ChannelId`] if you need to change any of the settings.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use agb::sound::mixer::*;
+    /// # use agb::*;
+    /// # fn foo(gba: &mut Gba) {
+    /// # let mut mixer = gba.mixer.mixer(agb::sound::mixer::Frequency::Hz10512);
+    /// # static JUMP_SOUND: &[u8] = include_wav!("examples/sfx/jump.wav");
+    /// mixer.play_oneshot(JUMP_SOUND);
+    /// # }
+    /// ```
+    pub fn play_oneshot(&mut self, data: &'static [u8]) -> bool {
+        self.play_sound(SoundChannel::new(data)).is_some()
+    }
+
     /// Lets you modify an already playing channel.
     ///
     /// Allows you to change the volume, panning or stop an already playing channel.
@@ -329,6 +522,264 @@ impl Mixer<'_> {
 
         None
     }
+
+    /// Whether the sound started with the given [`ChannelId`] is still playing. This is false if
+    /// it has finished, been stopped or is paused.
+    ///
+    /// Each channel keeps track of how many sounds it has played, so an old `ChannelId` for a
+    /// channel which is now playing a different sound reliably returns false.
+    #[must_use]
+    pub fn is_playing(&self, id: &ChannelId) -> bool {
+        self.current_channel(id)
+            .is_some_and(|channel| channel.is_playing)
+    }
+
+    /// Whether the sound started with the given [`ChannelId`] has finished, either because it
+    /// reached the end or because it was stopped or replaced by a higher priority sound. Unlike
+    /// [`is_playing()`](Mixer::is_playing()), a paused sound hasn't finished.
+    #[must_use]
+    pub fn is_finished(&self, id: &ChannelId) -> bool {
+        self.current_channel(id).is_none()
+    }
+
+    /// The number of channels which are playing a sound, including paused sounds. Sounds which
+    /// have finished stop taking up a channel straight away, even before the next call to
+    /// [`frame()`](Mixer::frame()).
+    #[must_use]
+    pub fn active_channels(&self) -> usize {
+        self.channels
+            .iter()
+            .flatten()
+            .filter(|channel| !channel.is_done)
+            .count()
+    }
+
+    /// The number of channels which [`play_sound()`](Mixer::play_sound()) can play a sound in
+    /// without replacing a sound which is already playing.
+    #[must_use]
+    pub fn free_channels(&self) -> usize {
+        self.max_channels() - self.active_channels()
+    }
+
+    /// The number of sounds which can play at once.
+    #[must_use]
+    pub fn max_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn current_channel(&self, id: &ChannelId) -> Option<&SoundChannel> {
+        self.channels[id.0]
+            .as_ref()
+            .filter(|channel| self.indices[id.0] == id.1 && !channel.is_done)
+    }
+
+    /// The frequency the mixer was created with
+    #[must_use]
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+}
+
+/// The longest delay supported by [`Mixer::set_echo()`].
+pub const MAX_ECHO_DELAY_FRAMES: usize = 16;
+
+struct Echo {
+    buffer: Vec<Num<i16, 4>>,
+    position: usize,
+    feedback: Num<i16, 8>,
+    mix: Num<i16, 8>,
+}
+
+impl Echo {
+    fn new(
+        delay_frames: usize,
+        frame_length: usize,
+        feedback: Num<i16, 8>,
+        mix: Num<i16, 8>,
+    ) -> Self {
+        Self {
+            buffer: alloc::vec![0.into(); delay_frames * frame_length],
+            position: 0,
+            feedback,
+            mix,
+        }
+    }
+
+    fn apply(&mut self, working_buffer: &mut [Num<i16, 4>]) {
+        let feedback = i32::from(self.feedback.to_raw());
+        let mix = i32::from(self.mix.to_raw());
+
+        let delay_line = &mut self.buffer[self.position..self.position + working_buffer.len()];
+
+        for (sample, delayed) in working_buffer.iter_mut().zip(delay_line) {
+            let input = i32::from(sample.to_raw());
+            let echo = i32::from(delayed.to_raw());
+
+            *sample = Num::from_raw(saturate(input + ((echo * mix) >> 8)));
+            *delayed = Num::from_raw(saturate(input + ((echo * feedback) >> 8)));
+        }
+
+        self.position = (self.position + working_buffer.len()) % self.buffer.len();
+    }
+}
+
+fn saturate(value: i32) -> i16 {
+    value.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
+fn working_buffer(frequency: Frequency) -> Box<[Num<i16, 4>], InternalAllocator> {
+    let mut working_buffer = Vec::with_capacity_in(frequency.buffer_size() * 2, InternalAllocator);
+    working_buffer.resize(frequency.buffer_size() * 2, 0.into());
+
+    working_buffer.into_boxed_slice()
+}
+
+/// A mono sound which is mixed in Rust rather than with the `agb_rs__mixer_add_mono` family of
+/// assembly functions, so that it can be interpolated or have its loop crossfaded.
+#[derive(Clone, Copy)]
+struct SoftwareMono<'a> {
+    data: &'a [u8],
+    restart_point: Option<Num<u32, 8>>,
+    interpolation: Interpolation,
+    crossfade: Option<Crossfade>,
+}
+
+/// The samples at the end of a looping sound which are blended into the ones which lead up to
+/// the restart point.
+#[derive(Clone, Copy)]
+struct Crossfade {
+    start: u32,
+    length: u32,
+    restart_index: u32,
+    // the amount the blend changes by per sample, with 16 bits of precision
+    step: u32,
+}
+
+impl<'a> SoftwareMono<'a> {
+    fn new(
+        data: &'a [u8],
+        restart_point: Option<Num<u32, 8>>,
+        interpolation: Interpolation,
+        crossfade_length: u32,
+    ) -> Self {
+        let len = data.len() as u32;
+
+        let crossfade = restart_point.and_then(|restart_point| {
+            let restart_index = restart_point.floor();
+            let length = crossfade_length
+                .min(restart_index)
+                .min(len.saturating_sub(restart_index));
+
+            (length > 0).then(|| Crossfade {
+                start: len - length,
+                length,
+                restart_index,
+                step: (1u32 << 16).div_ceil(length),
+            })
+        });
+
+        Self {
+            data,
+            restart_point,
+            interpolation,
+            crossfade,
+        }
+    }
+
+    fn sample(&self, index: u32) -> i32 {
+        let raw_sample = |index: u32| {
+            self.data
+                .get(index as usize)
+                .map_or(0, |&s| i32::from(s as i8))
+        };
+
+        let value = raw_sample(index);
+
+        match self.crossfade {
+            Some(crossfade) if index >= crossfade.start => {
+                let offset = index - crossfade.start;
+                let target = raw_sample(crossfade.restart_index - crossfade.length + offset);
+                let amount = (((offset + 1) * crossfade.step) >> 8).min(256) as i32;
+
+                value + (((target - value) * amount) >> 8)
+            }
+            _ => value,
+        }
+    }
+
+    /// Adds the sound to a buffer of packed left and right samples, the same as the
+    /// `agb_rs__mixer_add_mono` family of assembly functions. Returns the new position in the
+    /// sound.
+    fn add_to_buffer(
+        &self,
+        buffer: &mut [i32],
+        mut pos: Num<u32, 8>,
+        playback_speed: Num<u32, 8>,
+        mul_amount: i32,
+        is_first: bool,
+    ) -> Num<u32, 8> {
+        let len = self.data.len() as u32;
+
+        let mut ended_at = None;
+
+        for (i, output) in buffer.iter_mut().enumerate() {
+            if pos.floor() >= len {
+                if let Some(restart_point) = self.restart_point {
+                    pos -= Num::new(len) - restart_point;
+                } else {
+                    ended_at = Some(i);
+                    break;
+                }
+            }
+
+            let index = pos.floor();
+            let current = self.sample(index);
+
+            let value = match self.interpolation {
+                Interpolation::Nearest => current,
+                Interpolation::Linear => {
+                    let next_index = if index + 1 < len {
+                        index + 1
+                    } else {
+                        self.restart_point.map_or(index, Num::floor)
+                    };
+
+                    current + (((self.sample(next_index) - current) * pos.frac() as i32) >> 8)
+                }
+            };
+            let value = value.wrapping_mul(mul_amount);
+
+            *output = if is_first {
+                value
+            } else {
+                output.wrapping_add(value)
+            };
+
+            pos += playback_speed;
+        }
+
+        if let Some(ended_at) = ended_at {
+            if is_first {
+                buffer[ended_at..].fill(0);
+            }
+        }
+
+        pos
+    }
+}
+
+/// A one pole low pass filter over interleaved stereo samples. `state` holds the
+/// previous output for each side with 4 extra bits of precision.
+fn apply_low_pass(buffer: &mut [Num<i16, 4>], amount: Num<i16, 8>, state: &mut [i32; 2]) {
+    let amount = i32::from(amount.to_raw());
+
+    for samples in buffer.chunks_exact_mut(2) {
+        for (sample, previous) in samples.iter_mut().zip(state.iter_mut()) {
+            let input = i32::from(sample.to_raw()) << 4;
+            *previous += ((input - *previous) * amount) >> 8;
+            *sample = Num::from_raw((*previous >> 4) as i16);
+        }
+    }
 }
 
 struct SoundBuffer(Box<[i8], InternalAllocator>);
@@ -417,27 +868,44 @@ impl MixerBuffer {
     fn write_channels<'a>(
         &self,
         working_buffer: &mut [Num<i16, 4>],
+        filter_buffer: &mut [Num<i16, 4>],
+        echo: Option<&mut Echo>,
+        pan_law: PanLaw,
+        interpolation: Interpolation,
         channels: impl Iterator<Item = &'a mut SoundChannel>,
     ) {
-        let mut channels = channels
-            .filter(|channel| !channel.is_done && channel.volume != 0.into() && channel.is_playing);
+        let channels = channels.filter(|channel| {
+            !channel.is_done && channel.effective_volume() != 0.into() && channel.is_playing
+        });
+
+        let mut is_first = true;
+
+        for channel in channels {
+            if let Some(amount) = channel.low_pass {
+                // filtered channels are mixed on their own so the filter only affects them
+                self.write_channel(channel, filter_buffer, pan_law, interpolation, true);
+                apply_low_pass(filter_buffer, amount, &mut channel.low_pass_state);
 
-        if let Some(channel) = channels.next() {
-            if channel.is_stereo {
-                self.write_stereo(channel, working_buffer, true);
+                if is_first {
+                    working_buffer.copy_from_slice(filter_buffer);
+                } else {
+                    for (output, filtered) in working_buffer.iter_mut().zip(filter_buffer.iter()) {
+                        *output += *filtered;
+                    }
+                }
             } else {
-                self.write_mono(channel, working_buffer, true);
+                self.write_channel(channel, working_buffer, pan_law, interpolation, is_first);
             }
-        } else {
+
+            is_first = false;
+        }
+
+        if is_first {
             working_buffer.fill(0.into());
         }
 
-        for channel in channels {
-            if channel.is_stereo {
-                self.write_stereo(channel, working_buffer, false);
-            } else {
-                self.write_mono(channel, working_buffer, false);
-            }
+        if let Some(echo) = echo {
+            echo.apply(working_buffer);
         }
 
         let write_buffer =
@@ -452,6 +920,21 @@ impl MixerBuffer {
         }
     }
 
+    fn write_channel(
+        &self,
+        channel: &mut SoundChannel,
+        working_buffer: &mut [Num<i16, 4>],
+        pan_law: PanLaw,
+        interpolation: Interpolation,
+        is_first: bool,
+    ) {
+        if channel.is_stereo {
+            self.write_stereo(channel, working_buffer, is_first);
+        } else {
+            self.write_mono(channel, working_buffer, pan_law, interpolation, is_first);
+        }
+    }
+
     fn write_stereo(
         &self,
         channel: &mut SoundChannel,
@@ -476,14 +959,14 @@ impl MixerBuffer {
                 agb_rs__mixer_add_stereo_first(
                     channel.data.as_ptr().add(channel.pos.floor() as usize),
                     working_buffer.as_mut_ptr(),
-                    channel.volume.change_base(),
+                    channel.effective_volume().change_base(),
                     self.frequency.buffer_size(),
                 );
             } else {
                 agb_rs__mixer_add_stereo(
                     channel.data.as_ptr().add(channel.pos.floor() as usize),
                     working_buffer.as_mut_ptr(),
-                    channel.volume.change_base(),
+                    channel.effective_volume().change_base(),
                     self.frequency.buffer_size(),
                 );
             }
@@ -496,10 +979,14 @@ impl MixerBuffer {
         &self,
         channel: &mut SoundChannel,
         working_buffer: &mut [Num<i16, 4>],
+        pan_law: PanLaw,
+        interpolation: Interpolation,
         is_first: bool,
     ) {
-        let right_amount = ((channel.panning + 1) / 2) * channel.volume;
-        let left_amount = ((-channel.panning + 1) / 2) * channel.volume;
+        let volume = channel.effective_volume();
+        let (left_gain, right_gain) = pan_law.gains(channel.panning);
+        let right_amount = right_gain * volume;
+        let left_amount = left_gain * volume;
 
         let right_amount: Num<i16, 4> = right_amount.change_base();
         let left_amount: Num<i16, 4> = left_amount.change_base();
@@ -522,6 +1009,31 @@ impl MixerBuffer {
         let mul_amount =
             ((left_amount.to_raw() as i32) << 16) | (right_amount.to_raw() as i32 & 0x0000ffff);
 
+        let has_crossfade = channel.should_loop && channel.loop_crossfade > 0;
+
+        if interpolation == Interpolation::Linear || has_crossfade {
+            let sound = SoftwareMono::new(
+                channel.data,
+                channel.should_loop.then_some(channel.restart_point),
+                interpolation,
+                channel.loop_crossfade,
+            );
+
+            channel.pos = sound.add_to_buffer(
+                working_buffer_i32,
+                channel.pos,
+                channel.playback_speed,
+                mul_amount,
+                is_first,
+            );
+
+            if !channel.should_loop {
+                channel.is_done = channel.pos >= channel_len;
+            }
+
+            return;
+        }
+
         macro_rules! call_mono_fn {
             ($fn_name:ident) => {
                 channel.pos = unsafe {
@@ -685,4 +1197,366 @@ mod test {
         );
         assert_eq!(result, num!(7.0));
     }
+
+    #[test_case]
+    fn low_pass_should_smooth_an_impulse(_: &mut crate::Gba) {
+        let mut buffer = vec![Num::<i16, 4>::new(0); 14];
+        buffer[0] = num!(8.0);
+        buffer[1] = num!(-8.0);
+
+        let mut state = [0; 2];
+        apply_low_pass(&mut buffer, num!(0.5), &mut state);
+
+        let left: alloc::vec::Vec<_> = buffer.iter().step_by(2).copied().collect();
+        let right: alloc::vec::Vec<_> = buffer.iter().skip(1).step_by(2).copied().collect();
+
+        assert_eq!(
+            left,
+            &[
+                num!(4.0),
+                num!(2.0),
+                num!(1.0),
+                num!(0.5),
+                num!(0.25),
+                num!(0.125),
+                num!(0.0625)
+            ]
+        );
+        assert_eq!(
+            right,
+            left.iter()
+                .map(|&sample| -sample)
+                .collect::<alloc::vec::Vec<_>>()
+        );
+    }
+
+    #[test_case]
+    fn pan_laws_split_the_volume_between_the_speakers(_: &mut crate::Gba) {
+        assert_eq!(PanLaw::Linear.gains(num!(-1.0)), (num!(1.0), num!(0.0)));
+        assert_eq!(PanLaw::Linear.gains(num!(0.0)), (num!(0.5), num!(0.5)));
+        assert_eq!(PanLaw::Linear.gains(num!(1.0)), (num!(0.0), num!(1.0)));
+
+        assert_eq!(
+            PanLaw::ConstantPower.gains(num!(-1.0)),
+            (num!(1.0), num!(0.0))
+        );
+        assert_eq!(
+            PanLaw::ConstantPower.gains(num!(1.0)),
+            (num!(0.0), num!(1.0))
+        );
+
+        let (left, right) = PanLaw::ConstantPower.gains(num!(0.0));
+        assert_eq!(left, right);
+        // 1 / sqrt(2) to within the precision of the table
+        assert!((left - num!(0.70710678)).abs() <= Num::from_raw(1));
+
+        // the total power should stay about the same wherever the sound is
+        for raw in (-256..=256).step_by(16) {
+            let (left, right) = PanLaw::ConstantPower.gains(Num::from_raw(raw));
+            let power = left * left + right * right;
+            assert!(
+                (power - 1).abs() <= num!(0.02),
+                "power {power} at panning {raw}"
+            );
+        }
+    }
+
+    fn add_mono_linear(
+        data: &[u8],
+        buffer: &mut [i32],
+        playback_speed: Num<u32, 8>,
+        restart_point: Option<Num<u32, 8>>,
+        is_first: bool,
+    ) -> Num<u32, 8> {
+        SoftwareMono::new(data, restart_point, Interpolation::Linear, 0).add_to_buffer(
+            buffer,
+            0.into(),
+            playback_speed,
+            1,
+            is_first,
+        )
+    }
+
+    #[test_case]
+    fn linear_interpolation_smooths_slow_sounds(_: &mut crate::Gba) {
+        let ramp = [0, 16, 32, 48, 64, 80, 96, 112];
+        let mut buffer = [1234; 8];
+
+        let pos = add_mono_linear(&ramp, &mut buffer, num!(0.5), None, true);
+
+        assert_eq!(buffer, [0, 8, 16, 24, 32, 40, 48, 56]);
+        assert_eq!(pos, 4.into());
+
+        // adds to what is already there if it isn't the first channel
+        add_mono_linear(&ramp, &mut buffer, num!(0.5), None, false);
+        assert_eq!(buffer, [0, 16, 32, 48, 64, 80, 96, 112]);
+    }
+
+    #[test_case]
+    fn linear_interpolation_handles_the_end_of_the_sound(_: &mut crate::Gba) {
+        let mut buffer = [1234; 8];
+        let pos = add_mono_linear(&[0, 64], &mut buffer, num!(0.5), None, true);
+
+        // nothing to interpolate towards after the last sample, and silence once it has ended
+        assert_eq!(buffer, [0, 32, 64, 64, 0, 0, 0, 0]);
+        assert_eq!(pos, 2.into());
+
+        let mut buffer = [1234; 8];
+        add_mono_linear(&[0, 64], &mut buffer, num!(0.5), Some(0.into()), true);
+
+        // interpolates towards the restart point when looping
+        assert_eq!(buffer, [0, 32, 64, 32, 0, 32, 64, 32]);
+    }
+
+    #[test_case]
+    fn loop_crossfade_smooths_the_jump_back_to_the_restart_point(_: &mut crate::Gba) {
+        // silence up to the restart point, then a ramp up to a loud sample at the end, so there
+        // is a big jump when it loops
+        let mut data = [0u8; 64];
+        for (i, sample) in data[32..].iter_mut().enumerate() {
+            *sample = (i as u8 * 4).min(100);
+        }
+
+        let largest_jump = |crossfade_length| {
+            let mut buffer = [0; 128];
+            SoftwareMono::new(
+                &data,
+                Some(32.into()),
+                Interpolation::Nearest,
+                crossfade_length,
+            )
+            .add_to_buffer(&mut buffer, 0.into(), 1.into(), 1, true);
+
+            buffer
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .max()
+                .unwrap()
+        };
+
+        assert_eq!(largest_jump(0), 100);
+        assert!(largest_jump(16) <= 8, "{}", largest_jump(16));
+
+        // the sound before the crossfade is unchanged
+        let mut buffer = [0; 48];
+        SoftwareMono::new(&data, Some(32.into()), Interpolation::Nearest, 16).add_to_buffer(
+            &mut buffer,
+            0.into(),
+            1.into(),
+            1,
+            true,
+        );
+        assert!(buffer
+            .iter()
+            .zip(data)
+            .all(|(&output, sample)| output == i32::from(sample as i8)));
+    }
+
+    static TEST_SOUND: &[u8] = &[0; 16];
+
+    #[test_case]
+    fn fade_out_should_stop_the_channel(_: &mut crate::Gba) {
+        let mut channel = SoundChannel::new(TEST_SOUND);
+        channel.volume(num!(0.5)).fade_out(4);
+
+        let mut volumes = alloc::vec::Vec::new();
+        for _ in 0..4 {
+            assert!(!channel.is_done);
+            channel.update_fade();
+            volumes.push(channel.effective_volume());
+        }
+
+        assert_eq!(volumes, &[num!(0.375), num!(0.25), num!(0.125), num!(0.0)]);
+        assert!(channel.is_done);
+    }
+
+    #[test_case]
+    fn fade_in_should_reach_the_channel_volume(_: &mut crate::Gba) {
+        let mut channel = SoundChannel::new(TEST_SOUND);
+        channel.fade_in(2);
+
+        assert_eq!(channel.effective_volume(), num!(0.0));
+        channel.update_fade();
+        assert_eq!(channel.effective_volume(), num!(0.5));
+        channel.update_fade();
+        assert_eq!(channel.effective_volume(), num!(1.0));
+
+        channel.update_fade();
+        assert_eq!(channel.effective_volume(), num!(1.0));
+        assert!(!channel.is_done);
+
+        channel.fade_out(0);
+        assert!(channel.is_done);
+    }
+
+    #[test_case]
+    fn echo_should_repeat_after_the_delay(_: &mut crate::Gba) {
+        let mut echo = Echo::new(2, 4, num!(0.5), num!(0.5));
+
+        let mut frames = alloc::vec::Vec::new();
+        for i in 0..6 {
+            let mut buffer = vec![Num::<i16, 4>::new(0); 4];
+            if i == 0 {
+                buffer[1] = num!(16.0);
+            }
+
+            echo.apply(&mut buffer);
+            frames.push(buffer[1]);
+
+            assert_eq!(buffer[0], 0.into(), "other samples should be unaffected");
+        }
+
+        assert_eq!(
+            frames,
+            &[
+                num!(16.0),
+                num!(0.0),
+                num!(8.0),
+                num!(0.0),
+                num!(4.0),
+                num!(0.0)
+            ]
+        );
+    }
+
+    #[test_case]
+    fn oneshot_sounds_free_their_channel_when_finished(gba: &mut crate::Gba) {
+        let mut mixer = gba.mixer.mixer(Frequency::Hz10512);
+        mixer.enable();
+
+        let vblank = crate::interrupt::VBlank::get();
+
+        assert!(mixer.play_oneshot(TEST_SOUND));
+        assert_eq!(mixer.channels.iter().flatten().count(), 1);
+
+        for _ in 0..3 {
+            vblank.wait_for_vblank();
+            mixer.frame();
+        }
+
+        assert_eq!(mixer.channels.iter().flatten().count(), 0);
+    }
+
+    #[test_case]
+    fn channel_counts_update_as_sounds_finish(gba: &mut crate::Gba) {
+        let mut mixer = gba.mixer.mixer(Frequency::Hz10512);
+        mixer.enable();
+
+        let vblank = crate::interrupt::VBlank::get();
+
+        assert_eq!(mixer.max_channels(), 8);
+        assert_eq!(mixer.free_channels(), 8);
+
+        let mut looping = SoundChannel::new(TEST_SOUND);
+        looping.should_loop();
+        let looping = mixer.play_sound(looping).unwrap();
+        mixer.channel(&looping).unwrap().pause();
+
+        for _ in 0..3 {
+            assert!(mixer.play_oneshot(TEST_SOUND));
+        }
+        assert_eq!(mixer.active_channels(), 4);
+        assert_eq!(mixer.free_channels(), 4);
+
+        for _ in 0..3 {
+            vblank.wait_for_vblank();
+            mixer.frame();
+        }
+
+        // the paused sound still holds on to its channel
+        assert_eq!(mixer.active_channels(), 1);
+        assert_eq!(mixer.free_channels(), 7);
+
+        mixer.channel(&looping).unwrap().stop();
+        assert_eq!(mixer.active_channels(), 0);
+    }
+
+    #[test_case]
+    fn old_channel_ids_report_that_their_sound_finished(gba: &mut crate::Gba) {
+        let mut mixer = gba.mixer.mixer(Frequency::Hz10512);
+        mixer.enable();
+
+        let vblank = crate::interrupt::VBlank::get();
+
+        let id = mixer.play_sound(SoundChannel::new(TEST_SOUND)).unwrap();
+        assert!(mixer.is_playing(&id));
+        assert!(!mixer.is_finished(&id));
+
+        mixer.channel(&id).unwrap().pause();
+        assert!(!mixer.is_playing(&id));
+        assert!(!mixer.is_finished(&id));
+        mixer.channel(&id).unwrap().resume();
+
+        for _ in 0..3 {
+            vblank.wait_for_vblank();
+            mixer.frame();
+        }
+
+        assert!(!mixer.is_playing(&id));
+        assert!(mixer.is_finished(&id));
+
+        // the new sound reuses the same channel, but the old id is still finished
+        let mut looping = SoundChannel::new(TEST_SOUND);
+        looping.should_loop();
+        let new_id = mixer.play_sound(looping).unwrap();
+
+        assert_eq!(new_id.0, id.0);
+        assert!(mixer.is_playing(&new_id));
+        assert!(!mixer.is_playing(&id));
+        assert!(mixer.is_finished(&id));
+    }
+
+    #[test_case]
+    fn last_frame_cycles_scales_with_channel_count(gba: &mut crate::Gba) {
+        static LOOPING_SOUND: &[u8] = &[0; 1024];
+
+        let mut mixer = gba.mixer.mixer(Frequency::Hz18157);
+        mixer.enable();
+
+        let vblank = crate::interrupt::VBlank::get();
+
+        let cycles_for_channels = |mixer: &mut Mixer, channels: usize| {
+            let ids: alloc::vec::Vec<_> = (0..channels)
+                .map(|_| {
+                    let mut channel = SoundChannel::new(LOOPING_SOUND);
+                    channel.should_loop();
+                    mixer.play_sound(channel).unwrap()
+                })
+                .collect();
+
+            // make sure the mixer has buffers ready to mix into
+            for _ in 0..3 {
+                vblank.wait_for_vblank();
+                mixer.frame();
+            }
+
+            let cycles = mixer.last_frame_cycles();
+
+            for id in &ids {
+                mixer.channel(id).unwrap().stop();
+            }
+            mixer.frame();
+
+            cycles
+        };
+
+        let no_channels = cycles_for_channels(&mut mixer, 0);
+        let one_channel = cycles_for_channels(&mut mixer, 1);
+        let four_channels = cycles_for_channels(&mut mixer, 4);
+
+        assert!(no_channels > 0);
+        assert!(
+            one_channel > no_channels,
+            "one channel ({one_channel} cycles) should take longer than none ({no_channels} cycles)"
+        );
+
+        // each channel should cost about the same, on top of the fixed cost of the mixer
+        let one_channel_cost = one_channel - no_channels;
+        let four_channel_cost = four_channels - no_channels;
+        assert!(
+            four_channel_cost > one_channel_cost * 3 && four_channel_cost < one_channel_cost * 5,
+            "four channels cost {four_channel_cost} cycles, but one costs {one_channel_cost} cycles"
+        );
+        assert!(four_channels < 280896);
+    }
 }
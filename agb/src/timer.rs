@@ -57,6 +57,91 @@ impl Timers<'_> {
             phantom: PhantomData,
         }
     }
+
+    /// Claims both timers to measure elapsed time, see [`Stopwatch`].
+    pub fn stopwatch(&mut self) -> Stopwatch<'_> {
+        Stopwatch::new(&mut self.timer2, &mut self.timer3)
+    }
+}
+
+/// Counts CPU cycles for profiling, using timer 2 as the low 16 bits of the count and timer 3
+/// cascading from it as the high 16 bits. This counts for about 4 minutes before wrapping.
+///
+/// The mixer uses timers 0 and 1 to play sound, so a stopwatch can be used alongside it. Time
+/// spent in interrupt handlers, including the mixer's, is counted too.
+pub struct Stopwatch<'a> {
+    low: &'a mut Timer,
+    high: &'a mut Timer,
+    started: bool,
+}
+
+impl<'a> Stopwatch<'a> {
+    fn new(low: &'a mut Timer, high: &'a mut Timer) -> Self {
+        low.set_divider(Divider::Divider1).set_cascade(false);
+        high.set_cascade(true);
+
+        let mut stopwatch = Self {
+            low,
+            high,
+            started: false,
+        };
+        stopwatch.reset();
+        stopwatch
+    }
+
+    /// Starts counting. Calling this while the stopwatch is already running does nothing.
+    pub fn start(&mut self) -> &mut Self {
+        self.high.set_enabled(true);
+        self.low.set_enabled(true);
+        self.started = true;
+        self
+    }
+
+    /// Stops the stopwatch and sets the count back to zero.
+    pub fn reset(&mut self) -> &mut Self {
+        self.low.set_enabled(false);
+        self.high.set_enabled(false);
+
+        // a disabled timer reloads the overflow amount when it is next enabled, but until then
+        // it keeps reading back its old count, so `elapsed_cycles` can't rely on the timers
+        self.low.set_overflow_amount(0);
+        self.high.set_overflow_amount(0);
+        self.started = false;
+        self
+    }
+
+    /// The number of cycles since the stopwatch was started, or 0 if it hasn't been started since
+    /// it was last reset.
+    #[must_use]
+    pub fn elapsed_cycles(&self) -> u32 {
+        if !self.started {
+            return 0;
+        }
+
+        loop {
+            let high = self.high.value();
+            let low = self.low.value();
+
+            // if the low timer overflowed between the two reads, then it doesn't match the high
+            // timer so we need to try again
+            if self.high.value() == high {
+                return (u32::from(high) << 16) | u32::from(low);
+            }
+        }
+    }
+
+    /// Runs `f` from a freshly reset stopwatch and prints how many cycles it took to the mgba
+    /// log, returning the result of `f`.
+    pub fn profile<T>(&mut self, name: &str, f: impl FnOnce() -> T) -> T {
+        self.reset().start();
+        let result = f();
+        let cycles = self.elapsed_cycles();
+        self.reset();
+
+        crate::println!("{} took {} cycles", name, cycles);
+
+        result
+    }
 }
 
 impl Timer {
@@ -139,3 +224,47 @@ impl TimerController {
         unsafe { Timers::new() }
     }
 }
+
+#[cfg(test)]
+mod test {
+    fn busy_loop(iterations: u32) -> u32 {
+        let mut total = 0u32;
+        for i in 0..iterations {
+            total = core::hint::black_box(total.wrapping_add(i));
+        }
+        total
+    }
+
+    #[test_case]
+    fn stopwatch_counts_up_from_zero(gba: &mut crate::Gba) {
+        let mut timers = gba.timers.timers();
+        let mut stopwatch = timers.stopwatch();
+
+        assert_eq!(stopwatch.elapsed_cycles(), 0);
+
+        stopwatch.start();
+        busy_loop(10);
+        let short = stopwatch.elapsed_cycles();
+        busy_loop(50_000);
+        let long = stopwatch.elapsed_cycles();
+
+        assert!(short > 0);
+        // 50,000 iterations is well over the 65,536 cycles of a single timer
+        assert!(
+            long > short + 0x1_0000,
+            "{long} should be more than {short}"
+        );
+
+        stopwatch.reset();
+        assert_eq!(stopwatch.elapsed_cycles(), 0);
+    }
+
+    #[test_case]
+    fn profile_returns_the_result(gba: &mut crate::Gba) {
+        let mut timers = gba.timers.timers();
+        let mut stopwatch = timers.stopwatch();
+
+        assert_eq!(stopwatch.profile("busy loop", || busy_loop(4)), 6);
+        assert_eq!(stopwatch.elapsed_cycles(), 0);
+    }
+}
@@ -634,13 +634,14 @@ impl<'a, 'b> PlayingLevel<'a, 'b> {
 
         let start_pos: Vector2D<FixedNumberType> = level.start_pos.into();
 
-        let background_position = (
-            (start_pos.x - WIDTH / 2)
-                .clamp(0.into(), ((level.dimensions.x * 8) as i32 - WIDTH).into()),
-            (start_pos.y - HEIGHT / 2)
-                .clamp(0.into(), ((level.dimensions.y * 8) as i32 - HEIGHT).into()),
+        let half_screen: Vector2D<FixedNumberType> = (WIDTH / 2, HEIGHT / 2).into();
+        let max_background_position: Vector2D<FixedNumberType> = (
+            (level.dimensions.x * 8) as i32 - WIDTH,
+            (level.dimensions.y * 8) as i32 - HEIGHT,
         )
             .into();
+        let background_position =
+            (start_pos - half_screen).clamp((0, 0).into(), max_background_position);
 
         PlayingLevel {
             timer: 0,
@@ -759,18 +760,15 @@ impl<'a, 'b> PlayingLevel<'a, 'b> {
         let half_screen = screen / 2;
         let current_centre = self.background.position.floor() + half_screen;
 
-        let mut target_position = ((current_centre * 3 + new_target_position) / 4) - half_screen;
+        let target_position = ((current_centre * 3 + new_target_position) / 4) - half_screen;
 
-        target_position.x = target_position.x.clamp(
-            0,
+        let max_position: Vector2D<i32> = (
             (self.background.level.dimensions.x * 8 - (WIDTH as u32)) as i32,
-        );
-        target_position.y = target_position.y.clamp(
-            0,
             (self.background.level.dimensions.y * 8 - (HEIGHT as u32)) as i32,
-        );
+        )
+            .into();
 
-        target_position.into()
+        target_position.clamp((0, 0).into(), max_position).into()
     }
 }
 
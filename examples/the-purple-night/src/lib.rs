@@ -259,34 +259,28 @@ impl<'a> Entity<'a> {
     }
 
     fn commit_with_fudge(&mut self, offset: Vector2D<Number>, fudge: Vector2D<Number>) {
-        if !self.visible {
-            self.sprite.hide();
-        } else {
-            let position =
-                (self.position - offset + fudge + Vector2D::new(num!(0.5), num!(0.5))).floor();
-            self.sprite.set_position(position - (8, 8).into());
-            if position.x < -8
-                || position.x > WIDTH + 8
-                || position.y < -8
-                || position.y > HEIGHT + 8
-            {
-                self.sprite.hide();
-            } else {
-                self.sprite.show();
-            }
-        }
+        let position =
+            (self.position - offset + fudge + Vector2D::new(num!(0.5), num!(0.5))).floor();
+        self.commit_centred_at(position);
+    }
+
+    fn commit(&mut self, offset: Vector2D<Number>) {
+        let position = (self.position - offset).floor();
+        self.commit_centred_at(position);
     }
 
-    fn commit_with_size(&mut self, offset: Vector2D<Number>, size: Vector2D<i32>) {
+    fn commit_centred_at(&mut self, position: Vector2D<i32>) {
         if !self.visible {
             self.sprite.hide();
         } else {
-            let position = (self.position - offset).floor();
+            let size = self.sprite.bounding_box().size;
             self.sprite.set_position(position - size / 2);
-            if position.x < -8
-                || position.x > WIDTH + 8
-                || position.y < -8
-                || position.y > HEIGHT + 8
+
+            let half_size = size / 2;
+            if position.x < -half_size.x
+                || position.x > WIDTH + half_size.x
+                || position.y < -half_size.y
+                || position.y > HEIGHT + half_size.y
             {
                 self.sprite.hide();
             } else {
@@ -1789,8 +1783,7 @@ impl<'a> Boss<'a> {
             (0, 0).into()
         };
 
-        self.entity
-            .commit_with_size(offset + shake, (32, 32).into());
+        self.entity.commit(offset + shake);
     }
     fn explode(&self, enemies: &mut Arena<Enemy<'a>>, object_controller: &'a OamManaged) {
         for _ in 0..(6 - self.health) {
@@ -429,7 +429,8 @@ fn midi_key_to_speed(key: i16, sample: &SampleData, tune: f64) -> Num<u16, 8> {
     let relative_note = sample.note_offset as f64;
 
     Num::from_f64(
-        2f64.powf((key as f64 - relative_note + tune + 1.0) / 12.0) * sample_rate / 32768.0,
+        2f64.powf((key as f64 - relative_note + tune + 1.0) / 12.0) * sample_rate
+            / agb_tracker_interop::TRACK_FREQUENCY as f64,
     )
 }
 
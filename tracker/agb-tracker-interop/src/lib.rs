@@ -5,6 +5,10 @@ extern crate alloc;
 use agb_fixnum::Num;
 use alloc::borrow::Cow;
 
+/// The mixer frequency which the playback speeds stored in a [`Track`] are relative to.
+/// Players running at a different frequency need to scale the speeds accordingly.
+pub const TRACK_FREQUENCY: u32 = 32768;
+
 #[derive(Debug)]
 pub struct Track {
     pub samples: Cow<'static, [Sample]>,
@@ -93,7 +97,9 @@ pub enum PatternEffect {
     FinePortamento(Num<u16, 12>),
     /// Slide each tick the first amount to at most the second amount
     TonePortamento(Num<u16, 12>, Num<u16, 12>),
-    Vibrato(Waveform, Num<u16, 12>, u8),
+    Vibrato(Num<u16, 12>, u8),
+    /// Change the waveform used by vibrato on this channel until it is changed again
+    SetVibratoWaveform(Waveform),
     SetTicksPerStep(u32),
     SetFramesPerTick(Num<u32, 8>),
     SetGlobalVolume(Num<i32, 8>),
@@ -104,6 +110,10 @@ pub enum PatternEffect {
     SampleOffset(u16),
     /// Retrigger the note every u8 ticks with the volume change specified
     Retrigger(RetriggerVolumeChange, u8),
+    /// Jump to the given frame of the volume envelope
+    SetEnvelopePosition(u16),
+    /// A marker for the game to react to, which doesn't change the sound
+    Event(u8),
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -421,10 +431,11 @@ impl quote::ToTokens for PatternEffect {
                 let amount = amount.to_raw();
                 quote! { PitchBend(agb_tracker::__private::Num::from_raw(#amount)) }
             }
-            PatternEffect::Vibrato(waveform, amount, speed) => {
+            PatternEffect::Vibrato(amount, speed) => {
                 let amount = amount.to_raw();
-                quote! { Vibrato(#waveform, agb_tracker::__private::Num::from_raw(#amount), #speed) }
+                quote! { Vibrato(agb_tracker::__private::Num::from_raw(#amount), #speed) }
             }
+            PatternEffect::SetVibratoWaveform(waveform) => quote! { SetVibratoWaveform(#waveform) },
             PatternEffect::Jump(jump) => {
                 quote! { Jump(#jump) }
             }
@@ -432,6 +443,8 @@ impl quote::ToTokens for PatternEffect {
             PatternEffect::Retrigger(retrigger_volume_change, ticks) => {
                 quote! { Retrigger(#retrigger_volume_change, #ticks) }
             }
+            PatternEffect::SetEnvelopePosition(frame) => quote! { SetEnvelopePosition(#frame) },
+            PatternEffect::Event(event) => quote! { Event(#event) },
         };
 
         tokens.append_all(quote! {
@@ -49,8 +49,21 @@
 //! }
 //! ```
 //!
-//! Note that currently you have to select 32768Hz as the frequency for the mixer.
-//! This restriction will be lifted in a future version.
+//! The tracker works with any mixer frequency, and adjusts the playback speed of the samples
+//! so that they stay in tune. Lower frequencies use less CPU time at the cost of sound quality.
+//!
+//! # Playing more than one track
+//!
+//! A tracker doesn't own any particular mixer channels. Each note asks the mixer for a free
+//! channel with [`Mixer::play_sound`] and the tracker only ever stops channels it was given,
+//! so you can step several trackers, for example some music and an ambient loop, with the same
+//! mixer alongside your sound effects.
+//!
+//! Every channel of every track which is currently playing a note uses up one of the mixer's
+//! channels, and `agb`'s mixer has 8. Once they are all in use, new notes are dropped until
+//! one frees up, and high priority sound effects will take over channels from the tracker. So
+//! make sure the total number of channels in the tracks you play at once, plus the sound
+//! effects you expect to overlap with them, fits in that budget.
 //!
 //! # Concepts
 //!
@@ -67,15 +80,25 @@ extern crate alloc;
 
 mod lookups;
 mod mixer;
+mod playback_state;
 
 use agb_tracker_interop::{Jump, PatternEffect, Sample, Waveform};
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 
 pub use mixer::{Mixer, SoundChannel};
+pub use playback_state::PlaybackState;
 
 use agb_fixnum::Num;
 
 /// Import an XM file. Only available if you have the `xm` feature enabled (enabled by default).
+///
+/// Any samples which are never played by the track are removed to save ROM space. If you need
+/// to keep these (because you play them yourself for example), pass `keep_unused_samples` as a
+/// second argument. This also applies to [`include_s3m`] and [`include_mod`].
+///
+/// ```rust,ignore
+/// static DB_TOFFE: Track = include_xm!("examples/db_toffe.xm", keep_unused_samples);
+/// ```
 #[cfg(feature = "xm")]
 pub use agb_xm::include_xm;
 
@@ -94,6 +117,58 @@ pub use agb_xm::include_mod;
 #[cfg(feature = "midi")]
 pub use agb_midi::include_midi;
 
+/// Parses an XM file while the game is running, for tracks which aren't known at compile time
+/// such as ones loaded from save data. Only available if you have the `xm-runtime` feature
+/// enabled (disabled by default). Like [`include_xm`], any samples which are never played are
+/// removed.
+///
+/// Prefer [`include_xm`] wherever you can. Enabling this feature adds the XM parser and
+/// software floating point routines to your ROM, parsing takes a long time on the GBA so should
+/// be done during a loading screen rather than while music is playing, and the returned track's
+/// patterns and samples are copied into RAM, of which there is only 256kB.
+///
+/// A [`Tracker`] plays a `&'static Track`, so a track which is played until the game ends can be
+/// leaked to give it a `'static` lifetime.
+///
+/// ```rust,no_run
+/// #![no_std]
+/// #![no_main]
+/// # extern crate alloc;
+///
+/// use agb::{Gba, sound::mixer::Frequency};
+/// use agb_tracker::{Track, Tracker};
+/// use alloc::boxed::Box;
+///
+/// # fn load_xm_file() -> &'static [u8] { &[] }
+/// #[agb::entry]
+/// fn main(mut gba: Gba) -> ! {
+///     let vblank_provider = agb::interrupt::VBlank::get();
+///     let xm_file_contents = load_xm_file();
+///
+///     let track = agb_tracker::parse_xm(xm_file_contents).expect("not a valid XM file");
+///     let track: &'static Track = Box::leak(Box::new(track));
+///
+///     let mut mixer = gba.mixer.mixer(Frequency::Hz32768);
+///     mixer.enable();
+///
+///     let mut tracker = Tracker::new(track);
+///
+///     loop {
+///         tracker.step(&mut mixer);
+///         mixer.frame();
+///
+///         vblank_provider.wait_for_vblank();
+///     }
+/// }
+/// ```
+#[cfg(feature = "xm-runtime")]
+pub use agb_xm_core::parse_xm;
+
+/// The error returned by [`parse_xm`] if the data can't be played by the tracker. Only available
+/// if you have the `xm-runtime` feature enabled.
+#[cfg(feature = "xm-runtime")]
+pub use agb_xm_core::ParseError;
+
 #[doc(hidden)]
 pub mod __private {
     pub use agb_fixnum::Num;
@@ -116,10 +191,61 @@ pub struct TrackerInner<'track, TChannelId> {
     first: bool,
 
     global_settings: GlobalSettings,
+    volume: Num<i32, 8>,
+    volume_fade: Option<VolumeFade>,
 
     current_row: usize,
     current_pattern: usize,
     current_jump: Option<Jump>,
+
+    repeat: usize,
+    looping: bool,
+    finished: bool,
+    swing: Num<i32, 8>,
+
+    on_row: Option<Box<dyn FnMut(TrackerEvent) + 'track>>,
+}
+
+/// Something which happened while playing a track, passed to the callback given to
+/// [`on_row`](TrackerInner::on_row()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerEvent {
+    /// A new row has started playing.
+    Row {
+        /// The position in the pattern order which is playing
+        order_position: usize,
+        /// The pattern which is playing
+        pattern: usize,
+        /// The row within that pattern
+        row: usize,
+    },
+    /// The row which just started contains a `Zxx` effect with the given parameter. The tracker
+    /// does nothing else with these, so composers can use them to mark points in the track which
+    /// the game should react to.
+    Event(u8),
+}
+
+/// What one of the track's channels is currently playing, returned by
+/// [`channel_state`](TrackerInner::channel_state()). Useful for visualisers which react to the
+/// music.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelState {
+    /// Whether the channel has started playing a note. This is false before the channel's first
+    /// note and while a note is delayed. Notes which the track cuts or stops keep playing with a
+    /// volume of 0, and the tracker doesn't know when a sample which doesn't loop reaches its
+    /// end, so this stays true in both of those cases.
+    pub is_playing: bool,
+    /// The volume the channel is played at, between 0 and 1. This includes the track's volume
+    /// effects and envelopes, the [global volume](TrackerInner::global_volume()) and the
+    /// [tracker volume](TrackerInner::volume()), and is 0 if the channel is
+    /// [disabled](TrackerInner::set_channel_enabled()).
+    pub volume: Num<i32, 8>,
+    /// How fast the channel's sample is being played, relative to the sample being played at its
+    /// original pitch in a 32768Hz mixer. Doubling this is an octave higher. This doesn't include
+    /// vibrato.
+    pub speed: Num<u32, 16>,
+    /// The panning of the channel, between -1 (left) and 1 (right).
+    pub panning: Num<i32, 8>,
 }
 
 #[derive(Default)]
@@ -134,9 +260,14 @@ struct TrackerChannel {
     current_speed: Num<u32, 16>,
     current_panning: Num<i32, 8>,
     is_playing: bool,
+    is_muted: bool,
 
     // if some, should set the current position to this
-    current_pos: Option<u16>,
+    current_pos: Option<u32>,
+
+    // where the sample offset effect can start the current sample from
+    sample_length: u32,
+    sample_restart_point: Option<u32>,
 }
 
 #[derive(Default)]
@@ -176,6 +307,13 @@ struct EnvelopeState {
     vibrato_pos: usize,
 }
 
+struct VolumeFade {
+    from: Num<i32, 8>,
+    to: Num<i32, 8>,
+    frame: u32,
+    total_frames: u32,
+}
+
 #[derive(Clone)]
 struct GlobalSettings {
     ticks_per_step: u32,
@@ -213,16 +351,266 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
             tick: 0,
 
             global_settings,
+            volume: 1.into(),
+            volume_fade: None,
 
             current_pattern: 0,
             current_row: 0,
             current_jump: None,
+
+            repeat: track.repeat,
+            looping: true,
+            finished: false,
+            swing: 0.into(),
+
+            on_row: None,
+        }
+    }
+
+    /// Create a new tracker which starts playing the track from the given position in the pattern
+    /// order and the given row within that pattern, rather than from the very beginning.
+    ///
+    /// This is useful if your track contains multiple sections, and you want to start at a section
+    /// other than the first one. Combine this with [`set_repeat`](TrackerInner::set_repeat()) to
+    /// control where the track loops back to once it has finished.
+    ///
+    /// Panics if `order_position` is past the end of the pattern order, or if `row` is past the end
+    /// of the pattern at that position.
+    pub fn new_at(track: &'track Track, order_position: usize, row: usize) -> Self {
+        assert!(
+            order_position < track.patterns_to_play.len(),
+            "order position {order_position} out of range for track with {} patterns in its order",
+            track.patterns_to_play.len()
+        );
+
+        let pattern_length = track.patterns[track.patterns_to_play[order_position]].length;
+        assert!(
+            row < pattern_length,
+            "row {row} out of range for pattern with {pattern_length} rows"
+        );
+
+        let mut tracker = Self::new(track);
+        tracker.current_pattern = order_position;
+        tracker.current_row = row;
+
+        tracker
+    }
+
+    /// Sets the position in the pattern order which the track will loop back to once it reaches
+    /// the end. This overrides the repeat position stored in the track itself, which comes from
+    /// the restart position in the XM file and is 0 unless the composer set it.
+    ///
+    /// This is how to play a track with an intro: the patterns before `order_position` play once
+    /// from the start, and after that only the patterns from `order_position` to the end of the
+    /// pattern order are repeated.
+    ///
+    /// Panics if `order_position` is past the end of the pattern order.
+    pub fn set_repeat(&mut self, order_position: usize) {
+        assert!(
+            order_position < self.track.patterns_to_play.len(),
+            "order position {order_position} out of range for track with {} patterns in its order",
+            self.track.patterns_to_play.len()
+        );
+
+        self.repeat = order_position;
+    }
+
+    /// Sets whether the track loops back to the [repeat position](TrackerInner::set_repeat())
+    /// once it reaches the end of its pattern order, which it does by default. When not looping,
+    /// the track instead stops all of its channels and [`is_finished`](TrackerInner::is_finished())
+    /// becomes true, which is useful for one off jingles.
+    ///
+    /// Jumps within the track are still followed, so a track which jumps backwards itself will
+    /// keep playing regardless. Turning looping back on doesn't restart a finished track.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Sets how much swing to play the track with, which makes alternate rows last for different
+    /// lengths of time to give it a less mechanical feel. Each tick of an even row lasts for
+    /// `1 + amount` times as many frames as the track's tempo says, and each tick of an odd row
+    /// lasts for `1 - amount` times as many, so each pair of rows takes the same time as it
+    /// would without swing. For example, an amount of 1/3 makes even rows twice as long as odd
+    /// rows. A negative amount makes the odd rows the longer ones instead, and 0, the default,
+    /// turns swing off.
+    ///
+    /// Swing is applied on top of the track's tempo rather than changing it, so it stays in
+    /// proportion when the track changes its speed or tempo with its effects. Rows are counted
+    /// from the start of each pattern, so the pattern lengths should be even for the swing to
+    /// carry on across patterns.
+    ///
+    /// Panics if `amount` is not strictly between -1 and 1.
+    pub fn set_swing(&mut self, amount: Num<i32, 8>) {
+        assert!(
+            amount > (-1).into() && amount < 1.into(),
+            "swing must be between -1 and 1"
+        );
+
+        self.swing = amount;
+    }
+
+    /// The amount of swing set with [`set_swing`](TrackerInner::set_swing()).
+    #[must_use]
+    pub fn swing(&self) -> Num<i32, 8> {
+        self.swing
+    }
+
+    /// Whether the track has reached its end with [looping](TrackerInner::set_looping())
+    /// disabled. Once finished, [`step`](TrackerInner::step()) does nothing.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Calls `callback` every time a new row of the track starts playing, with a
+    /// [`TrackerEvent::Row`] giving the position in the track followed by a [`TrackerEvent::Event`]
+    /// for each `Zxx` effect in that row. This is called from within [`step`](TrackerInner::step()),
+    /// so is useful for synchronising things happening in your game with the music.
+    ///
+    /// Calling this again replaces the previous callback. The tracker does no extra work when
+    /// there is no callback, see [`clear_on_row`](TrackerInner::clear_on_row()).
+    pub fn on_row(&mut self, callback: impl FnMut(TrackerEvent) + 'track) {
+        self.on_row = Some(Box::new(callback));
+    }
+
+    /// Removes the callback set by [`on_row`](TrackerInner::on_row()).
+    pub fn clear_on_row(&mut self) {
+        self.on_row = None;
+    }
+
+    /// Enables or disables the given channel of the track.
+    ///
+    /// A disabled channel continues to advance exactly as it would if it were playing, but
+    /// its sound is silenced. Re-enabling the channel will therefore resume it in sync with
+    /// the rest of the track. This is useful for adding or removing instruments based on what
+    /// is happening in your game.
+    ///
+    /// Note that the agb mixer doesn't advance silent sounds, so a note which was silenced part
+    /// way through will continue from where it was silenced. Any notes started after re-enabling
+    /// will be perfectly in sync.
+    ///
+    /// Panics if `channel` is not a valid channel for the current track.
+    pub fn set_channel_enabled(&mut self, channel: usize, enabled: bool) {
+        self.channels[channel].is_muted = !enabled;
+    }
+
+    /// Whether the given channel is currently enabled. See [`set_channel_enabled`](TrackerInner::set_channel_enabled())
+    /// for more details.
+    ///
+    /// Panics if `channel` is not a valid channel for the current track.
+    #[must_use]
+    pub fn is_channel_enabled(&self, channel: usize) -> bool {
+        !self.channels[channel].is_muted
+    }
+
+    /// Enables only the given channel, disabling every other channel. Passing `None` will
+    /// enable every channel again.
+    ///
+    /// Panics if `channel` is not a valid channel for the current track.
+    pub fn solo_channel(&mut self, channel: Option<usize>) {
+        if let Some(channel) = channel {
+            assert!(
+                channel < self.channels.len(),
+                "channel {channel} out of range for track with {} channels",
+                self.channels.len()
+            );
+        }
+
+        for (i, tracker_channel) in self.channels.iter_mut().enumerate() {
+            tracker_channel.is_muted = channel.is_some_and(|channel| channel != i);
+        }
+    }
+
+    /// What the given channel of the track is currently playing. See [`ChannelState`] for
+    /// details.
+    ///
+    /// Panics if `channel` is not a valid channel for the current track.
+    #[must_use]
+    pub fn channel_state(&self, channel: usize) -> ChannelState {
+        let channel = &self.channels[channel];
+
+        ChannelState {
+            is_playing: channel.is_playing,
+            volume: if channel.is_muted {
+                0.into()
+            } else {
+                channel.current_volume * self.volume
+            },
+            speed: channel.current_speed,
+            panning: channel.current_panning,
+        }
+    }
+
+    /// The current volume of the tracker, between 0 and 1. This is controlled by
+    /// [`fade_volume_to`](TrackerInner::fade_volume_to()) and defaults to 1.
+    #[must_use]
+    pub fn volume(&self) -> Num<i32, 8> {
+        self.volume
+    }
+
+    /// Smoothly changes the volume of the tracker to `target` over the next `frames` calls to
+    /// [`step`](TrackerInner::step()). Passing 0 frames changes the volume immediately. Useful for
+    /// fading the music in and out when pausing the game or moving between areas.
+    ///
+    /// This volume is applied on top of any volume changes made by the track itself, so a track
+    /// which sets its own global volume to 0.5 while the tracker volume is 0.5 will play at 0.25.
+    ///
+    /// Panics if `target` is not between 0 and 1.
+    pub fn fade_volume_to(&mut self, target: Num<i32, 8>, frames: u32) {
+        assert!(
+            target >= 0.into() && target <= 1.into(),
+            "volume must be between 0 and 1"
+        );
+
+        if frames == 0 {
+            self.volume = target;
+            self.volume_fade = None;
+        } else {
+            self.volume_fade = Some(VolumeFade {
+                from: self.volume,
+                to: target,
+                frame: 0,
+                total_frames: frames,
+            });
         }
     }
 
+    /// The track's global volume, between 0 and 1. This starts at 1 and is changed by
+    /// [`set_global_volume`](TrackerInner::set_global_volume()) and by the track's own global
+    /// volume effects.
+    #[must_use]
+    pub fn global_volume(&self) -> Num<i32, 8> {
+        self.global_settings.volume
+    }
+
+    /// Sets the track's global volume, the same value the track changes with its set global
+    /// volume and global volume slide effects. A set global volume effect later in the track
+    /// replaces this value, and a global volume slide continues on from it.
+    ///
+    /// Like those effects, the new volume is only applied to each channel the next time the
+    /// track changes that channel's volume. To scale the tracker's output immediately, for example
+    /// to duck the music while a character speaks, use
+    /// [`fade_volume_to`](TrackerInner::fade_volume_to()) instead, which the track can't override.
+    ///
+    /// Panics if `volume` is not between 0 and 1.
+    pub fn set_global_volume(&mut self, volume: Num<i32, 8>) {
+        assert!(
+            volume >= 0.into() && volume <= 1.into(),
+            "volume must be between 0 and 1"
+        );
+
+        self.global_settings.volume = volume;
+    }
+
     /// Call this once per frame before calling [`mixer.frame`](agb::sound::mixer::Mixer::frame()).
     /// See the [example](crate#example) for how to use the tracker.
-    pub fn step<M: Mixer<ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
+    pub fn step<M: Mixer<'track, ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
+        if self.finished {
+            return;
+        }
+
+        self.update_volume_fade();
+
         if !self.increment_frame() {
             self.update_envelopes();
 
@@ -230,6 +618,11 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
             return;
         }
 
+        if self.finished {
+            self.stop(mixer);
+            return;
+        }
+
         let pattern_to_play = self.track.patterns_to_play[self.current_pattern];
         let current_pattern = &self.track.patterns[pattern_to_play];
 
@@ -240,7 +633,17 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
 
         for (i, (channel, pattern_slot)) in self.channels.iter_mut().zip(pattern_slots).enumerate()
         {
-            if pattern_slot.sample != 0 && self.tick == 0 {
+            // delayed notes start from scratch once the delay is over, so their envelope
+            // doesn't advance while they are waiting
+            let trigger_tick = [&pattern_slot.effect1, &pattern_slot.effect2]
+                .into_iter()
+                .find_map(|effect| match effect {
+                    PatternEffect::NoteDelay(wait) => Some(*wait),
+                    _ => None,
+                })
+                .unwrap_or(0);
+
+            if pattern_slot.sample != 0 && self.tick == trigger_tick {
                 let sample = &self.track.samples[pattern_slot.sample as usize - 1];
 
                 if let Some(channel) = self.mixer_channels[i]
@@ -271,7 +674,7 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
                 });
             }
 
-            if self.tick == 0 {
+            if self.tick == trigger_tick {
                 channel.set_speed(pattern_slot.speed.change_base());
             }
 
@@ -293,6 +696,24 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
             );
         }
 
+        if self.tick == 0 {
+            if let Some(on_row) = &mut self.on_row {
+                on_row(TrackerEvent::Row {
+                    order_position: self.current_pattern,
+                    pattern: pattern_to_play,
+                    row: self.current_row,
+                });
+
+                for pattern_slot in pattern_slots {
+                    for effect in [&pattern_slot.effect1, &pattern_slot.effect2] {
+                        if let PatternEffect::Event(event) = effect {
+                            on_row(TrackerEvent::Event(*event));
+                        }
+                    }
+                }
+            }
+        }
+
         self.update_envelopes();
         self.realise(mixer);
     }
@@ -301,7 +722,7 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
     ///
     /// It is expected that you don't call step after this. But doing so will continue from
     /// where you left off. However, notes which were playing won't resume.
-    pub fn stop<M: Mixer<ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
+    pub fn stop<M: Mixer<'track, ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
         for channel_id in &mut self.mixer_channels {
             if let Some(channel) = channel_id
                 .take()
@@ -312,7 +733,27 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
         }
     }
 
-    fn realise<M: Mixer<ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
+    fn update_volume_fade(&mut self) {
+        let Some(fade) = &mut self.volume_fade else {
+            return;
+        };
+
+        fade.frame += 1;
+
+        if fade.frame >= fade.total_frames {
+            self.volume = fade.to;
+            self.volume_fade = None;
+        } else {
+            self.volume =
+                fade.from + (fade.to - fade.from) * fade.frame as i32 / fade.total_frames as i32;
+        }
+    }
+
+    fn realise<M: Mixer<'track, ChannelId = TChannelId>>(&mut self, mixer: &mut M) {
+        // the speeds in the track are relative to TRACK_FREQUENCY, so need scaling to the mixer's
+        let speed_scale =
+            Num::<u32, 16>::new(agb_tracker_interop::TRACK_FREQUENCY) / mixer.frequency();
+
         for (i, (mixer_channel, tracker_channel)) in self
             .mixer_channels
             .iter()
@@ -344,12 +785,20 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
                     }
                 }
 
-                channel.playback(current_speed.change_base());
-                channel.volume(tracker_channel.current_volume.try_change_base().unwrap());
+                channel.playback((current_speed * speed_scale).change_base());
+                if tracker_channel.is_muted {
+                    channel.volume(0);
+                } else {
+                    channel.volume(
+                        (tracker_channel.current_volume * self.volume)
+                            .try_change_base()
+                            .unwrap(),
+                    );
+                }
                 channel.panning(tracker_channel.current_panning.try_change_base().unwrap());
 
                 if let Some(offset) = tracker_channel.current_pos.take() {
-                    channel.set_pos(offset as u32);
+                    channel.set_pos(offset);
                 }
 
                 if tracker_channel.is_playing {
@@ -366,6 +815,9 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
             if let Some(envelope_state) = envelope_state_option {
                 let envelope = &self.track.envelopes[envelope_state.envelope_id];
 
+                // the envelope position can be set past the end of the envelope by an effect
+                envelope_state.frame = envelope_state.frame.min(envelope.amount.len() - 1);
+
                 if !channel.update_volume_envelope(envelope_state, envelope, &self.global_settings)
                 {
                     envelope_state_option.take();
@@ -402,9 +854,10 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
 
         self.frame += 1;
 
-        if self.frame >= self.global_settings.frames_per_tick {
+        let frames_per_tick = self.swung_frames_per_tick();
+        if self.frame >= frames_per_tick {
             self.tick += 1;
-            self.frame -= self.global_settings.frames_per_tick;
+            self.frame -= frames_per_tick;
 
             if self.tick >= self.global_settings.ticks_per_step {
                 if let Some(jump) = self.current_jump.take() {
@@ -420,7 +873,7 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
                         self.current_row = 0;
 
                         if self.current_pattern >= self.track.patterns_to_play.len() {
-                            self.current_pattern = self.track.repeat;
+                            self.wrap_to_repeat();
                         }
                     }
                 }
@@ -434,6 +887,27 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
         }
     }
 
+    fn swung_frames_per_tick(&self) -> Num<u32, 8> {
+        let frames_per_tick = self.global_settings.frames_per_tick;
+        if self.swing == 0.into() {
+            return frames_per_tick;
+        }
+
+        let scale = if self.current_row % 2 == 0 {
+            Num::<i32, 8>::new(1) + self.swing
+        } else {
+            Num::<i32, 8>::new(1) - self.swing
+        };
+
+        // the swing is between -1 and 1, so the scale is always positive
+        frames_per_tick * Num::from_raw(scale.to_raw() as u32)
+    }
+
+    fn wrap_to_repeat(&mut self) {
+        self.current_pattern = self.repeat;
+        self.finished |= !self.looping;
+    }
+
     fn handle_jump(&mut self, jump: Jump) {
         match jump {
             Jump::Position { pattern } => {
@@ -450,7 +924,7 @@ impl<'track, TChannelId> TrackerInner<'track, TChannelId> {
             }
         };
         if self.current_pattern >= self.track.patterns_to_play.len() {
-            self.current_pattern = self.track.repeat;
+            self.wrap_to_repeat();
         }
         if self.current_row
             >= self.track.patterns[self.track.patterns_to_play[self.current_pattern]].length
@@ -467,6 +941,19 @@ impl TrackerChannel {
         self.current_volume = self.volume;
         self.current_panning = 0.into();
         self.is_playing = true;
+
+        self.sample_length = sample.data.len() as u32;
+        self.sample_restart_point = sample.should_loop.then_some(sample.restart_point);
+    }
+
+    /// Offsets past the end of the sample start from the restart point if the sample loops,
+    /// or from the end if it doesn't so it goes silent, rather than reading past the end.
+    fn clamp_sample_offset(&self, offset: u32) -> u32 {
+        if offset < self.sample_length {
+            offset
+        } else {
+            self.sample_restart_point.unwrap_or(self.sample_length)
+        }
     }
 
     fn set_speed(&mut self, speed: Num<u32, 8>) {
@@ -601,7 +1088,7 @@ impl TrackerChannel {
                 global_settings.volume =
                     (global_settings.volume + *volume_delta).clamp(0.into(), 1.into());
             }
-            PatternEffect::Vibrato(waveform, amount, speed) => {
+            PatternEffect::Vibrato(amount, speed) => {
                 if *amount != 0.into() {
                     self.vibrato.amount = amount.change_base();
                 }
@@ -610,15 +1097,17 @@ impl TrackerChannel {
                     self.vibrato.speed = *speed as usize;
                 }
 
-                self.vibrato.waveform = *waveform;
                 self.vibrato.enable = true;
             }
+            PatternEffect::SetVibratoWaveform(waveform) => {
+                self.vibrato.waveform = *waveform;
+            }
             PatternEffect::Jump(jump) => {
                 *current_jump = Some(jump.clone());
             }
             PatternEffect::SampleOffset(offset) => {
                 if tick == 0 {
-                    self.current_pos = Some(*offset);
+                    self.current_pos = Some(self.clamp_sample_offset(u32::from(*offset)));
                 }
             }
             PatternEffect::Retrigger(volume_change, ticks) => {
@@ -636,6 +1125,16 @@ impl TrackerChannel {
                     self.current_pos = Some(0);
                 }
             }
+            PatternEffect::SetEnvelopePosition(frame) => {
+                if tick == 0 {
+                    if let Some(envelope_state) = envelope_state {
+                        // the sustain and loop points carry on applying from the new position
+                        envelope_state.frame = *frame as usize;
+                    }
+                }
+            }
+            // these are passed to the on_row callback in step
+            PatternEffect::Event(_) => {}
         }
     }
 
@@ -670,15 +1169,834 @@ fn main(gba: agb::Gba) -> ! {
     loop {}
 }
 
-#[cfg(feature = "agb")]
-impl SoundChannel for agb::sound::mixer::SoundChannel {
-    fn new(data: &alloc::borrow::Cow<'static, [u8]>) -> Self {
-        Self::new(match data {
-            alloc::borrow::Cow::Borrowed(data) => data,
-            alloc::borrow::Cow::Owned(_) => {
-                unimplemented!("Must use borrowed COW data for tracker")
+#[cfg(test)]
+mod test {
+    use alloc::{borrow::Cow, vec, vec::Vec};
+
+    use agb_fixnum::num;
+    use agb_tracker_interop::{Pattern, PatternSlot};
+
+    use super::*;
+
+    struct TestChannel {
+        sample: u8,
+        volume: Num<i16, 8>,
+        playback_speed: Num<u32, 8>,
+        pos: Num<u32, 8>,
+        is_stopped: bool,
+    }
+
+    struct TestMixer {
+        channels: Vec<TestChannel>,
+        frequency: u32,
+    }
+
+    impl Default for TestMixer {
+        fn default() -> Self {
+            Self {
+                channels: Vec::new(),
+                frequency: 32768,
             }
-        })
+        }
+    }
+
+    impl SoundChannel<'_> for TestChannel {
+        fn new(data: &Cow<'static, [u8]>) -> Self {
+            Self {
+                sample: data[0],
+                volume: 1.into(),
+                playback_speed: 1.into(),
+                pos: 0.into(),
+                is_stopped: false,
+            }
+        }
+
+        fn stop(&mut self) {
+            self.is_stopped = true;
+        }
+
+        fn pause(&mut self) -> &mut Self {
+            self
+        }
+
+        fn resume(&mut self) -> &mut Self {
+            self
+        }
+
+        fn should_loop(&mut self) -> &mut Self {
+            self
+        }
+
+        fn volume(&mut self, value: impl Into<Num<i16, 8>>) -> &mut Self {
+            self.volume = value.into();
+            self
+        }
+
+        fn restart_point(&mut self, _value: impl Into<Num<u32, 8>>) -> &mut Self {
+            self
+        }
+
+        fn playback(&mut self, playback_speed: impl Into<Num<u32, 8>>) -> &mut Self {
+            self.playback_speed = playback_speed.into();
+            self
+        }
+
+        fn panning(&mut self, _panning: impl Into<Num<i16, 8>>) -> &mut Self {
+            self
+        }
+
+        fn set_pos(&mut self, pos: impl Into<Num<u32, 8>>) -> &mut Self {
+            self.pos = pos.into();
+            self
+        }
+    }
+
+    impl Mixer<'_> for TestMixer {
+        type ChannelId = usize;
+        type SoundChannel = TestChannel;
+
+        fn channel(&mut self, channel_id: &Self::ChannelId) -> Option<&mut Self::SoundChannel> {
+            self.channels
+                .get_mut(*channel_id)
+                .filter(|channel| !channel.is_stopped)
+        }
+
+        fn play_sound(&mut self, channel: Self::SoundChannel) -> Option<Self::ChannelId> {
+            self.channels.push(channel);
+            Some(self.channels.len() - 1)
+        }
+
+        fn frequency(&self) -> u32 {
+            self.frequency
+        }
+    }
+
+    /// Plays the given sample. Each sample's data is filled with its (1 based) index.
+    fn play(sample: u16) -> PatternSlot {
+        PatternSlot {
+            speed: 1.into(),
+            sample,
+            effect1: PatternEffect::None,
+            effect2: PatternEffect::None,
+        }
+    }
+
+    /// A track where each pattern is given as a list of rows of slots, and each pattern is played
+    /// once in order.
+    fn track_with_patterns(num_channels: usize, patterns: &[&[PatternSlot]]) -> Track {
+        let samples = (1..=4)
+            .map(|i| Sample {
+                data: Cow::Owned(vec![i; 64]),
+                should_loop: true,
+                restart_point: 0,
+                volume: 1.into(),
+                volume_envelope: None,
+                fadeout: 0.into(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut pattern_data = vec![];
+        let mut track_patterns = vec![];
+
+        for pattern in patterns {
+            track_patterns.push(Pattern {
+                length: pattern.len() / num_channels,
+                start_position: pattern_data.len(),
+            });
+            pattern_data.extend_from_slice(pattern);
+        }
+
+        Track {
+            samples: Cow::Owned(samples),
+            envelopes: Cow::Owned(vec![]),
+            pattern_data: Cow::Owned(pattern_data),
+            patterns_to_play: Cow::Owned((0..track_patterns.len()).collect()),
+            patterns: Cow::Owned(track_patterns),
+
+            num_channels,
+            frames_per_tick: 1.into(),
+            ticks_per_step: 1,
+            repeat: 0,
+        }
+    }
+
+    /// A track with a single pattern where every channel plays the first sample on every row.
+    fn test_track(num_channels: usize, rows: usize) -> Track {
+        track_with_patterns(num_channels, &[&vec![play(1); num_channels * rows]])
+    }
+
+    fn playing_volumes(mixer: &TestMixer) -> Vec<Num<i16, 8>> {
+        mixer
+            .channels
+            .iter()
+            .filter(|channel| !channel.is_stopped)
+            .map(|channel| channel.volume)
+            .collect()
+    }
+
+    fn playing_samples(mixer: &TestMixer) -> Vec<u8> {
+        mixer
+            .channels
+            .iter()
+            .filter(|channel| !channel.is_stopped)
+            .map(|channel| channel.sample)
+            .collect()
+    }
+
+    #[test_case]
+    fn non_looping_tracks_finish_at_the_end(_gba: &mut agb::Gba) {
+        let track = track_with_patterns(1, &[&[play(1)], &[play(2)]]);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+        tracker.set_looping(false);
+
+        tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [1]);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [2]);
+        assert!(!tracker.is_finished());
+
+        tracker.step(&mut mixer);
+        assert!(tracker.is_finished());
+        assert!(playing_samples(&mixer).is_empty());
+
+        tracker.step(&mut mixer);
+        assert!(playing_samples(&mixer).is_empty());
+    }
+
+    #[test_case]
+    fn looping_tracks_never_finish(_gba: &mut agb::Gba) {
+        let track = track_with_patterns(1, &[&[play(1)], &[play(2)]]);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        for _ in 0..3 {
+            tracker.step(&mut mixer);
+        }
+
+        assert!(!tracker.is_finished());
+        assert_eq!(playing_samples(&mixer), [1]);
+    }
+
+    #[test_case]
+    fn multiple_trackers_share_a_mixer(_gba: &mut agb::Gba) {
+        let music = track_with_patterns(2, &[&[play(1), play(1), play(1), play(1)]]);
+        let ambience = track_with_patterns(1, &[&[play(2), play(2)]]);
+
+        let mut mixer = TestMixer::default();
+        let mut music_tracker = TrackerInner::new(&music);
+        let mut ambience_tracker = TrackerInner::new(&ambience);
+
+        music_tracker.step(&mut mixer);
+        ambience_tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [1, 1, 2]);
+
+        // each tracker only replaces the notes it started itself
+        music_tracker.step(&mut mixer);
+        ambience_tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [1, 1, 2]);
+
+        music_tracker.stop(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [2]);
+    }
+
+    #[test_case]
+    fn disabled_channels_are_silenced_but_keep_playing(_gba: &mut agb::Gba) {
+        let track = test_track(2, 4);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        tracker.set_channel_enabled(1, false);
+        assert!(tracker.is_channel_enabled(0));
+        assert!(!tracker.is_channel_enabled(1));
+
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [1.into(), 0.into()]);
+
+        tracker.set_channel_enabled(1, true);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [1.into(), 1.into()]);
+
+        // the tracker should never hold more than one mixer channel per track channel
+        tracker.set_channel_enabled(0, false);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [0.into(), 1.into()]);
+    }
+
+    #[test_case]
+    fn solo_channel_only_enables_that_channel(_gba: &mut agb::Gba) {
+        let track = test_track(3, 1);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        tracker.solo_channel(Some(1));
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [0.into(), 1.into(), 0.into()]);
+
+        tracker.solo_channel(None);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [1.into(), 1.into(), 1.into()]);
+    }
+
+    #[test_case]
+    fn restoring_a_snapshot_resumes_the_sequence(_gba: &mut agb::Gba) {
+        let mut track = track_with_patterns(
+            1,
+            &[
+                &[play(1), play(2)],
+                &[play(3)],
+                &[play(4), play(1), play(2)],
+            ],
+        );
+        track.ticks_per_step = 2;
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        // part way through the second row
+        for _ in 0..4 {
+            tracker.step(&mut mixer);
+        }
+
+        let snapshot = tracker.snapshot();
+        assert_eq!((snapshot.order_position(), snapshot.row()), (0, 1));
+
+        let mut restored_mixer = TestMixer::default();
+        let mut restored = TrackerInner::new(&track);
+        restored.restore(&snapshot);
+        assert_eq!(restored.snapshot(), snapshot);
+
+        for _ in 0..16 {
+            tracker.step(&mut mixer);
+            restored.step(&mut restored_mixer);
+
+            assert_eq!(restored.snapshot(), tracker.snapshot());
+            assert_eq!(
+                playing_samples(&restored_mixer).last(),
+                playing_samples(&mixer).last()
+            );
+        }
+    }
+
+    #[test_case]
+    fn sample_offsets_past_the_end_of_the_sample_are_clamped(_gba: &mut agb::Gba) {
+        let with_offset = |sample, offset| PatternSlot {
+            effect1: PatternEffect::SampleOffset(offset),
+            ..play(sample)
+        };
+
+        let mut track = track_with_patterns(
+            1,
+            &[&[
+                with_offset(1, 0x20),
+                with_offset(1, 0x100),
+                with_offset(2, 0x100),
+            ]],
+        );
+        let samples = track.samples.to_mut();
+        samples[0].restart_point = 16;
+        samples[1].should_loop = false;
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        let mut positions = vec![];
+        for _ in 0..3 {
+            tracker.step(&mut mixer);
+            positions.push(mixer.channels.last().unwrap().pos);
+        }
+
+        // within the sample, then the loop point of a looping sample and the end of one which
+        // doesn't loop
+        assert_eq!(positions, [32.into(), 16.into(), 64.into()]);
+    }
+
+    #[test_case]
+    fn new_at_starts_from_the_given_position(_gba: &mut agb::Gba) {
+        let track = track_with_patterns(
+            1,
+            &[&[play(1)], &[play(2)], &[play(3), play(4)], &[play(1)]],
+        );
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new_at(&track, 2, 0);
+
+        tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [3]);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [4]);
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new_at(&track, 2, 1);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_samples(&mixer), [4]);
+    }
+
+    #[test_case]
+    fn intros_before_the_tracks_repeat_position_only_play_once(_gba: &mut agb::Gba) {
+        let mut track = track_with_patterns(1, &[&[play(1)], &[play(2)], &[play(3)]]);
+        track.repeat = 1;
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        let mut played = vec![];
+        for _ in 0..7 {
+            tracker.step(&mut mixer);
+            played.extend(playing_samples(&mixer));
+        }
+
+        assert_eq!(played, [1, 2, 3, 2, 3, 2, 3]);
+    }
+
+    #[test_case]
+    fn set_repeat_overrides_the_loop_position(_gba: &mut agb::Gba) {
+        let track = track_with_patterns(1, &[&[play(1)], &[play(2)], &[play(3)]]);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+        tracker.set_repeat(1);
+
+        let mut played = vec![];
+        for _ in 0..6 {
+            tracker.step(&mut mixer);
+            played.extend(playing_samples(&mixer));
+        }
+
+        assert_eq!(played, [1, 2, 3, 2, 3, 2]);
+    }
+
+    #[test_case]
+    fn fade_volume_to_approaches_the_target(_gba: &mut agb::Gba) {
+        let track = test_track(1, 4);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        tracker.fade_volume_to(num!(0.25), 10);
+
+        let mut volumes = vec![];
+        for _ in 0..12 {
+            tracker.step(&mut mixer);
+            volumes.push(playing_volumes(&mixer)[0]);
+        }
+
+        assert!(volumes.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(volumes[0] < 1.into());
+        assert_eq!(volumes[9], num!(0.25));
+        assert_eq!(volumes[11], num!(0.25));
+        assert_eq!(tracker.volume(), num!(0.25));
+
+        tracker.fade_volume_to(1.into(), 0);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [1.into()]);
+    }
+
+    #[test_case]
+    fn global_volume_scales_channel_volumes_until_the_track_sets_it(_gba: &mut agb::Gba) {
+        let set_volume = PatternSlot {
+            effect1: PatternEffect::Volume(1.into()),
+            ..play(1)
+        };
+        let set_global_volume = PatternSlot {
+            effect1: PatternEffect::SetGlobalVolume(1.into()),
+            effect2: PatternEffect::Volume(1.into()),
+            ..play(1)
+        };
+        let track = track_with_patterns(1, &[&[set_volume.clone(), set_volume, set_global_volume]]);
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        assert_eq!(tracker.global_volume(), 1.into());
+        tracker.set_global_volume(num!(0.5));
+        assert_eq!(tracker.global_volume(), num!(0.5));
+
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [num!(0.5)]);
+
+        // the tracker volume is applied on top of the global volume
+        tracker.fade_volume_to(num!(0.5), 0);
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [num!(0.25)]);
+
+        tracker.fade_volume_to(1.into(), 0);
+        tracker.step(&mut mixer);
+        assert_eq!(tracker.global_volume(), 1.into());
+        assert_eq!(playing_volumes(&mixer), [1.into()]);
+    }
+
+    #[test_case]
+    fn channel_state_reports_the_volume_set_by_the_track(_gba: &mut agb::Gba) {
+        let with_volume = |sample, volume| PatternSlot {
+            effect1: PatternEffect::Volume(volume),
+            ..play(sample)
+        };
+        let track = track_with_patterns(
+            2,
+            &[&[
+                with_volume(1, num!(0.5)),
+                play(2),
+                PatternSlot {
+                    effect2: PatternEffect::Panning(num!(-0.5)),
+                    ..with_volume(1, num!(0.25))
+                },
+                PatternSlot {
+                    effect1: PatternEffect::Stop,
+                    ..play(0)
+                },
+            ]],
+        );
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        assert!(!tracker.channel_state(0).is_playing);
+
+        tracker.step(&mut mixer);
+        assert_eq!(
+            tracker.channel_state(0),
+            ChannelState {
+                is_playing: true,
+                volume: num!(0.5),
+                speed: 1.into(),
+                panning: 0.into(),
+            }
+        );
+        assert_eq!(tracker.channel_state(1).volume, 1.into());
+
+        tracker.fade_volume_to(num!(0.5), 0);
+        tracker.step(&mut mixer);
+        let state = tracker.channel_state(0);
+        assert_eq!(state.volume, num!(0.125));
+        assert_eq!(state.panning, num!(-0.5));
+        assert_eq!(tracker.channel_state(1).volume, 0.into());
+        assert!(tracker.channel_state(1).is_playing);
+
+        // these match what the mixer was told
+        assert_eq!(playing_volumes(&mixer), [0.into(), num!(0.125)]);
+
+        tracker.set_channel_enabled(0, false);
+        assert_eq!(tracker.channel_state(0).volume, 0.into());
+    }
+
+    #[cfg(feature = "xm-runtime")]
+    #[test_case]
+    fn xm_files_can_be_parsed_at_runtime(_gba: &mut agb::Gba) {
+        let track = parse_xm(include_bytes!(
+            "../examples/tracks/peak_and_drozerix_-_spectrum.xm"
+        ))
+        .unwrap();
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        for _ in 0..100 {
+            tracker.step(&mut mixer);
+        }
+
+        assert!(!playing_samples(&mixer).is_empty());
+        assert!(parse_xm(&[0; 16]).is_err());
+    }
+
+    #[cfg(feature = "xm-runtime")]
+    #[test_case]
+    fn runtime_parsed_tracks_play_on_the_agb_mixer(gba: &mut agb::Gba) {
+        let track = parse_xm(include_bytes!(
+            "../examples/tracks/peak_and_drozerix_-_spectrum.xm"
+        ))
+        .unwrap();
+        // the samples are owned by the parsed track rather than being in ROM
+        assert!(matches!(track.samples[0].data, Cow::Owned(_)));
+        let track: &'static Track = Box::leak(Box::new(track));
+
+        let mut mixer = gba.mixer.mixer(agb::sound::mixer::Frequency::Hz10512);
+        mixer.enable();
+        let mut tracker = Tracker::new(track);
+
+        let mut played_a_note = false;
+        for _ in 0..100 {
+            tracker.step(&mut mixer);
+            mixer.frame();
+
+            played_a_note |= mixer.free_channels() < mixer.max_channels();
+        }
+
+        assert!(played_a_note);
+    }
+
+    #[test_case]
+    fn playback_speed_is_scaled_to_the_mixer_frequency(_gba: &mut agb::Gba) {
+        let track = test_track(1, 4);
+
+        let speed_at = |frequency| {
+            let mut mixer = TestMixer {
+                frequency,
+                ..Default::default()
+            };
+            let mut tracker = TrackerInner::new(&track);
+            tracker.step(&mut mixer);
+            mixer.channels[0].playback_speed
+        };
+
+        assert_eq!(speed_at(32768), 1.into());
+        assert_eq!(speed_at(16384), 2.into());
+
+        // 32768 / 18157 = 1.8047...
+        assert!((461..=463).contains(&speed_at(18157).to_raw()));
+    }
+
+    #[test_case]
+    fn set_envelope_position_jumps_to_that_frame(_gba: &mut agb::Gba) {
+        let set_envelope_position = |frame| PatternSlot {
+            speed: 0.into(),
+            sample: 0,
+            effect1: PatternEffect::SetEnvelopePosition(frame),
+            effect2: PatternEffect::None,
+        };
+
+        let mut track = track_with_patterns(
+            1,
+            &[&[
+                play(1),
+                set_envelope_position(3),
+                set_envelope_position(100),
+            ]],
+        );
+        track.samples.to_mut()[0].volume_envelope = Some(0);
+        track.envelopes = Cow::Owned(vec![agb_tracker_interop::Envelope {
+            amount: Cow::Owned(vec![
+                num!(1.),
+                num!(0.75),
+                num!(0.5),
+                num!(0.25),
+                num!(0.125),
+            ]),
+            sustain: None,
+            loop_start: None,
+            loop_end: None,
+
+            vib_waveform: Default::default(),
+            vib_amount: 0.into(),
+            vib_speed: 0,
+        }]);
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        let mut volumes = vec![];
+        for _ in 0..3 {
+            tracker.step(&mut mixer);
+            volumes.extend(playing_volumes(&mixer));
+        }
+
+        // positions past the end of the envelope stay on the last frame
+        assert_eq!(volumes, [num!(1.), num!(0.25), num!(0.125)]);
+    }
+
+    #[test_case]
+    fn delayed_notes_start_their_envelope_when_the_delay_is_over(_gba: &mut agb::Gba) {
+        let mut track = track_with_patterns(
+            1,
+            &[&[PatternSlot {
+                effect1: PatternEffect::NoteDelay(2),
+                ..play(1)
+            }]],
+        );
+        track.ticks_per_step = 4;
+        track.samples.to_mut()[0].volume_envelope = Some(0);
+        track.envelopes = Cow::Owned(vec![agb_tracker_interop::Envelope {
+            amount: Cow::Owned(vec![num!(1.), num!(0.5), num!(0.25)]),
+            sustain: None,
+            loop_start: None,
+            loop_end: None,
+
+            vib_waveform: Default::default(),
+            vib_amount: 0.into(),
+            vib_speed: 0,
+        }]);
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        for _ in 0..2 {
+            tracker.step(&mut mixer);
+            assert!(playing_volumes(&mixer).is_empty());
+            assert!(tracker.envelopes[0].is_none());
+        }
+
+        // the envelope is on its first frame on the tick the note starts
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [num!(1.)]);
+
+        tracker.step(&mut mixer);
+        assert_eq!(playing_volumes(&mixer), [num!(0.5)]);
+    }
+
+    #[test_case]
+    fn vibrato_changes_the_pitch_using_its_waveform(_gba: &mut agb::Gba) {
+        let speeds_with = |waveform| {
+            let mut track = track_with_patterns(
+                1,
+                &[&[PatternSlot {
+                    effect1: PatternEffect::Vibrato(num!(0.5), 16),
+                    effect2: PatternEffect::SetVibratoWaveform(waveform),
+                    ..play(1)
+                }]],
+            );
+            track.ticks_per_step = 4;
+
+            let mut mixer = TestMixer::default();
+            let mut tracker = TrackerInner::new(&track);
+
+            (0..4)
+                .map(|_| {
+                    tracker.step(&mut mixer);
+                    mixer.channels[0].playback_speed
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // a square wave jumps between the lowest and highest pitch, while a saw slides up
+        assert_eq!(
+            speeds_with(Waveform::Square),
+            [num!(0.5), num!(1.5), num!(1.5), num!(0.5)]
+        );
+        assert_eq!(
+            speeds_with(Waveform::Saw),
+            [num!(0.75), num!(1.), num!(1.25), num!(0.5)]
+        );
+    }
+
+    #[test_case]
+    fn vibrato_waveform_is_kept_across_patterns(_gba: &mut agb::Gba) {
+        let mut track = track_with_patterns(
+            1,
+            &[
+                &[PatternSlot {
+                    effect1: PatternEffect::SetVibratoWaveform(Waveform::Square),
+                    ..play(1)
+                }],
+                &[PatternSlot {
+                    speed: 0.into(),
+                    sample: 0,
+                    effect1: PatternEffect::Vibrato(num!(0.5), 16),
+                    effect2: PatternEffect::None,
+                }],
+            ],
+        );
+        track.ticks_per_step = 4;
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        let speeds = (0..8)
+            .map(|_| {
+                tracker.step(&mut mixer);
+                mixer.channels[0].playback_speed
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            speeds,
+            [
+                num!(1.),
+                num!(1.),
+                num!(1.),
+                num!(1.),
+                num!(0.5),
+                num!(1.5),
+                num!(1.5),
+                num!(0.5)
+            ]
+        );
+    }
+
+    #[test_case]
+    fn swing_alternates_the_length_of_rows(_gba: &mut agb::Gba) {
+        let mut track = test_track(1, 4);
+        track.frames_per_tick = 4.into();
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+        tracker.set_swing(num!(0.5));
+
+        // the number of frames each row plays for
+        let mut row_lengths: Vec<(usize, u32)> = vec![];
+        for _ in 0..32 {
+            tracker.step(&mut mixer);
+
+            let row = tracker.snapshot().row();
+            match row_lengths.last_mut() {
+                Some((last_row, frames)) if *last_row == row => *frames += 1,
+                _ => row_lengths.push((row, 1)),
+            }
+        }
+
+        assert_eq!(
+            row_lengths[..6],
+            [(0, 6), (1, 2), (2, 6), (3, 2), (0, 6), (1, 2)]
+        );
+    }
+
+    #[test_case]
+    fn on_row_is_called_for_each_row_with_its_events(_gba: &mut agb::Gba) {
+        use alloc::rc::Rc;
+        use core::cell::RefCell;
+
+        let event = |event| PatternSlot {
+            effect2: PatternEffect::Event(event),
+            ..play(1)
+        };
+
+        let mut track = track_with_patterns(
+            2,
+            &[
+                &[play(1), event(5), event(6), event(7)],
+                &[event(8), play(2)],
+            ],
+        );
+        track.ticks_per_step = 2;
+
+        let mut mixer = TestMixer::default();
+        let mut tracker = TrackerInner::new(&track);
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_in_callback = seen.clone();
+        tracker.on_row(move |event| seen_in_callback.borrow_mut().push(event));
+
+        // 3 rows of 2 ticks each, then back to the start
+        for _ in 0..7 {
+            tracker.step(&mut mixer);
+        }
+
+        let row = |order_position, row| TrackerEvent::Row {
+            order_position,
+            pattern: order_position,
+            row,
+        };
+
+        assert_eq!(
+            *seen.borrow(),
+            [
+                row(0, 0),
+                TrackerEvent::Event(5),
+                row(0, 1),
+                TrackerEvent::Event(6),
+                TrackerEvent::Event(7),
+                row(1, 0),
+                TrackerEvent::Event(8),
+                row(0, 0),
+                TrackerEvent::Event(5),
+            ]
+        );
+
+        tracker.clear_on_row();
+        for _ in 0..2 {
+            tracker.step(&mut mixer);
+        }
+        assert_eq!(seen.borrow().len(), 9);
+    }
+}
+
+#[cfg(feature = "agb")]
+impl SoundChannel<'static> for agb::sound::mixer::SoundChannel {
+    fn new(data: &'static alloc::borrow::Cow<'static, [u8]>) -> Self {
+        Self::new(data)
     }
 
     fn stop(&mut self) {
@@ -719,7 +2037,7 @@ impl SoundChannel for agb::sound::mixer::SoundChannel {
 }
 
 #[cfg(feature = "agb")]
-impl Mixer for agb::sound::mixer::Mixer<'_> {
+impl Mixer<'static> for agb::sound::mixer::Mixer<'_> {
     type ChannelId = agb::sound::mixer::ChannelId;
     type SoundChannel = agb::sound::mixer::SoundChannel;
 
@@ -730,6 +2048,10 @@ impl Mixer for agb::sound::mixer::Mixer<'_> {
     fn play_sound(&mut self, channel: Self::SoundChannel) -> Option<Self::ChannelId> {
         self.play_sound(channel)
     }
+
+    fn frequency(&self) -> u32 {
+        self.frequency().frequency() as u32
+    }
 }
 
 #[cfg(feature = "agb")]
@@ -3,10 +3,12 @@
 use agb_fixnum::Num;
 use alloc::borrow::Cow;
 
-pub trait SoundChannel {
-    // I need a reference to a cow here to support the static data correctly
+/// A channel which plays sample data from a track which lives for `'track`
+pub trait SoundChannel<'track> {
+    // I need a reference to a cow here to support the static data correctly. Owned sample data,
+    // for example from a track parsed at runtime, can still be borrowed for the track's lifetime.
     #[allow(clippy::ptr_arg)]
-    fn new(data: &Cow<'static, [u8]>) -> Self;
+    fn new(data: &'track Cow<'static, [u8]>) -> Self;
 
     fn stop(&mut self);
     fn pause(&mut self) -> &mut Self;
@@ -21,10 +23,18 @@ pub trait SoundChannel {
     fn set_pos(&mut self, pos: impl Into<Num<u32, 8>>) -> &mut Self;
 }
 
-pub trait Mixer {
+/// A mixer which can play tracks which live for `'track`
+pub trait Mixer<'track> {
     type ChannelId;
-    type SoundChannel: SoundChannel;
+    type SoundChannel: SoundChannel<'track>;
 
     fn channel(&mut self, channel_id: &Self::ChannelId) -> Option<&mut Self::SoundChannel>;
     fn play_sound(&mut self, channel: Self::SoundChannel) -> Option<Self::ChannelId>;
+
+    /// The frequency the mixer is running at in Hz. Defaults to
+    /// [`TRACK_FREQUENCY`](agb_tracker_interop::TRACK_FREQUENCY), the frequency which tracks
+    /// are stored at.
+    fn frequency(&self) -> u32 {
+        agb_tracker_interop::TRACK_FREQUENCY
+    }
 }
@@ -0,0 +1,192 @@
+use agb_fixnum::Num;
+use agb_tracker_interop::Jump;
+use alloc::vec::Vec;
+
+use crate::{GlobalSettings, TrackerInner};
+
+/// Where a tracker is in its track, created with [`snapshot`](TrackerInner::snapshot()) and
+/// restored with [`restore`](TrackerInner::restore()).
+///
+/// This is for resuming music after loading a saved game. With the `serde` feature enabled this
+/// can be serialized, so it can be stored alongside the rest of your save data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaybackState {
+    order_position: usize,
+    row: usize,
+    tick: u32,
+    frame: Num<u32, 8>,
+    started: bool,
+    pending_jump: Option<PendingJump>,
+
+    ticks_per_step: u32,
+    frames_per_tick: Num<u32, 8>,
+    global_volume: Num<i32, 8>,
+    volume: Num<i32, 8>,
+
+    repeat: usize,
+    finished: bool,
+
+    channels: Vec<SavedChannel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SavedChannel {
+    original_speed: Num<u32, 16>,
+    base_speed: Num<u32, 16>,
+    volume: Num<i32, 8>,
+    current_volume: Num<i32, 8>,
+    current_speed: Num<u32, 16>,
+    current_panning: Num<i32, 8>,
+    is_playing: bool,
+}
+
+// A copy of Jump so that the interop crate doesn't need to know about serde
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum PendingJump {
+    Position { pattern: u8 },
+    PatternBreak { row: u8 },
+    Combined { pattern: u8, row: u8 },
+}
+
+impl From<&Jump> for PendingJump {
+    fn from(jump: &Jump) -> Self {
+        match *jump {
+            Jump::Position { pattern } => PendingJump::Position { pattern },
+            Jump::PatternBreak { row } => PendingJump::PatternBreak { row },
+            Jump::Combined { pattern, row } => PendingJump::Combined { pattern, row },
+        }
+    }
+}
+
+impl From<&PendingJump> for Jump {
+    fn from(jump: &PendingJump) -> Self {
+        match *jump {
+            PendingJump::Position { pattern } => Jump::Position { pattern },
+            PendingJump::PatternBreak { row } => Jump::PatternBreak { row },
+            PendingJump::Combined { pattern, row } => Jump::Combined { pattern, row },
+        }
+    }
+}
+
+impl PlaybackState {
+    /// The position in the pattern order which was playing
+    #[must_use]
+    pub fn order_position(&self) -> usize {
+        self.order_position
+    }
+
+    /// The row within the pattern which was playing
+    #[must_use]
+    pub fn row(&self) -> usize {
+        self.row
+    }
+}
+
+impl<TChannelId> TrackerInner<'_, TChannelId> {
+    /// Records where the tracker is in the track, along with the speed, volumes and panning of
+    /// each channel, so that it can be resumed later with [`restore`](TrackerInner::restore()).
+    #[must_use]
+    pub fn snapshot(&self) -> PlaybackState {
+        PlaybackState {
+            order_position: self.current_pattern,
+            row: self.current_row,
+            tick: self.tick,
+            frame: self.frame,
+            started: !self.first,
+            pending_jump: self.current_jump.as_ref().map(PendingJump::from),
+
+            ticks_per_step: self.global_settings.ticks_per_step,
+            frames_per_tick: self.global_settings.frames_per_tick,
+            global_volume: self.global_settings.volume,
+            volume: self.volume,
+
+            repeat: self.repeat,
+            finished: self.finished,
+
+            channels: self
+                .channels
+                .iter()
+                .map(|channel| SavedChannel {
+                    original_speed: channel.original_speed,
+                    base_speed: channel.base_speed,
+                    volume: channel.volume,
+                    current_volume: channel.current_volume,
+                    current_speed: channel.current_speed,
+                    current_panning: channel.current_panning,
+                    is_playing: channel.is_playing,
+                })
+                .collect(),
+        }
+    }
+
+    /// Continues playing from a [`snapshot`](TrackerInner::snapshot()), which would normally
+    /// be restored into a new tracker for the same track.
+    ///
+    /// The sequence carries on from exactly where it was, but notes which were playing when the
+    /// snapshot was taken won't be heard until the next note on their channel, and effects which
+    /// change over time such as vibrato and envelopes restart. Any volume fade is cancelled. The
+    /// enabled channels, looping, [swing](TrackerInner::set_swing()) and the
+    /// [`on_row`](TrackerInner::on_row()) callback are kept from this tracker rather than the
+    /// snapshot, so set them again after restoring into a new tracker.
+    ///
+    /// If this tracker is already playing, call [`stop`](TrackerInner::stop()) first so that
+    /// its current notes don't keep playing.
+    ///
+    /// Panics if the snapshot was taken from a track with a different number of channels or
+    /// whose position is out of range for this tracker's track.
+    pub fn restore(&mut self, state: &PlaybackState) {
+        assert_eq!(
+            state.channels.len(),
+            self.channels.len(),
+            "snapshot has a different number of channels to the track"
+        );
+        assert!(
+            state.order_position < self.track.patterns_to_play.len()
+                && state.repeat < self.track.patterns_to_play.len(),
+            "snapshot's order position is out of range for the track"
+        );
+        let pattern_length =
+            self.track.patterns[self.track.patterns_to_play[state.order_position]].length;
+        assert!(
+            state.row < pattern_length,
+            "snapshot's row is out of range for the track"
+        );
+
+        self.current_pattern = state.order_position;
+        self.current_row = state.row;
+        self.tick = state.tick;
+        self.frame = state.frame;
+        self.first = !state.started;
+        self.current_jump = state.pending_jump.as_ref().map(Jump::from);
+
+        self.global_settings = GlobalSettings {
+            ticks_per_step: state.ticks_per_step,
+            frames_per_tick: state.frames_per_tick,
+            volume: state.global_volume,
+        };
+        self.volume = state.volume;
+        self.volume_fade = None;
+
+        self.repeat = state.repeat;
+        self.finished = state.finished;
+
+        for (channel, saved) in self.channels.iter_mut().zip(&state.channels) {
+            let is_muted = channel.is_muted;
+
+            *channel = Default::default();
+            channel.original_speed = saved.original_speed;
+            channel.base_speed = saved.base_speed;
+            channel.volume = saved.volume;
+            channel.current_volume = saved.current_volume;
+            channel.current_speed = saved.current_speed;
+            channel.current_panning = saved.current_panning;
+            channel.is_playing = saved.is_playing;
+            channel.is_muted = is_muted;
+        }
+
+        self.envelopes.fill_with(|| None);
+    }
+}
@@ -1,11 +1,185 @@
-use std::collections::HashMap;
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
 
 use agb_fixnum::Num;
+use agb_hashmap::HashMap;
 use agb_tracker_interop::{Jump, PatternEffect, RetriggerVolumeChange, Waveform};
 
-use xmrs::prelude::*;
+use xmrs::{prelude::*, xm::xmmodule::XmModule};
+
+/// An effect in a module which isn't supported, so is ignored when the track is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// The effect type, where 0xA is effect `A` and so on
+    pub effect: u8,
+    /// The parameter given to the effect
+    pub parameter: u8,
+    /// The index of the pattern containing the effect
+    pub pattern: usize,
+    /// The row within that pattern
+    pub row: usize,
+    /// The channel within that row
+    pub channel: usize,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let effect_char = char::from_digit(self.effect as u32, 36)
+            .unwrap_or('?')
+            .to_ascii_uppercase();
+
+        write!(
+            f,
+            "Unsupported effect {effect_char}{:02X} in pattern {} row {} channel {}",
+            self.parameter, self.pattern, self.row, self.channel
+        )
+    }
+}
+
+/// Converts the module into a track, printing any unsupported effects to stderr if the
+/// `print-warnings` feature is enabled (which it is by default).
+pub fn parse_module(module: &Module) -> Result<agb_tracker_interop::Track, ParseError> {
+    let (track, warnings) = parse_module_with_warnings(module)?;
+
+    #[cfg(feature = "print-warnings")]
+    for warning in &warnings {
+        std::eprintln!("{warning}");
+    }
+    #[cfg(not(feature = "print-warnings"))]
+    let _ = warnings;
+
+    Ok(track)
+}
+
+/// Parses the contents of an XM file into a track, removing any samples which are never
+/// played like `include_xm!` does. Unsupported effects are ignored, and are only printed if the
+/// `print-warnings` feature is enabled.
+pub fn parse_xm(data: &[u8]) -> Result<agb_tracker_interop::Track, ParseError> {
+    let module = XmModule::load(data)
+        .map_err(|e| ParseError::InvalidFile(e.to_string()))?
+        .to_module();
+
+    let mut track = parse_module(&module)?;
+    strip_unused_samples(&mut track);
+
+    Ok(track)
+}
+
+/// The error returned by [`parse_xm`] and [`parse_module`] if the module can't be converted
+/// into a track.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The data isn't a valid XM file
+    InvalidFile(String),
+    /// The module's default BPM is 0
+    InvalidBpm,
+    /// A sample is in stereo, which the tracker can't play
+    StereoSample {
+        /// The index of the instrument containing the sample
+        instrument: usize,
+        /// The index of the sample within that instrument
+        sample: usize,
+    },
+    /// An instrument's volume envelope has no points, or its sustain or loop refers to a point
+    /// which doesn't exist
+    InvalidEnvelope {
+        /// The index of the instrument with the envelope
+        instrument: usize,
+    },
+    /// An instrument's auto vibrato is too deep to be stored in the track
+    VibratoOutOfRange {
+        /// The index of the instrument with the vibrato
+        instrument: usize,
+    },
+    /// A note is too low or too high to be played once its sample's relative note is applied
+    NoteOutOfRange {
+        /// The index of the pattern containing the note
+        pattern: usize,
+        /// The row within that pattern
+        row: usize,
+        /// The channel within that row
+        channel: usize,
+    },
+    /// An effect produces an amount which is too large to be stored in the track
+    EffectOutOfRange {
+        /// The effect type, where 0xA is effect `A` and so on
+        effect: u8,
+        /// The parameter given to the effect
+        parameter: u8,
+        /// The index of the pattern containing the effect
+        pattern: usize,
+        /// The row within that pattern
+        row: usize,
+        /// The channel within that row
+        channel: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidFile(message) => write!(f, "Failed to parse XM file: {message}"),
+            ParseError::InvalidBpm => write!(f, "The module's default BPM is 0"),
+            ParseError::StereoSample { instrument, sample } => write!(
+                f,
+                "Sample {sample} of instrument {instrument} is stereo, which isn't supported"
+            ),
+            ParseError::InvalidEnvelope { instrument } => {
+                write!(f, "Instrument {instrument} has an invalid volume envelope")
+            }
+            ParseError::VibratoOutOfRange { instrument } => {
+                write!(f, "Instrument {instrument} has too much vibrato")
+            }
+            ParseError::NoteOutOfRange {
+                pattern,
+                row,
+                channel,
+            } => write!(
+                f,
+                "Note out of range in pattern {pattern} row {row} channel {channel}"
+            ),
+            ParseError::EffectOutOfRange {
+                effect,
+                parameter,
+                pattern,
+                row,
+                channel,
+            } => {
+                let effect_char = char::from_digit(*effect as u32, 36)
+                    .unwrap_or('?')
+                    .to_ascii_uppercase();
+
+                write!(
+                    f,
+                    "Effect {effect_char}{parameter:02X} out of range in pattern {pattern} row {row} channel {channel}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Converts the module into a track, along with a warning for each effect which isn't supported.
+pub fn parse_module_with_warnings(
+    module: &Module,
+) -> Result<(agb_tracker_interop::Track, Vec<ParseWarning>), ParseError> {
+    if module.default_bpm == 0 {
+        return Err(ParseError::InvalidBpm);
+    }
 
-pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
     let instruments = &module.instrument;
     let mut instruments_map = HashMap::new();
 
@@ -34,9 +208,10 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
             let envelope = EnvelopeData::new(
                 envelope,
                 instrument,
+                instrument_index,
                 module.frequency_type,
                 module.default_bpm as u32,
-            );
+            )?;
             let id = existing_envelopes
                 .entry(envelope)
                 .or_insert_with_key(|envelope| {
@@ -55,14 +230,16 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
             let relative_note = sample.relative_note;
             let restart_point = sample.loop_start;
             let sample_len = if sample.loop_length > 0 {
-                (sample.loop_length + sample.loop_start) as usize
+                sample.loop_length.saturating_add(sample.loop_start) as usize
             } else {
                 usize::MAX
             };
 
             let volume = Num::from_f32(sample.volume);
 
-            let sample = match &sample.data {
+            let loop_type = sample.flags;
+
+            let mut sample = match &sample.data {
                 SampleDataType::Mono8(depth8) => depth8
                     .iter()
                     .map(|value| *value as u8)
@@ -73,9 +250,18 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                     .map(|sample| (sample >> 8) as i8 as u8)
                     .take(sample_len)
                     .collect::<Vec<_>>(),
-                _ => panic!("Stereo samples not supported"),
+                _ => {
+                    return Err(ParseError::StereoSample {
+                        instrument: instrument_index,
+                        sample: sample_index,
+                    })
+                }
             };
 
+            if matches!(loop_type, LoopType::PingPong) && sample_len != usize::MAX {
+                unroll_ping_pong_loop(&mut sample, restart_point as usize);
+            }
+
             let fadeout = Num::from_f32(instrument.volume_fadeout);
 
             instruments_map.insert((instrument_index, sample_index), samples.len());
@@ -94,16 +280,17 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
 
     let mut patterns = vec![];
     let mut pattern_data = vec![];
+    let mut warnings = vec![];
 
-    for pattern in &module.pattern {
+    for (pattern_index, pattern) in module.pattern.iter().enumerate() {
         let start_pos = pattern_data.len();
-        let mut effect_parameters: [u8; 255] = [0; u8::MAX as usize];
+        let mut effect_parameters = [0u8; u8::MAX as usize + 1];
         let mut tone_portamento_directions = vec![0; module.get_num_channels()];
         let mut note_and_sample = vec![None; module.get_num_channels()];
         let mut previous_retriggers: Vec<Option<(RetriggerVolumeChange, u8)>> =
             vec![None; module.get_num_channels()];
 
-        for row in pattern.iter() {
+        for (row_index, row) in pattern.iter().enumerate() {
             // the combined jump for each row
             let mut jump = None;
 
@@ -188,6 +375,28 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                     effect_parameters[slot.effect_type as usize]
                 };
 
+                let mut unsupported = || {
+                    warnings.push(ParseWarning {
+                        effect: slot.effect_type,
+                        parameter: effect_parameter,
+                        pattern: pattern_index,
+                        row: row_index,
+                        channel: channel_number,
+                    });
+                };
+                let effect_out_of_range = || ParseError::EffectOutOfRange {
+                    effect: slot.effect_type,
+                    parameter: effect_parameter,
+                    pattern: pattern_index,
+                    row: row_index,
+                    channel: channel_number,
+                };
+                let note_out_of_range = || ParseError::NoteOutOfRange {
+                    pattern: pattern_index,
+                    row: row_index,
+                    channel: channel_number,
+                };
+
                 let effect2 = match slot.effect_type {
                     0x0 => {
                         if slot.effect_parameter == 0 {
@@ -196,58 +405,60 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                             let first_arpeggio = slot.effect_parameter >> 4;
                             let second_arpeggio = slot.effect_parameter & 0xF;
 
-                            let first_arpeggio_speed = note_to_speed(
-                                *note,
-                                sample.fine_tune,
-                                sample.relative_note + first_arpeggio as i8,
-                                module.frequency_type,
-                            );
-                            let second_arpeggio_speed = note_to_speed(
-                                *note,
-                                sample.fine_tune,
-                                sample.relative_note + second_arpeggio as i8,
-                                module.frequency_type,
-                            );
+                            let arpeggio_speed = |arpeggio: u8| {
+                                sample
+                                    .relative_note
+                                    .checked_add(arpeggio as i8)
+                                    .and_then(|relative_note| {
+                                        note_to_speed(
+                                            *note,
+                                            sample.fine_tune,
+                                            relative_note,
+                                            module.frequency_type,
+                                        )
+                                    })
+                                    .ok_or_else(note_out_of_range)?
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)
+                            };
 
                             PatternEffect::Arpeggio(
-                                first_arpeggio_speed
-                                    .try_change_base()
-                                    .expect("Arpeggio size too large"),
-                                second_arpeggio_speed
-                                    .try_change_base()
-                                    .expect("Arpeggio size too large"),
+                                arpeggio_speed(first_arpeggio)?,
+                                arpeggio_speed(second_arpeggio)?,
                             )
                         } else {
                             PatternEffect::None
                         }
                     }
                     0x1 => {
-                        let c4_speed: Num<u32, 12> =
-                            note_to_speed(Note::C4, 0.0, 0, module.frequency_type).change_base();
-                        let speed: Num<u32, 12> = note_to_speed(
-                            Note::C4,
+                        let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                        let speed = fine_tuned_c4_speed(
                             effect_parameter as f64 * 8.0,
-                            0,
                             module.frequency_type,
-                        )
-                        .change_base();
+                        );
 
                         let portamento_amount = speed / c4_speed;
 
-                        PatternEffect::Portamento(portamento_amount.try_change_base().unwrap())
+                        PatternEffect::Portamento(
+                            portamento_amount
+                                .try_change_base()
+                                .ok_or_else(effect_out_of_range)?,
+                        )
                     }
                     0x2 => {
-                        let c4_speed = note_to_speed(Note::C4, 0.0, 0, module.frequency_type);
-                        let speed = note_to_speed(
-                            Note::C4,
+                        let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                        let speed = fine_tuned_c4_speed(
                             effect_parameter as f64 * 8.0,
-                            0,
                             module.frequency_type,
                         );
 
                         let portamento_amount = c4_speed / speed;
 
-                        PatternEffect::Portamento(portamento_amount.try_change_base().unwrap())
+                        PatternEffect::Portamento(
+                            portamento_amount
+                                .try_change_base()
+                                .ok_or_else(effect_out_of_range)?,
+                        )
                     }
                     0x3 => {
                         if let (Some((note, sample)), Some((prev_note, _))) =
@@ -258,23 +469,23 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                                 sample.fine_tune,
                                 sample.relative_note,
                                 module.frequency_type,
-                            );
+                            )
+                            .and_then(|speed| speed.try_change_base())
+                            .ok_or_else(note_out_of_range)?;
 
                             let direction = match (prev_note as usize).cmp(&(*note as usize)) {
-                                std::cmp::Ordering::Less => 1,
-                                std::cmp::Ordering::Equal => {
+                                core::cmp::Ordering::Less => 1,
+                                core::cmp::Ordering::Equal => {
                                     tone_portamento_directions[channel_number]
                                 }
-                                std::cmp::Ordering::Greater => -1,
+                                core::cmp::Ordering::Greater => -1,
                             };
 
                             tone_portamento_directions[channel_number] = direction;
 
-                            let c4_speed = note_to_speed(Note::C4, 0.0, 0, module.frequency_type);
-                            let speed = note_to_speed(
-                                Note::C4,
+                            let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                            let speed = fine_tuned_c4_speed(
                                 effect_parameter as f64 * 8.0,
-                                0,
                                 module.frequency_type,
                             );
 
@@ -285,8 +496,10 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                             };
 
                             PatternEffect::TonePortamento(
-                                portamento_amount.try_change_base().unwrap(),
-                                target_speed.try_change_base().unwrap(),
+                                portamento_amount
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)?,
+                                target_speed,
                             )
                         } else {
                             PatternEffect::None
@@ -296,15 +509,13 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                         let vibrato_speed = effect_parameter >> 4;
                         let depth = effect_parameter & 0xF;
 
-                        let c4_speed = note_to_speed(Note::C4, 0.0, 0, module.frequency_type);
-                        let speed =
-                            note_to_speed(Note::C4, depth as f64 * 16.0, 0, module.frequency_type);
+                        let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                        let speed = fine_tuned_c4_speed(depth as f64 * 16.0, module.frequency_type);
 
                         let amount = speed / c4_speed - 1;
 
                         PatternEffect::Vibrato(
-                            Waveform::Sine,
-                            amount.try_change_base().unwrap(),
+                            amount.try_change_base().ok_or_else(effect_out_of_range)?,
                             vibrato_speed,
                         )
                     }
@@ -374,39 +585,49 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                     }
                     0xE => match slot.effect_parameter >> 4 {
                         0x1 => {
-                            let c4_speed: Num<u32, 12> =
-                                note_to_speed(Note::C4, 0.0, 0, module.frequency_type)
-                                    .change_base();
-                            let speed: Num<u32, 12> = note_to_speed(
-                                Note::C4,
-                                effect_parameter as f64 * 8.0,
-                                0,
+                            let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                            let speed = fine_tuned_c4_speed(
+                                (effect_parameter & 0xF) as f64 * 8.0,
                                 module.frequency_type,
-                            )
-                            .change_base();
+                            );
 
                             let portamento_amount = speed / c4_speed;
 
                             PatternEffect::FinePortamento(
-                                portamento_amount.try_change_base().unwrap(),
+                                portamento_amount
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)?,
                             )
                         }
                         0x2 => {
-                            let c4_speed = note_to_speed(Note::C4, 0.0, 0, module.frequency_type);
-                            let speed = note_to_speed(
-                                Note::C4,
-                                effect_parameter as f64 * 8.0,
-                                0,
+                            let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                            let speed = fine_tuned_c4_speed(
+                                (effect_parameter & 0xF) as f64 * 8.0,
                                 module.frequency_type,
                             );
 
                             let portamento_amount = c4_speed / speed;
 
                             PatternEffect::FinePortamento(
-                                portamento_amount.try_change_base().unwrap(),
+                                portamento_amount
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)?,
                             )
                         }
-
+                        0x4 => {
+                            // 4 and above use the same waveforms but don't restart them on new
+                            // notes, which the tracker never does anyway
+                            match slot.effect_parameter & 0x3 {
+                                0 => PatternEffect::SetVibratoWaveform(Waveform::Sine),
+                                1 => PatternEffect::SetVibratoWaveform(Waveform::Saw),
+                                2 => PatternEffect::SetVibratoWaveform(Waveform::Square),
+                                // random
+                                _ => {
+                                    unsupported();
+                                    PatternEffect::None
+                                }
+                            }
+                        }
                         0x8 => PatternEffect::Panning(
                             Num::new(((slot.effect_parameter & 0xf) as i16) - 8) / 8,
                         ),
@@ -439,8 +660,8 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                         ),
                         0xC => PatternEffect::NoteCut((slot.effect_parameter & 0xf).into()),
                         0xD => PatternEffect::NoteDelay((slot.effect_parameter & 0xf).into()),
-                        u => {
-                            eprintln!("Unsupported extended effect E{u:X}y");
+                        _ => {
+                            unsupported();
                             PatternEffect::None
                         }
                     },
@@ -466,6 +687,13 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                             PatternEffect::GlobalVolumeSlide(Num::new(first as i32) / 0x40)
                         }
                     }
+                    // L
+                    0x15 => PatternEffect::SetEnvelopePosition(
+                        EnvelopeData::envelope_frame_to_gba_frame(
+                            effect_parameter as usize,
+                            module.default_bpm as u32,
+                        ) as u16,
+                    ),
                     // R
                     0x1B => {
                         let first = effect_parameter >> 4;
@@ -479,7 +707,7 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                             1 => RetriggerVolumeChange::DecreaseByOne,
                             8 => RetriggerVolumeChange::NoChange,
                             _ => {
-                                eprintln!("Unsupported retrigger effect volume {first}");
+                                unsupported();
                                 RetriggerVolumeChange::NoChange
                             }
                         };
@@ -498,12 +726,47 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
 
                         PatternEffect::Retrigger(volume_type, ticks_between_retriggers)
                     }
-                    e => {
-                        let effect_char = char::from_digit(e as u32, 36)
-                            .unwrap_or('?')
-                            .to_ascii_uppercase();
-                        eprintln!("Unsupported effect {effect_char}xy");
+                    // Z, which isn't otherwise used so games can use it to mark points in the track
+                    0x23 => PatternEffect::Event(slot.effect_parameter),
+                    // extra fine portamento, which is what S3M's EEx and FEx get converted into
+                    0x21 => match slot.effect_parameter >> 4 {
+                        0x1 => {
+                            let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                            let speed = fine_tuned_c4_speed(
+                                (effect_parameter & 0xF) as f64 * 2.0,
+                                module.frequency_type,
+                            );
+
+                            let portamento_amount = speed / c4_speed;
+
+                            PatternEffect::FinePortamento(
+                                portamento_amount
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)?,
+                            )
+                        }
+                        0x2 => {
+                            let c4_speed = fine_tuned_c4_speed(0.0, module.frequency_type);
+                            let speed = fine_tuned_c4_speed(
+                                (effect_parameter & 0xF) as f64 * 2.0,
+                                module.frequency_type,
+                            );
+
+                            let portamento_amount = c4_speed / speed;
 
+                            PatternEffect::FinePortamento(
+                                portamento_amount
+                                    .try_change_base()
+                                    .ok_or_else(effect_out_of_range)?,
+                            )
+                        }
+                        _ => {
+                            unsupported();
+                            PatternEffect::None
+                        }
+                    },
+                    _ => {
+                        unsupported();
                         PatternEffect::None
                     }
                 };
@@ -526,10 +789,12 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
                         sample_played.fine_tune,
                         sample_played.relative_note,
                         module.frequency_type,
-                    );
+                    )
+                    .and_then(|speed| speed.try_change_base())
+                    .ok_or_else(note_out_of_range)?;
 
                     pattern_data.push(agb_tracker_interop::PatternSlot {
-                        speed: speed.try_change_base().unwrap(),
+                        speed,
                         sample: sample as u16,
                         effect1,
                         effect2,
@@ -576,7 +841,7 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
             loop_start: envelope.loop_start,
             loop_end: envelope.loop_end,
 
-            vib_amount: envelope.vib_amount.try_change_base().unwrap(),
+            vib_amount: envelope.vib_amount,
             vib_waveform: envelope.vib_waveform,
             vib_speed: envelope.vib_speed,
         })
@@ -585,7 +850,7 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
     let frames_per_tick = bpm_to_frames_per_tick(module.default_bpm as u32);
     let ticks_per_step = module.default_tempo;
 
-    agb_tracker_interop::Track {
+    let track = agb_tracker_interop::Track {
         samples: samples.into(),
         pattern_data: pattern_data.into(),
         patterns: patterns.into(),
@@ -596,7 +861,100 @@ pub fn parse_module(module: &Module) -> agb_tracker_interop::Track {
         frames_per_tick,
         ticks_per_step: ticks_per_step.into(),
         repeat: module.restart_position,
+    };
+
+    Ok((track, warnings))
+}
+
+/// Removes any samples which are never played by any pattern in the track, along with any envelopes
+/// which are no longer used by the remaining samples. The sample and envelope indices in the rest
+/// of the track are updated to match.
+///
+/// Don't use this if you intend to play samples from the track which aren't referenced by its patterns.
+pub fn strip_unused_samples(track: &mut agb_tracker_interop::Track) {
+    let mut is_sample_used = vec![false; track.samples.len()];
+    for slot in track.pattern_data.iter() {
+        if slot.sample != 0 {
+            is_sample_used[slot.sample as usize - 1] = true;
+        }
+    }
+
+    let mut is_envelope_used = vec![false; track.envelopes.len()];
+    for (sample, _) in track
+        .samples
+        .iter()
+        .zip(&is_sample_used)
+        .filter(|(_, &is_used)| is_used)
+    {
+        if let Some(envelope_id) = sample.volume_envelope {
+            is_envelope_used[envelope_id] = true;
+        }
+    }
+
+    let new_sample_indices = new_indices(&is_sample_used);
+    let new_envelope_indices = new_indices(&is_envelope_used);
+
+    for slot in track.pattern_data.to_mut() {
+        if slot.sample != 0 {
+            slot.sample = new_sample_indices[slot.sample as usize - 1]
+                .map(|index| index as u16 + 1)
+                .expect("Used sample should have been kept");
+        }
+    }
+
+    let samples = track
+        .samples
+        .iter()
+        .zip(&is_sample_used)
+        .filter(|(_, &is_used)| is_used)
+        .map(|(sample, _)| agb_tracker_interop::Sample {
+            volume_envelope: sample
+                .volume_envelope
+                .and_then(|envelope_id| new_envelope_indices[envelope_id]),
+            ..sample.clone()
+        })
+        .collect::<Vec<_>>();
+
+    let envelopes = track
+        .envelopes
+        .iter()
+        .zip(&is_envelope_used)
+        .filter(|(_, &is_used)| is_used)
+        .map(|(envelope, _)| envelope.clone())
+        .collect::<Vec<_>>();
+
+    track.samples = samples.into();
+    track.envelopes = envelopes.into();
+}
+
+fn new_indices(is_used: &[bool]) -> Vec<Option<usize>> {
+    let mut next_index = 0;
+
+    is_used
+        .iter()
+        .map(|&is_used| {
+            is_used.then(|| {
+                next_index += 1;
+                next_index - 1
+            })
+        })
+        .collect()
+}
+
+/// The mixer can only play loops forwards, so a ping pong loop is played by appending the loop
+/// backwards to the end of the sample. The ends of the loop aren't repeated so playing forwards
+/// from the end of the sample back to `loop_start` gives the same result as a ping pong loop.
+fn unroll_ping_pong_loop(sample: &mut Vec<u8>, loop_start: usize) {
+    if loop_start + 2 >= sample.len() {
+        return;
     }
+
+    let reversed = sample[loop_start + 1..sample.len() - 1]
+        .iter()
+        .rev()
+        .copied()
+        .collect::<Vec<_>>();
+    sample.extend(reversed);
 }
 
 fn bpm_to_frames_per_tick(bpm: u32) -> Num<u32, 8> {
@@ -609,39 +967,44 @@ fn note_to_speed(
     fine_tune: f64,
     relative_note: i8,
     frequency_type: FrequencyType,
-) -> Num<u32, 12> {
+) -> Option<Num<u32, 12>> {
     let frequency = match frequency_type {
         FrequencyType::LinearFrequencies => {
             note_to_frequency_linear(note, fine_tune, relative_note)
         }
-        FrequencyType::AmigaFrequencies => note_to_frequency_amiga(note, fine_tune, relative_note),
+        FrequencyType::AmigaFrequencies => note_to_frequency_amiga(note, fine_tune, relative_note)?,
     };
 
-    let gba_audio_frequency = 32768f64;
+    let speed = frequency / agb_tracker_interop::TRACK_FREQUENCY as f64;
+    Some(Num::from_f64(speed))
+}
 
-    let speed = frequency / gba_audio_frequency;
-    Num::from_f64(speed)
+/// The speed of C4 with the given fine tune, which effect amounts are measured relative to
+fn fine_tuned_c4_speed(fine_tune: f64, frequency_type: FrequencyType) -> Num<u32, 12> {
+    note_to_speed(Note::C4, fine_tune, 0, frequency_type).expect("C4 is always a valid note")
 }
 
 fn note_to_frequency_linear(note: Note, fine_tune: f64, relative_note: i8) -> f64 {
     let real_note = (note as usize as f64) + (relative_note as f64) - 1.0; // notes are 1 indexed but below is 0 indexed
     let period = 10.0 * 12.0 * 16.0 * 4.0 - real_note * 16.0 * 4.0 - fine_tune / 2.0;
-    8363.0 * 2.0f64.powf((6.0 * 12.0 * 16.0 * 4.0 - period) / (12.0 * 16.0 * 4.0))
+    8363.0
+        * libm::pow(
+            2.0,
+            (6.0 * 12.0 * 16.0 * 4.0 - period) / (12.0 * 16.0 * 4.0),
+        )
 }
 
-fn note_to_frequency_amiga(note: Note, fine_tune: f64, relative_note: i8) -> f64 {
-    let note = (note as usize)
-        .checked_add_signed(relative_note as isize)
-        .expect("Note gone negative");
+fn note_to_frequency_amiga(note: Note, fine_tune: f64, relative_note: i8) -> Option<f64> {
+    let note = (note as usize).checked_add_signed(relative_note as isize)?;
     let pos = ((note % 12) * 8 + (fine_tune / 16.0) as usize).min(AMIGA_FREQUENCIES.len() - 2);
-    let frac = (fine_tune / 16.0) - (fine_tune / 16.0).floor();
+    let frac = (fine_tune / 16.0) - libm::floor(fine_tune / 16.0);
 
     let period = ((AMIGA_FREQUENCIES[pos] as f64 * (1.0 - frac))
         + AMIGA_FREQUENCIES[pos + 1] as f64 * frac)
         * 32.0 // docs say 16 here, but for some reason I need 32 :/
         / (1 << ((note as i64) / 12)) as f64;
 
-    8363.0 * 1712.0 / period
+    Some(8363.0 * 1712.0 / period)
 }
 
 static AMIGA_FREQUENCIES: &[u32] = &[
@@ -662,19 +1025,31 @@ struct EnvelopeData {
 
     vib_waveform: Waveform,
     vib_speed: u8,
-    vib_amount: Num<i32, 12>,
+    vib_amount: Num<i16, 12>,
 }
 
 impl EnvelopeData {
     fn new(
         e: &xmrs::envelope::Envelope,
         instrument: &xmrs::instr_default::InstrDefault,
+        instrument_index: usize,
         frequency_type: FrequencyType,
         bpm: u32,
-    ) -> Self {
+    ) -> Result<Self, ParseError> {
+        let invalid_envelope = || ParseError::InvalidEnvelope {
+            instrument: instrument_index,
+        };
+        let point_frame = |index: usize| {
+            e.point
+                .get(index)
+                .map(|point| Self::envelope_frame_to_gba_frame(point.frame, bpm))
+                .ok_or_else(invalid_envelope)
+        };
+
         let mut amounts = vec![];
 
-        for frame in 0..=(Self::envelope_frame_to_gba_frame(e.point.last().unwrap().frame, bpm)) {
+        let last_point = e.point.last().ok_or_else(invalid_envelope)?;
+        for frame in 0..=(Self::envelope_frame_to_gba_frame(last_point.frame, bpm)) {
             let xm_frame = Self::gba_frame_to_envelope_frame(frame, bpm);
             let index = e
                 .point
@@ -683,7 +1058,8 @@ impl EnvelopeData {
                 .unwrap_or(0);
 
             let first_point = &e.point[index];
-            let second_point = &e.point[index + 1];
+            // a single point envelope stays at that point's value
+            let second_point = e.point.get(index + 1).unwrap_or(first_point);
 
             let amount = EnvelopePoint::lerp(first_point, second_point, xm_frame);
             let amount = Num::from_f32(amount);
@@ -692,23 +1068,14 @@ impl EnvelopeData {
         }
 
         let sustain = if e.sustain_enabled {
-            Some(Self::envelope_frame_to_gba_frame(
-                e.point[e.sustain_point].frame,
-                bpm,
-            ))
+            Some(point_frame(e.sustain_point)?)
         } else {
             None
         };
         let (loop_start, loop_end) = if e.loop_enabled {
             (
-                Some(Self::envelope_frame_to_gba_frame(
-                    e.point[e.loop_start_point].frame,
-                    bpm,
-                )),
-                Some(Self::envelope_frame_to_gba_frame(
-                    e.point[e.loop_end_point].frame,
-                    bpm,
-                )),
+                Some(point_frame(e.loop_start_point)?),
+                Some(point_frame(e.loop_end_point)?),
             )
         } else {
             (None, None)
@@ -724,11 +1091,13 @@ impl EnvelopeData {
         let vib_speed = (instrument.vibrato.speed * 64.0) as u8;
         let vib_depth = instrument.vibrato.depth * 8.0;
 
-        let c4_speed = note_to_speed(Note::C4, 0.0, 0, frequency_type);
-        let mut vib_amount =
-            (note_to_speed(Note::C4, vib_depth.into(), 0, frequency_type) / c4_speed - 1)
+        let c4_speed = fine_tuned_c4_speed(0.0, frequency_type);
+        let mut vib_amount: Num<i16, 12> =
+            (fine_tuned_c4_speed(vib_depth.into(), frequency_type) / c4_speed - 1)
                 .try_change_base()
-                .unwrap();
+                .ok_or(ParseError::VibratoOutOfRange {
+                    instrument: instrument_index,
+                })?;
 
         if matches!(
             instrument.vibrato.waveform,
@@ -737,7 +1106,7 @@ impl EnvelopeData {
             vib_amount = -vib_amount;
         }
 
-        EnvelopeData {
+        Ok(EnvelopeData {
             amounts,
             sustain,
             loop_start,
@@ -746,7 +1115,7 @@ impl EnvelopeData {
             vib_waveform,
             vib_speed,
             vib_amount,
-        }
+        })
     }
 
     fn envelope_frame_to_gba_frame(envelope_frame: usize, bpm: u32) -> usize {
@@ -759,3 +1128,475 @@ impl EnvelopeData {
         (gba_frame as u32 * bpm / 250) as usize
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::borrow::Cow;
+
+    use agb_tracker_interop::{Envelope, Pattern, PatternSlot, Sample, Track};
+
+    use super::*;
+
+    fn sample(value: u8, volume_envelope: Option<usize>) -> Sample {
+        Sample {
+            data: vec![value; 4].into(),
+            should_loop: value > 2,
+            restart_point: value as u32,
+            volume: 1.into(),
+            volume_envelope,
+            fadeout: 0.into(),
+        }
+    }
+
+    fn envelope(value: i16) -> Envelope {
+        Envelope {
+            amount: vec![Num::new(value)].into(),
+            sustain: None,
+            loop_start: None,
+            loop_end: None,
+
+            vib_waveform: Waveform::Sine,
+            vib_amount: 0.into(),
+            vib_speed: 0,
+        }
+    }
+
+    fn slot(sample: u16) -> PatternSlot {
+        PatternSlot {
+            sample,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strip_unused_samples_removes_samples_not_in_any_pattern() {
+        let mut track = Track {
+            samples: Cow::Owned(vec![
+                sample(1, Some(0)),
+                sample(2, Some(1)),
+                sample(3, None),
+                sample(4, Some(2)),
+            ]),
+            envelopes: Cow::Owned(vec![envelope(1), envelope(2), envelope(3)]),
+            pattern_data: Cow::Owned(vec![slot(4), slot(0), slot(0), slot(1), slot(4), slot(0)]),
+            patterns: Cow::Owned(vec![Pattern {
+                length: 3,
+                start_position: 0,
+            }]),
+            patterns_to_play: Cow::Owned(vec![0]),
+
+            num_channels: 2,
+            frames_per_tick: 1.into(),
+            ticks_per_step: 1,
+            repeat: 0,
+        };
+
+        strip_unused_samples(&mut track);
+
+        let sample_values = track
+            .samples
+            .iter()
+            .map(|sample| sample.data[0])
+            .collect::<Vec<_>>();
+        assert_eq!(sample_values, [1, 4]);
+
+        let played_samples = track
+            .pattern_data
+            .iter()
+            .map(|slot| slot.sample)
+            .collect::<Vec<_>>();
+        assert_eq!(played_samples, [2, 0, 0, 1, 2, 0]);
+
+        assert!(!track.samples[0].should_loop);
+        assert!(track.samples[1].should_loop);
+        assert_eq!(track.samples[1].restart_point, 4);
+
+        assert_eq!(track.envelopes.len(), 2);
+        assert_eq!(track.samples[0].volume_envelope, Some(0));
+        assert_eq!(track.samples[1].volume_envelope, Some(1));
+        assert_eq!(track.envelopes[1].amount[0], Num::new(3));
+    }
+
+    #[test]
+    fn ping_pong_loops_play_back_and_forth() {
+        let mut data = vec![0, 1, 2, 3, 4, 5];
+        unroll_ping_pong_loop(&mut data, 2);
+
+        // play the sample forwards, looping back to the restart point at the end
+        let mut pos = 0;
+        let played = core::iter::from_fn(|| {
+            let value = data[pos];
+            pos += 1;
+            if pos == data.len() {
+                pos = 2;
+            }
+            Some(value)
+        })
+        .take(16)
+        .collect::<Vec<_>>();
+
+        assert_eq!(played, [0, 1, 2, 3, 4, 5, 4, 3, 2, 3, 4, 5, 4, 3, 2, 3]);
+    }
+
+    #[test]
+    fn ping_pong_loops_which_are_too_short_are_unchanged() {
+        let mut data = vec![0, 1, 2];
+        unroll_ping_pong_loop(&mut data, 1);
+        assert_eq!(data, [0, 1, 2]);
+    }
+
+    /// A module with a single channel playing the given (effect type, effect parameter) pair on
+    /// each row.
+    fn module_with_effects(module_effects: &[(u8, u8)], is_s3m: bool) -> Module {
+        let mut pattern = module_effects
+            .iter()
+            .map(|&(effect_type, effect_parameter)| {
+                vec![xmrs::prelude::PatternSlot {
+                    note: Note::None,
+                    instrument: 0,
+                    volume: 0,
+                    effect_type,
+                    effect_parameter,
+                }]
+            })
+            .collect();
+
+        if is_s3m {
+            xmrs::s3m::s3m_effect::S3mEffect::update_pattern(&mut pattern);
+        }
+
+        Module {
+            pattern_order: vec![0],
+            pattern: vec![pattern],
+            ..Default::default()
+        }
+    }
+
+    /// A module with a single instrument containing the given sample, which plays `note` with the
+    /// given effect on the first row.
+    fn module_with_sample(
+        data: SampleDataType,
+        relative_note: i8,
+        note: Note,
+        (effect_type, effect_parameter): (u8, u8),
+    ) -> Module {
+        let instrument = InstrDefault {
+            sample: vec![xmrs::sample::Sample {
+                name: String::new(),
+                loop_start: 0,
+                loop_length: 0,
+                volume: 1.0,
+                finetune: 0.0,
+                flags: LoopType::No,
+                panning: 0.5,
+                relative_note,
+                data,
+            }],
+            ..Default::default()
+        };
+
+        Module {
+            instrument: vec![xmrs::instrument::Instrument {
+                instr_type: InstrumentType::Default(instrument),
+                ..Default::default()
+            }],
+            pattern_order: vec![0],
+            pattern: vec![vec![vec![xmrs::prelude::PatternSlot {
+                note,
+                instrument: 1,
+                volume: 0,
+                effect_type,
+                effect_parameter,
+            }]]],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stereo_samples_are_an_error() {
+        let module = module_with_sample(SampleDataType::Stereo8(vec![0; 8]), 0, Note::C4, (0, 0));
+
+        assert_eq!(
+            parse_module(&module).err(),
+            Some(ParseError::StereoSample {
+                instrument: 0,
+                sample: 0
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_envelopes_are_an_error() {
+        let module_with_envelope = |point: Vec<EnvelopePoint>, sustain_point: usize| {
+            let mut module =
+                module_with_sample(SampleDataType::Mono8(vec![0; 8]), 0, Note::C4, (0, 0));
+            if let InstrumentType::Default(instrument) = &mut module.instrument[0].instr_type {
+                instrument.volume_envelope = xmrs::envelope::Envelope {
+                    enabled: true,
+                    point,
+                    sustain_enabled: true,
+                    sustain_point,
+                    ..Default::default()
+                };
+            }
+            module
+        };
+        let single_point = || {
+            vec![EnvelopePoint {
+                frame: 0,
+                value: 1.0,
+            }]
+        };
+
+        assert_eq!(
+            parse_module(&module_with_envelope(vec![], 0)).err(),
+            Some(ParseError::InvalidEnvelope { instrument: 0 })
+        );
+        assert_eq!(
+            parse_module(&module_with_envelope(single_point(), 3)).err(),
+            Some(ParseError::InvalidEnvelope { instrument: 0 })
+        );
+
+        let track = parse_module(&module_with_envelope(single_point(), 0)).unwrap();
+        assert_eq!(track.envelopes[0].amount[..], [Num::new(1)]);
+    }
+
+    #[test]
+    fn notes_which_go_negative_are_an_error() {
+        let mut module =
+            module_with_sample(SampleDataType::Mono8(vec![0; 8]), -2, Note::C0, (0, 0));
+        module.frequency_type = FrequencyType::AmigaFrequencies;
+
+        assert_eq!(
+            parse_module(&module).err(),
+            Some(ParseError::NoteOutOfRange {
+                pattern: 0,
+                row: 0,
+                channel: 0
+            })
+        );
+    }
+
+    #[test]
+    fn effects_which_are_too_large_are_an_error() {
+        // the note itself can be played, but an arpeggio 15 semitones above it is too fast
+        let module = module_with_sample(
+            SampleDataType::Mono8(vec![0; 8]),
+            110,
+            Note::C4,
+            (0x0, 0x0F),
+        );
+
+        assert_eq!(
+            parse_module(&module).err(),
+            Some(ParseError::EffectOutOfRange {
+                effect: 0x0,
+                parameter: 0x0F,
+                pattern: 0,
+                row: 0,
+                channel: 0,
+            })
+        );
+
+        let module = module_with_sample(
+            SampleDataType::Mono8(vec![0; 8]),
+            127,
+            Note::C4,
+            (0x0, 0xF0),
+        );
+        // the arpeggio goes past the highest relative note
+        assert_eq!(
+            parse_module(&module).err(),
+            Some(ParseError::NoteOutOfRange {
+                pattern: 0,
+                row: 0,
+                channel: 0
+            })
+        );
+    }
+
+    #[test]
+    fn modules_with_no_bpm_are_an_error() {
+        let module = Module {
+            default_bpm: 0,
+            ..module_with_effects(&[], false)
+        };
+
+        assert_eq!(parse_module(&module).err(), Some(ParseError::InvalidBpm));
+    }
+
+    #[test]
+    fn invalid_xm_files_are_an_error() {
+        assert!(matches!(
+            parse_xm(&[0; 16]),
+            Err(ParseError::InvalidFile(_))
+        ));
+    }
+
+    /// Parses a single channel of (effect type, effect parameter) pairs as
+    /// the effect produced for each row.
+    fn effects(module_effects: &[(u8, u8)], is_s3m: bool) -> Vec<PatternEffect> {
+        parse_module(&module_with_effects(module_effects, is_s3m))
+            .unwrap()
+            .pattern_data
+            .iter()
+            .map(|slot| slot.effect2.clone())
+            .collect()
+    }
+
+    #[test]
+    fn unsupported_effects_produce_warnings() {
+        // 7xy is tremolo and EFx is invert loop, neither of which are supported
+        let module = module_with_effects(&[(0xC, 0x20), (0x7, 0x34), (0xE, 0xF1)], false);
+
+        let (track, warnings) = parse_module_with_warnings(&module).unwrap();
+
+        assert_eq!(
+            warnings,
+            [
+                ParseWarning {
+                    effect: 0x7,
+                    parameter: 0x34,
+                    pattern: 0,
+                    row: 1,
+                    channel: 0,
+                },
+                ParseWarning {
+                    effect: 0xE,
+                    parameter: 0xF1,
+                    pattern: 0,
+                    row: 2,
+                    channel: 0,
+                },
+            ]
+        );
+        assert_eq!(track.pattern_data[1].effect2, PatternEffect::None);
+        assert_eq!(
+            warnings[0].to_string(),
+            "Unsupported effect 734 in pattern 0 row 1 channel 0"
+        );
+    }
+
+    #[test]
+    fn s3m_fine_volume_slides_match_xm() {
+        // S3M DxF / DFx are fine volume slides up / down, the same as XM EAx / EBx
+        assert_eq!(
+            effects(&[(0x4, 0x3F), (0x4, 0xF5)], true),
+            effects(&[(0xE, 0xA3), (0xE, 0xB5)], false),
+        );
+    }
+
+    #[test]
+    fn s3m_fine_portamento_matches_xm() {
+        // S3M FFx / EFx are fine portamento up / down, the same as XM E1x / E2x
+        assert_eq!(
+            effects(&[(0x6, 0xF3), (0x5, 0xF4)], true),
+            effects(&[(0xE, 0x13), (0xE, 0x24)], false),
+        );
+    }
+
+    #[test]
+    fn fine_portamento_moves_as_far_as_a_single_tick_of_portamento() {
+        let [PatternEffect::Portamento(up), PatternEffect::Portamento(down), PatternEffect::FinePortamento(fine_up), PatternEffect::FinePortamento(fine_down)] =
+            effects(&[(0x1, 0x3), (0x2, 0x3), (0xE, 0x13), (0xE, 0x23)], false)[..]
+        else {
+            panic!("expected portamento effects");
+        };
+
+        assert_eq!(fine_up, up);
+        assert_eq!(fine_down, down);
+    }
+
+    #[test]
+    fn vibrato_waveform_effects_set_the_waveform() {
+        let module = module_with_effects(
+            &[
+                (0xE, 0x40),
+                (0xE, 0x41),
+                (0xE, 0x42),
+                (0xE, 0x46),
+                (0xE, 0x43),
+            ],
+            false,
+        );
+
+        let (track, warnings) = parse_module_with_warnings(&module).unwrap();
+        let effects = track
+            .pattern_data
+            .iter()
+            .map(|slot| slot.effect2.clone())
+            .collect::<Vec<_>>();
+
+        // random waveforms aren't supported, so the previous waveform is kept
+        assert_eq!(
+            effects,
+            [
+                PatternEffect::SetVibratoWaveform(Waveform::Sine),
+                PatternEffect::SetVibratoWaveform(Waveform::Saw),
+                PatternEffect::SetVibratoWaveform(Waveform::Square),
+                PatternEffect::SetVibratoWaveform(Waveform::Square),
+                PatternEffect::None,
+            ]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].parameter, 0x43);
+    }
+
+    #[test]
+    fn vibrato_waveform_and_vibrato_can_be_in_different_patterns() {
+        // the waveform is set in pattern 1, which is played before the vibrato in pattern 0
+        let vibrato = module_with_effects(&[(0x4, 0x24)], false);
+        let waveform = module_with_effects(&[(0xE, 0x42)], false);
+        let module = Module {
+            pattern_order: vec![1, 0],
+            pattern: vec![vibrato.pattern[0].clone(), waveform.pattern[0].clone()],
+            ..Default::default()
+        };
+
+        let track = parse_module(&module).unwrap();
+
+        assert_eq!(&track.patterns_to_play[..], [1, 0]);
+        let effect_in_pattern =
+            |pattern: usize| &track.pattern_data[track.patterns[pattern].start_position].effect2;
+        assert!(matches!(effect_in_pattern(0), PatternEffect::Vibrato(_, 2)));
+        assert_eq!(
+            effect_in_pattern(1),
+            &PatternEffect::SetVibratoWaveform(Waveform::Square)
+        );
+    }
+
+    #[test]
+    fn unsupported_extra_fine_effects_produce_warnings() {
+        let module = module_with_effects(&[(0x21, 0x13), (0x21, 0x31)], false);
+
+        let (track, warnings) = parse_module_with_warnings(&module).unwrap();
+
+        assert!(matches!(
+            track.pattern_data[0].effect2,
+            PatternEffect::FinePortamento(_)
+        ));
+        assert_eq!(track.pattern_data[1].effect2, PatternEffect::None);
+        assert_eq!(
+            warnings,
+            [ParseWarning {
+                effect: 0x21,
+                parameter: 0x31,
+                pattern: 0,
+                row: 1,
+                channel: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn s3m_extra_fine_portamento_is_a_quarter_of_fine_portamento() {
+        let [PatternEffect::FinePortamento(extra_fine_up), PatternEffect::FinePortamento(extra_fine_down), PatternEffect::FinePortamento(fine_up), PatternEffect::FinePortamento(fine_down)] =
+            effects(&[(0x6, 0xE8), (0x5, 0xE8), (0x6, 0xF2), (0x5, 0xF2)], true)[..]
+        else {
+            panic!("expected fine portamento effects");
+        };
+
+        assert_eq!(extra_fine_up, fine_up);
+        assert_eq!(extra_fine_down, fine_down);
+    }
+}
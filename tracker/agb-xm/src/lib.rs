@@ -1,10 +1,13 @@
 use std::{error::Error, fs, path::Path};
 
-use agb_xm_core::parse_module;
+use agb_xm_core::{parse_module, strip_unused_samples};
 use proc_macro::TokenStream;
 use proc_macro_error::{abort, proc_macro_error};
 use quote::quote;
-use syn::LitStr;
+use syn::{
+    parse::{Parse, ParseStream},
+    LitStr, Token,
+};
 use xmrs::{
     amiga::amiga_module::AmigaModule, module::Module, s3m::s3m_module::S3mModule,
     xm::xmmodule::XmModule,
@@ -28,11 +31,48 @@ pub fn include_mod(args: TokenStream) -> TokenStream {
     agb_xm_core(args, |content| Ok(AmigaModule::load(content)?.to_module()))
 }
 
+struct IncludeTrackInput {
+    filename: LitStr,
+    keep_unused_samples: bool,
+}
+
+impl Parse for IncludeTrackInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let filename: LitStr = input.parse()?;
+
+        let mut keep_unused_samples = false;
+        if input.peek(Token![,]) {
+            let _: Token![,] = input.parse()?;
+
+            if !input.is_empty() {
+                let option: syn::Ident = input.parse()?;
+
+                if option == "keep_unused_samples" {
+                    keep_unused_samples = true;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        option,
+                        "Must either be the literal keep_unused_samples or missing",
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            filename,
+            keep_unused_samples,
+        })
+    }
+}
+
 fn agb_xm_core(
     args: TokenStream,
     load_module: impl Fn(&[u8]) -> Result<Module, Box<dyn Error>>,
 ) -> TokenStream {
-    let input = match syn::parse::<LitStr>(args) {
+    let IncludeTrackInput {
+        filename: input,
+        keep_unused_samples,
+    } = match syn::parse::<IncludeTrackInput>(args) {
         Ok(input) => input,
         Err(err) => return err.to_compile_error().into(),
     };
@@ -54,7 +94,13 @@ fn agb_xm_core(
         Err(e) => abort!(input, e),
     };
 
-    let parsed = parse_module(&module);
+    let mut parsed = match parse_module(&module) {
+        Ok(track) => track,
+        Err(e) => abort!(input, e),
+    };
+    if !keep_unused_samples {
+        strip_unused_samples(&mut parsed);
+    }
 
     quote! {
         {
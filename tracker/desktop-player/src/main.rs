@@ -18,7 +18,7 @@ fn main() -> anyhow::Result<()> {
     let file_path = &args[1];
     let module = load_module_from_file(Path::new(file_path))?;
 
-    let track = agb_xm_core::parse_module(&module);
+    let track = agb_xm_core::parse_module(&module)?;
 
     let mut mixer = Mixer::new();
     let mut tracker = agb_tracker::TrackerInner::new(&track);
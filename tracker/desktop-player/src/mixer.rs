@@ -128,7 +128,7 @@ impl SoundChannel {
 
 pub struct SoundChannelId(usize, Wrapping<usize>);
 
-impl agb_tracker::SoundChannel for SoundChannel {
+impl agb_tracker::SoundChannel<'_> for SoundChannel {
     fn new(data: &Cow<'static, [u8]>) -> Self {
         Self::new(data.clone())
     }
@@ -178,7 +178,7 @@ impl agb_tracker::SoundChannel for SoundChannel {
     }
 }
 
-impl agb_tracker::Mixer for Mixer {
+impl agb_tracker::Mixer<'_> for Mixer {
     type ChannelId = SoundChannelId;
 
     type SoundChannel = SoundChannel;